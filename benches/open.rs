@@ -0,0 +1,81 @@
+//! Open request benchmarks.
+//!
+//! Times opening folders and files against many open windows on the mock runtime.
+//! Run with `cargo bench`.
+
+use std::{
+  env::temp_dir,
+  path::PathBuf,
+  time::{Duration, Instant},
+};
+
+use tauri::{
+  test::{mock_builder, mock_context, noop_assets, MockRuntime},
+  App
+};
+
+use tauri_plugin_windows::{OpenConfiguration, TauriWindows, WindowOpenable, WindowsAPI};
+
+const ITERATIONS: u32 = 10;
+
+fn app() -> App<MockRuntime> {
+  mock_builder()
+    .plugin(TauriWindows::default().in_memory())
+    .build(mock_context(noop_assets()))
+    .unwrap()
+}
+
+fn folder(index: usize) -> PathBuf {
+  temp_dir().join("tauri-plugin-windows-bench").join(format!("folder-{}", index))
+}
+
+fn open(app: &App<MockRuntime>, openables: Vec<WindowOpenable>, force_new_window: bool) {
+  WindowsAPI::open_window(app, OpenConfiguration {
+    uris_to_open: Some(openables),
+    force_new_window,
+    ..Default::default()
+  }).unwrap();
+}
+
+fn folders(indices: impl Iterator<Item = usize>) -> Vec<WindowOpenable> {
+  indices.map(|index| WindowOpenable::new(None, Some(folder(index)))).collect()
+}
+
+fn files(indices: impl Iterator<Item = usize>) -> Vec<WindowOpenable> {
+  indices.map(|index| WindowOpenable::new(Some(folder(index).join("src").join("main.rs")), None)).collect()
+}
+
+/// Time a request of `paths` paths against `windows` open windows, averaged over fresh apps.
+fn bench(name: &str, windows: usize, paths: usize, request: impl Fn(&App<MockRuntime>, usize, usize)) {
+  let mut total = Duration::ZERO;
+
+  for _iteration in 0..ITERATIONS {
+    let app = app();
+
+    open(&app, folders(0..windows), true);
+
+    let start = Instant::now();
+    request(&app, windows, paths);
+    total += start.elapsed();
+  }
+
+  println!("{:<16} {:>4} windows {:>4} paths {:>12.3?}", name, windows, paths, total / ITERATIONS);
+}
+
+fn main() {
+  for windows in [10, 100, 500] {
+    for paths in [1, 10, 100] {
+      bench("new folders", windows, paths, |app, windows, paths| {
+        open(app, folders(windows..windows + paths), true)
+      });
+
+      bench("open folders", windows, paths, |app, windows, paths| {
+        open(app, folders((0..paths).map(|index| index % windows)), false)
+      });
+
+      bench("files", windows, paths, |app, windows, paths| {
+        open(app, files((0..paths).map(|index| index % windows)), false)
+      });
+    }
+  }
+}
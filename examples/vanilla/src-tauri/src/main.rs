@@ -7,10 +7,8 @@ use tauri_plugin_windows::{
   TauriWindows,
   windows::{
     Menu,
-    OpenConfiguration,
-    OpenContext,
-    WindowOpenable,
-    WindowsService,
+    OpenBehavior,
+    WindowsAPI,
   }
 };
 
@@ -52,57 +50,30 @@ fn main() {
       match event.menu_item_id() {
         "open_file" => {
           FileDialogBuilder::new().pick_files(move |file_paths| {
-            // do something with the optional file paths here
             // the file paths is `None` if the user closed the dialog
             if let Some(paths_to_open) = file_paths {
-              // pub uris_to_open: Option<Vec<WindowOpenable>>
-              // pub struct WindowOpenable {
-              //   pub folder_uri: Option<PathBuf>,
-              //   pub file_uri: Option<PathBuf>,
-              // }
-              let uris_to_open = paths_to_open.iter()
-              .map(|path| WindowOpenable { file_uri: Some(path.to_path_buf()), ..Default::default() })
-              .collect::<Vec<WindowOpenable>>();
-
-              match WindowsService::open_window(
+              if let Err(e) = WindowsAPI::open_files(
                 &event.window().app_handle(),
-                OpenConfiguration {
-                  context: OpenContext::Dialog,
-                  uris_to_open: Some(uris_to_open),
-                  prefer_new_window: true,
-                  ..Default::default()
-                }) {
-                Ok(_res) => {}
-                Err(e) => {
-                  eprintln!("Error: {:?}", e);
-                },
-              };              
+                &paths_to_open,
+                OpenBehavior::Default,
+              ) {
+                eprintln!("Error: {:?}", e);
+              }
             }
           })
         }
         "open_folder" => {
-          FileDialogBuilder::new().pick_folders(move |folder_paths| {
-            // do something with the optional folder path here
+          FileDialogBuilder::new().pick_folder(move |folder_path| {
             // the folder path is `None` if the user closed the dialog
-            if let Some(paths_to_open) = folder_paths {
-              let uris_to_open = paths_to_open.iter()
-              .map(|path| WindowOpenable { folder_uri: Some(path.to_path_buf()), ..Default::default() })
-              .collect::<Vec<WindowOpenable>>();
-
-              match WindowsService::open_window(
+            if let Some(path_to_open) = folder_path {
+              if let Err(e) = WindowsAPI::open_folder(
                 &event.window().app_handle(),
-                OpenConfiguration {
-                  context: OpenContext::Dialog,
-                  uris_to_open: Some(uris_to_open),
-                  prefer_new_window: true,
-                  ..Default::default()
-                }) {
-                Ok(_res) => {}
-                Err(e) => {
-                  eprintln!("Error: {:?}", e);
-                },
-              };              
-            }            
+                &path_to_open,
+                OpenBehavior::Default,
+              ) {
+                eprintln!("Error: {:?}", e);
+              }
+            }
           })
         }
         _ => {}
@@ -4,9 +4,10 @@
 
 use std::{
     io::Error as IoError,
-    result::Result as StdResult,
+    path::PathBuf,
 };
 
+use serde::Serialize;
 use serde_json::Error as SerdeJsonError;
 
 use tauri::{
@@ -21,6 +22,98 @@ pub enum Error {
   /// Cached window state not found with label
   #[error("a window state with label `{0}` doesn't exist")]
   WindowStateWithLabelNotFound(String),
+  /// Open window not found with label, its state may still be cached
+  #[error("a window with label `{0}` isn't open")]
+  WindowNotFound(String),
+  /// Label supplied for a window has invalid characters, is too long or is taken
+  #[error("invalid window label `{0}`: {1}")]
+  InvalidLabel(String, String),
+  /// Path outside the directories the plugin may touch
+  #[error("path `{}` is not allowed", .0.display())]
+  PathNotAllowed(PathBuf),
+  /// External url not in the allowlist
+  #[error("url `{0}` is not allowed")]
+  UrlNotAllowed(String),
+  /// Deep link is not an open link or has nothing to open
+  #[error("deep link `{0}` can't be opened")]
+  InvalidDeepLink(String),
+  /// Saved window layout not found with name
+  #[error("a window layout with name `{0}` doesn't exist")]
+  LayoutNotFound(String),
+  /// Folder missing or not a directory
+  #[error("folder `{0}` doesn't exist")]
+  FolderNotFound(String),
+  /// File of an openable is not inside its folder
+  #[error("file `{0}` is not inside folder `{1}`")]
+  FileOutsideFolder(String, String),
+  /// Reuse forced without an open window
+  #[error("no window to reuse is open")]
+  NoWindowToReuse,
+  /// File or folder isn't open in the window
+  #[error("`{0}` isn't open in window `{1}`")]
+  ResourceNotInWindow(String, String),
+  /// Moving a resource between windows would leave their states inconsistent
+  #[error("resource can't be moved: {0}")]
+  MoveRejected(String),
+  /// No open window in group
+  #[error("a window group with name `{0}` doesn't exist")]
+  GroupNotFound(String),
+  /// Imported state snapshot is invalid
+  #[error("invalid state snapshot: {0}")]
+  InvalidSnapshot(String),
+  /// Subsystem compiled out with its cargo feature
+  #[error("the `{0}` feature is disabled")]
+  FeatureDisabled(String),
+  /// Storage file can't be parsed, the cache starts empty
+  #[error("storage file `{}` is corrupted: {source}", path.display())]
+  StorageCorrupted {
+    path: PathBuf,
+    #[source]
+    source: SerdeJsonError,
+  },
+  /// Moving files of an earlier version failed
+  #[error("migrating `{}` to `{}` failed: {source}", from.display(), to.display())]
+  MigrationFailed {
+    from: PathBuf,
+    to: PathBuf,
+    #[source]
+    source: IoError,
+  },
+  /// Encrypting or decrypting a storage file failed
+  #[error("storage encryption failed: {0}")]
+  Encryption(String),
+  /// Invoking window lacks the permission for the command
+  #[error("window `{0}` doesn't have the `{1}` permission")]
+  PermissionDenied(String, String),
+  /// Invoking window acted for another window in a command limited to its own window
+  #[error("window `{0}` can't act for window `{1}`")]
+  NotOwnWindow(String, String),
+  /// Glob pattern can't be parsed
+  #[error("invalid glob pattern `{0}`: {1}")]
+  InvalidPattern(String, String),
+  /// Open request rejected by the open filter of the app
+  #[error("open request rejected: {0}")]
+  OpenRejected(String),
+  /// Window didn't provide a valid capture of its contents
+  #[error("screenshot of window `{0}` failed: {1}")]
+  Screenshot(String, String),
+  /// Open queue worker stopped before running the request
+  #[error("the open queue stopped before the request ran")]
+  OpenQueueClosed,
+  /// Open request panicked while it ran, requests queued after it still run
+  #[error("the open request panicked")]
+  OpenPanicked,
+  /// Webview creation failed on every attempt, see `create_retry` settings
+  #[error("window `{label}` couldn't be created after {attempts} attempts: {source}")]
+  WindowCreation {
+    label: String,
+    attempts: u32,
+    #[source]
+    source: TauriError,
+  },
+  /// Native dialog requested without the `dialog` feature
+  #[error("the native dialog is unavailable, enable the `dialog` feature")]
+  DialogUnavailable,
   /// Managed state[all are rwlock] error
   #[error("{0}")]
   RwLock(String),
@@ -37,7 +130,53 @@ pub enum Error {
   SerdeJson(#[from] SerdeJsonError)
 }
 
+/// Kind of an [`Error`], serializable for the frontend.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum ErrorKind {
+  /// Window, folder or other resource doesn't exist.
+  NotFound,
+  /// Not allowed by the settings or permissions.
+  NotAllowed,
+  /// Rejected by the app, e.g. by the open filter.
+  Rejected,
+  /// Window couldn't be created or changed by the runtime.
+  Window,
+  /// Reading or writing files failed.
+  Io,
+  Other,
+}
+
 impl Error {
+  /// Get the kind of the error.
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      Error::WindowStateWithLabelNotFound(_)
+      | Error::WindowNotFound(_)
+      | Error::LayoutNotFound(_)
+      | Error::FolderNotFound(_)
+      | Error::NoWindowToReuse
+      | Error::ResourceNotInWindow(_, _)
+      | Error::GroupNotFound(_) => ErrorKind::NotFound,
+      Error::UrlNotAllowed(_)
+      | Error::PathNotAllowed(_)
+      | Error::FileOutsideFolder(_, _)
+      | Error::PermissionDenied(_, _)
+      | Error::NotOwnWindow(_, _)
+      | Error::FeatureDisabled(_) => ErrorKind::NotAllowed,
+      Error::InvalidDeepLink(_)
+      | Error::InvalidLabel(_, _)
+      | Error::MoveRejected(_)
+      | Error::OpenRejected(_) => ErrorKind::Rejected,
+      Error::Tauri(_)
+      | Error::WindowCreation { .. } => ErrorKind::Window,
+      Error::Io(_)
+      | Error::StorageCorrupted { .. }
+      | Error::MigrationFailed { .. } => ErrorKind::Io,
+      _ => ErrorKind::Other,
+    }
+  }
+
   #[allow(dead_code)]
   pub(crate) fn into_anyhow(self) -> anyhow::Error {
     anyhow::anyhow!(self.to_string())
@@ -2,6 +2,10 @@
 //!
 //! This module contains basic functions and variables for.
 
+use tauri::{Manager, Result, Runtime, Window};
+
+use crate::windows::{AddFolderPayload, OpenFilePayload};
+
 /// Event label for new window.
 /// Window specific event or Menu specific event.
 pub const WINDOW_NEW_WINDOW_EVENT: &str = "windows://new_window";
@@ -15,11 +19,15 @@ pub const WINDOW_CLOSE_WINDOW_EVENT: &str = "windows://close_window";
 pub const WINDOW_OPEN_FILE_EVENT: &str = "windows://open_file";
 
 /// Event label for open files.
-/// Global event
+/// Window specific event, or global event when broadcast.
 pub const WINDOW_OPEN_FILES_EVENT: &str = "windows://open_files";
 
+/// Event label for open files progress.
+/// Window specific event, emitted after each chunk when `open_files_batch_size` splits an open.
+pub const WINDOW_OPEN_FILES_PROGRESS_EVENT: &str = "windows://open_files_progress";
+
 /// Event label for add folder.
-/// Global event
+/// Window specific event, or global event when broadcast.
 pub const WINDOW_ADD_FOLDERS_EVENT: &str = "windows://add_folders";
 
 /// Event label for open folder.
@@ -32,4 +40,123 @@ pub const WINDOW_CLOSE_FILE_EVENT: &str = "windows://close_file";
 
 /// Event label for close folder.
 /// Window specific event or Menu specific event.
-pub const WINDOW_CLOSE_FOLDER_EVENT: &str = "windows://close_folder";
\ No newline at end of file
+pub const WINDOW_CLOSE_FOLDER_EVENT: &str = "windows://close_folder";
+/// Event label for folder changed.
+/// Window specific event, emitted by the folder watcher.
+pub const WINDOW_FOLDER_CHANGED_EVENT: &str = "windows://folder-changed";
+
+/// Event label for trust request.
+/// Window specific event, answered through the `trust_folder` command.
+pub const WINDOW_TRUST_REQUEST_EVENT: &str = "windows://trust-request";
+
+/// Event label for close requested.
+/// Window specific event, answered through the `confirm_close` command.
+pub const WINDOW_CLOSE_REQUESTED_EVENT: &str = "windows://close-requested";
+
+/// Event label for before quit.
+/// Window specific event, answered through the `acknowledge_quit` command.
+pub const WINDOW_BEFORE_QUIT_EVENT: &str = "windows://before-quit";
+
+/// Event label for quit cancelled.
+/// Global event, emitted when a window vetoed the exit.
+pub const WINDOW_QUIT_CANCELLED_EVENT: &str = "windows://quit-cancelled";
+
+/// Event label for restore available.
+/// Global event, emitted on startup when the previous session ended unexpectedly.
+pub const WINDOW_RESTORE_AVAILABLE_EVENT: &str = "windows://restore-available";
+
+/// Event label for theme changed.
+/// Window specific event, emitted by `set_theme` and on system theme changes.
+pub const WINDOW_THEME_CHANGED_EVENT: &str = "windows://theme-changed";
+
+/// Event label for window created.
+/// Window specific event, emitted when the webview of a managed window loads.
+pub const WINDOW_CREATED_EVENT: &str = "windows://window-created";
+
+/// Event label for window activated.
+/// Window specific event, emitted on focus when `click_through_inactive` can't be handled natively.
+pub const WINDOW_ACTIVATED_EVENT: &str = "windows://activated";
+
+/// Event label for recents changed.
+/// Global event, emitted with the added, removed and missing entries when recents change.
+pub const WINDOW_RECENTS_CHANGED_EVENT: &str = "windows://recents-changed";
+
+/// Event label for state changed.
+/// Global event, emitted with the labels of windows whose cached state changed.
+pub const WINDOW_STATE_CHANGED_EVENT: &str = "windows://state-changed";
+
+/// Event label for window ready.
+/// Global event, emitted when the frontend of a window declared readiness with `window_ready`.
+pub const WINDOW_READY_EVENT: &str = "windows://ready";
+
+/// Event label for file missing.
+/// Window specific event, emitted with files of the window deleted or moved outside the app.
+pub const WINDOW_FILE_MISSING_EVENT: &str = "windows://file-missing";
+
+/// Event label for singleton.
+/// Window specific event, emitted to a singleton window with the payload it was opened or activated with.
+pub const WINDOW_SINGLETON_EVENT: &str = "windows://singleton";
+
+/// Event label for screenshot request.
+/// Window specific event, the frontend answers with `acknowledge_broadcast` and a base64 PNG of its contents.
+pub const WINDOW_SCREENSHOT_REQUEST_EVENT: &str = "windows://screenshot-request";
+
+/// Event label for zoom in.
+/// Menu specific event, handled by the plugin for the window of the menu.
+pub const WINDOW_ZOOM_IN_EVENT: &str = "windows://zoom-in";
+
+/// Event label for zoom out.
+/// Menu specific event, handled by the plugin for the window of the menu.
+pub const WINDOW_ZOOM_OUT_EVENT: &str = "windows://zoom-out";
+
+/// Event label for zoom reset.
+/// Menu specific event, handled by the plugin for the window of the menu.
+pub const WINDOW_ZOOM_RESET_EVENT: &str = "windows://zoom-reset";
+
+/// Event label for toggle fullscreen.
+/// Menu specific event, handled by the plugin for the window of the menu.
+pub const WINDOW_TOGGLE_FULLSCREEN_EVENT: &str = "windows://toggle-fullscreen";
+
+/// Event label prefix for the window list, followed by the slot, e.g. `windows://focus-window-0`.
+/// Menu specific event, handled by the plugin by focusing the listed window.
+pub const WINDOW_FOCUS_WINDOW_EVENT: &str = "windows://focus-window";
+
+/// Event label for help.
+/// Menu specific event.
+pub const WINDOW_HELP_EVENT: &str = "windows://help";
+
+/// Event label for context menu.
+/// Window specific event, the frontend renders the menu and answers with `select_context_menu_entry`.
+pub const WINDOW_CONTEXT_MENU_EVENT: &str = "windows://context-menu";
+
+/// Event label for context menu selected.
+/// Window specific event, emitted after the action of the selected entry ran.
+pub const WINDOW_CONTEXT_MENU_SELECTED_EVENT: &str = "windows://context-menu-selected";
+
+/// Event label for before navigate.
+/// Window specific event, emitted before a window leaves its page, after its state was captured.
+pub const WINDOW_BEFORE_NAVIGATE_EVENT: &str = "windows://before-navigate";
+
+/// Event label for error.
+/// Window specific event, emitted for plugin errors about the window that no caller receives.
+pub const WINDOW_ERROR_EVENT: &str = "windows://error";
+
+/// Send files to open to a window.
+pub fn emit_open_files<R: Runtime>(window: &Window<R>, payload: OpenFilePayload) -> Result<()> {
+  window.emit(WINDOW_OPEN_FILES_EVENT, payload)
+}
+
+/// Send files to open to all windows.
+pub fn broadcast_open_files<R: Runtime, M: Manager<R>>(manager: &M, payload: OpenFilePayload) -> Result<()> {
+  manager.emit_all(WINDOW_OPEN_FILES_EVENT, payload)
+}
+
+/// Send folders to add to a window.
+pub fn emit_add_folders<R: Runtime>(window: &Window<R>, payload: AddFolderPayload) -> Result<()> {
+  window.emit(WINDOW_ADD_FOLDERS_EVENT, payload)
+}
+
+/// Send folders to add to all windows.
+pub fn broadcast_add_folders<R: Runtime, M: Manager<R>>(manager: &M, payload: AddFolderPayload) -> Result<()> {
+  manager.emit_all(WINDOW_ADD_FOLDERS_EVENT, payload)
+}
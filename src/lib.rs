@@ -8,12 +8,18 @@
 
 use serde_json::Value as JsonValue;
 
-use std::result::Result as StdResult;
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  path::PathBuf,
+  result::Result as StdResult,
+  sync::Arc,
+  time::Duration,
+};
 
 use tauri::{
   plugin::{Plugin, Result as PluginResult},
-  AppHandle, Invoke, Manager, PageLoadPayload, Result as TauriResult, RunEvent, Runtime, Window,
-  WindowEvent, command
+  AppHandle, Invoke, Manager, PageLoadPayload, RunEvent, Runtime, Theme, Url,
+  Window, WindowEvent, WindowUrl, command
 };
 
 pub mod windows;
@@ -21,99 +27,1183 @@ pub mod error;
 pub mod event;
 
 pub use windows::{
+  ActivationPolicy,
+  BroadcastReply,
+  ContextMenuEntry,
+  Screenshot,
+  SwitcherEntry,
+  Diagnostics,
+  FolderChangedPayload,
+  LabelStrategy,
+  sanitize_label,
+  validate_label,
+  MonitorInfo,
+  OpenBehavior,
   OpenConfiguration,
+  OpenedWindowInfo,
+  OpenOutcome,
+  OpenStats,
+  PermissionSettings,
+  PickOptions,
+  RecentlyOpened,
+  ResourceValidation,
+  SnapRegion,
+  StartupInfo,
+  StartupMetrics,
+  StateSnapshot,
+  StorageCipher,
+  TileLayout,
   WindowOptions,
+  WindowInfo,
+  WindowOptionsUpdate,
+  WindowPermission,
+  WindowPosition,
+  WindowState,
+  WindowStatePatch,
   WindowsSettings,
   WindowsAPI,
+  WindowsOpenFilter,
+  WindowsTelemetry,
   WindowOpenable
 };
-pub use error::Error;
+pub use error::{Error, ErrorKind};
 
 use windows::{
+  check_open_permissions,
+  check_own_window,
+  check_permission,
+  check_window_permission,
+  clear_ready_queue,
+  clear_running_sentinel,
+  clear_subscriptions,
+  clear_sticky_deliveries,
+  clear_context_menu,
+  remove_broadcast_window,
+  DEFAULT_BROADCAST_TIMEOUT,
+  close_splash,
+  emit_cache_changes,
+  emit_sticky,
+  queue_open,
+  emit_trust_request,
+  emit_window_created,
+  handle_activated_window,
+  handle_system_theme_changed,
+  handle_window_bounds_changed,
+  handle_window_created,
+  handle_window_moved,
+  handle_zoom_menu_event,
+  is_excluded,
+  is_graceful_exit_enabled,
+  log_error,
+  migrate_empty_window_backups,
+  migrate_legacy_files,
+  parse_deep_link,
+  record_page_load,
+  report_window_error,
+  remove_clean_window_backups,
+  remove_quit_window,
+  restore_zoom,
+  request_close,
+  request_quit,
+  should_confirm_close,
+  show_splash,
+  start_auto_save,
+  unwatch_folder,
+  write_lock,
+  FolderWatchers,
+  SplashState,
+  WindowLabels,
   LastActiveWindow,
   WindowsStateCache,
-  WindowsBackupCache,
-  WindowsRecentsCache,
-  WindowStateTrait,
-  WindowTrait
-};
-use event::{
-  WINDOW_OPEN_FILES_EVENT,
-  WINDOW_ADD_FOLDERS_EVENT,
-  WINDOW_NEW_WINDOW_EVENT,
-  WINDOW_OPEN_FILE_EVENT,
-  WINDOW_OPEN_FOLDER_EVENT,
-  WINDOW_CLOSE_WINDOW_EVENT,
-  WINDOW_CLOSE_FILE_EVENT,
-  WINDOW_CLOSE_FOLDER_EVENT,
+  WindowsCloseState,
+  WindowsCreating,
+  WindowsLayoutCache,
+  WindowsQuitState,
+  SystemTheme,
+  WindowsReadyQueue,
+  WindowsStackState,
+  WindowsStartupInfo,
+  WindowsBroadcasts,
+  WindowsOpenQueue,
+  WindowsOpenRequests,
+  WindowsContextMenus,
+  WindowsStickyEvents,
+  WindowsSubscriptions,
+  WindowsOpenFilterHook,
+  WindowsTelemetryHook,
+  WindowsTrustCache,
 };
+#[cfg(feature = "backups")]
+use windows::WindowsBackupCache;
+#[cfg(feature = "recents")]
+use windows::WindowsRecentsCache;
+#[cfg(feature = "menu")]
+use windows::{handle_window_menu_event, refresh_window_list};
+use event::WINDOW_RESTORE_AVAILABLE_EVENT;
 
 type Result<T> = StdResult<T, String>;
 
+/// Run work that waits on windows or the filesystem on a blocking thread, so it doesn't hold up the async runtime.
+async fn run_blocking<T, F>(work: F) -> StdResult<T, Error>
+where
+  T: Send + 'static,
+  F: FnOnce() -> StdResult<T, Error> + Send + 'static,
+{
+  tauri::async_runtime::spawn_blocking(work).await
+    .map_err(Error::from)
+    .and_then(|res| res)
+}
+
+#[command]
+async fn open_window<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  configuration: OpenConfiguration,
+) -> Result<OpenedWindowInfo> {
+  let app = _app.clone();
+
+  let res = match check_open_permissions(&_app, _window.label(), &configuration) {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_window(&app, configuration).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_with_outcome<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  configuration: OpenConfiguration,
+) -> Result<OpenOutcome> {
+  let app = _app.clone();
+
+  let res = match check_open_permissions(&_app, _window.label(), &configuration) {
+    Ok(()) => queue_open(&_app, move || Ok(WindowsAPI::open_with_outcome(&app, configuration))).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((outcome, _position)) => Ok(outcome),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_windows<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  configurations: Vec<OpenConfiguration>,
+) -> Vec<Result<OpenedWindowInfo>> {
+  let permitted = configurations
+    .iter()
+    .try_for_each(|configuration| check_open_permissions(&_app, _window.label(), configuration));
+
+  if let Err(e) = permitted {
+    log_error(&e);
+    return configurations.iter().map(|_configuration| Err(e.to_string())).collect();
+  }
+
+  let app = _app.clone();
+  let requested = configurations.len();
+
+  let (results, position) = match queue_open(&_app, move || {
+    Ok(
+      WindowsAPI::open_windows(&app, configurations)
+        .into_iter()
+        .map(|res| res.map(|opened_window| opened_window.info()))
+        .collect::<Vec<_>>()
+    )
+  }).await {
+    Ok(queued) => queued,
+    Err(e) => {
+      log_error(&e);
+      return (0..requested).map(|_index| Err(e.to_string())).collect();
+    },
+  };
+
+  results
+    .into_iter()
+    .map(|res| match res {
+      Ok(info) => Ok(info.with_queue_position(position)),
+      Err(e) => {
+        log_error(&e);
+        Err(e.to_string())
+      },
+    })
+    .collect()
+}
+#[command]
+async fn open_empty_window<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  configuration: OpenConfiguration,
+  options: WindowOptions,
+) -> Result<OpenedWindowInfo> {
+  let app = _app.clone();
+
+  let res = match check_open_permissions(&_app, _window.label(), &configuration) {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_empty_window(&app, configuration, options).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_route<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  route: String,
+  options: Option<WindowOptions>,
+) -> Result<OpenedWindowInfo> {
+  let options = options.unwrap_or_default();
+  let app = _app.clone();
+
+  let permitted = match options.folder {
+    Some(_) => check_permission(&_app, _window.label(), WindowPermission::OpenFolders),
+    None => Ok(()),
+  };
+
+  let res = match permitted {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_route(&app, &route, options).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn navigate_window<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, url: WindowUrl) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::navigate_window(&_app, &label, url)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_existing_window<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  configuration: OpenConfiguration,
+  _window_to_use: String,
+) -> Result<()> {
+  let app = _app.clone();
+  let window = _window.clone();
+
+  let res = match check_open_permissions(&_app, _window.label(), &configuration) {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_existing_window(&app, &window, configuration)).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok(((), _position)) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_deep_link<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  url: Url,
+) -> Result<OpenedWindowInfo> {
+  let permitted = parse_deep_link(&url)
+    .map_or(Ok(()), |configuration| check_open_permissions(&_app, _window.label(), &configuration));
+
+  let app = _app.clone();
+
+  let res = match permitted {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_deep_link(&app, &url).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_files<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  paths: Vec<PathBuf>,
+  behavior: Option<OpenBehavior>,
+) -> Result<OpenedWindowInfo> {
+  let app = _app.clone();
+
+  let res = match check_permission(&_app, _window.label(), WindowPermission::OpenFiles) {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_files(&app, &paths, behavior.unwrap_or_default()).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn open_folder<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  path: PathBuf,
+  behavior: Option<OpenBehavior>,
+) -> Result<OpenedWindowInfo> {
+  let app = _app.clone();
+
+  let res = match check_permission(&_app, _window.label(), WindowPermission::OpenFolders) {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::open_folder(&app, &path, behavior.unwrap_or_default()).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn pick_and_open_files<R: Runtime>(
+  _window: Window<R>,
+  options: Option<PickOptions>,
+) -> Result<Option<OpenedWindowInfo>> {
+  let window = _window.clone();
+
+  // The dialog waits on the user, only opening the picked paths waits its turn
+  let picked = match check_permission(&_window, _window.label(), WindowPermission::OpenFiles) {
+    Ok(()) => run_blocking(move || WindowsAPI::pick_files(&window, options.unwrap_or_default())).await,
+    Err(e) => Err(e),
+  };
+
+  let window = _window.clone();
+
+  let res = match picked {
+    Ok(Some(configuration)) => queue_open(&_window, move || {
+      WindowsAPI::open_window(&window, configuration).map(|opened_window| Some(opened_window.info()))
+    }).await,
+    Ok(None) => Ok((None, 0)),
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.map(|info| info.with_queue_position(position))),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn pick_and_open_folder<R: Runtime>(
+  _window: Window<R>,
+  options: Option<PickOptions>,
+) -> Result<Option<OpenedWindowInfo>> {
+  let window = _window.clone();
+
+  // The dialog waits on the user, only opening the picked paths waits its turn
+  let picked = match check_permission(&_window, _window.label(), WindowPermission::OpenFolders) {
+    Ok(()) => run_blocking(move || WindowsAPI::pick_folder(&window, options.unwrap_or_default())).await,
+    Err(e) => Err(e),
+  };
+
+  let window = _window.clone();
+
+  let res = match picked {
+    Ok(Some(configuration)) => queue_open(&_window, move || {
+      WindowsAPI::open_window(&window, configuration).map(|opened_window| Some(opened_window.info()))
+    }).await,
+    Ok(None) => Ok((None, 0)),
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.map(|info| info.with_queue_position(position))),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn duplicate_window<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+) -> Result<OpenedWindowInfo> {
+  let app = _app.clone();
+
+  let permitted = check_permission(&_app, _window.label(), WindowPermission::OpenFolders)
+    .and_then(|()| check_permission(&_app, _window.label(), WindowPermission::OpenFiles));
+
+  let res = match permitted {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::duplicate_window(&app, &label).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn move_resource<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  from_label: String,
+  to_label: String,
+  path: PathBuf,
+) -> Result<OpenedWindowInfo> {
+  let permission = match path.is_dir() {
+    true => WindowPermission::OpenFolders,
+    false => WindowPermission::OpenFiles,
+  };
+
+  let app = _app.clone();
+
+  let res = match check_permission(&_app, _window.label(), permission) {
+    Ok(()) => queue_open(&_app, move || WindowsAPI::move_resource(&app, &from_label, &to_label, path).map(|opened_window| opened_window.info())).await,
+    Err(e) => Err(e),
+  };
+
+  match res {
+    Ok((info, position)) => Ok(info.with_queue_position(position)),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn prune_state<R: Runtime>(_app: AppHandle<R>, _window: Window<R>) -> Result<usize> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::prune_state(&_app)) {
+    Ok(removed) => Ok(removed),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn update_window_options<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+  options: WindowOptionsUpdate,
+) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::update_window_options(&_app, &label, options)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn screenshot_window<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+  to_file: Option<bool>,
+) -> Result<Screenshot> {
+  let screenshot = match check_window_permission(&_app, _window.label(), &label, WindowPermission::Settings) {
+    Ok(()) => run_blocking(move || WindowsAPI::screenshot_window(&_app, &label, to_file.unwrap_or(false))).await,
+    Err(e) => Err(e),
+  };
+
+  match screenshot {
+    Ok(screenshot) => Ok(screenshot),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn get_switcher_entries<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  thumbnails: Option<bool>,
+) -> Result<Vec<SwitcherEntry>> {
+  let thumbnails = thumbnails.unwrap_or(false);
+
+  // Thumbnails capture the contents of every window
+  let permitted = match thumbnails {
+    true => check_permission(&_app, _window.label(), WindowPermission::Settings),
+    false => Ok(()),
+  };
+
+  let entries = match permitted {
+    Ok(()) => run_blocking(move || WindowsAPI::get_switcher_entries(&_app, thumbnails)).await,
+    Err(e) => Err(e),
+  };
+
+  match entries {
+    Ok(entries) => Ok(entries),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn zoom_in<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String) -> Result<f64> {
+  match check_window_permission(&_app, _window.label(), &label, WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::zoom_in(&_app, &label)) {
+    Ok(zoom) => Ok(zoom),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn zoom_out<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String) -> Result<f64> {
+  match check_window_permission(&_app, _window.label(), &label, WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::zoom_out(&_app, &label)) {
+    Ok(zoom) => Ok(zoom),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn zoom_reset<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String) -> Result<f64> {
+  match check_window_permission(&_app, _window.label(), &label, WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::zoom_reset(&_app, &label)) {
+    Ok(zoom) => Ok(zoom),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_zoom<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, zoom: f64) -> Result<f64> {
+  match check_window_permission(&_app, _window.label(), &label, WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::set_zoom(&_app, &label, zoom)) {
+    Ok(zoom) => Ok(zoom),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_devtools<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+  open: bool,
+) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::set_devtools(&_app, &label, open)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn show_context_menu<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+  entries: Vec<ContextMenuEntry>,
+  position: Option<WindowPosition>,
+) -> Result<u64> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::show_context_menu(&_app, &label, entries, position)) {
+    Ok(id) => Ok(id),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn select_context_menu_entry<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+  id: u64,
+  entry_id: Option<String>,
+) -> Result<()> {
+  let app = _app.clone();
+
+  // Actions may open or move windows, they wait their turn with the other open requests
+  let selected = match check_own_window(_window.label(), &label) {
+    Ok(()) => queue_open(&_app, move || {
+      WindowsAPI::select_context_menu_entry(&app, &label, id, entry_id.as_deref())
+    }).await,
+    Err(e) => Err(e),
+  };
+
+  match selected {
+    Ok(((), _position)) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_content_protected<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  label: String,
+  protected: bool,
+) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::set_content_protected(&_app, &label, protected)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_default_open_directory<R: Runtime>(_app: AppHandle<R>) -> Option<PathBuf> {
+  WindowsAPI::get_default_open_directory(&_app)
+}
+#[command]
+fn get_window_state<R: Runtime>(_app: AppHandle<R>, label: String) -> Result<WindowState> {
+  match WindowsAPI::get_window_state(&_app, &label) {
+    Ok(state) => Ok(state),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_backup_path<R: Runtime>(_app: AppHandle<R>, label: String) -> Result<Option<PathBuf>> {
+  match WindowsAPI::get_backup_path(&_app, &label) {
+    Ok(backup_path) => Ok(backup_path),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_window_state<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, patch: WindowStatePatch) -> Result<WindowState> {
+  let permission = match patch.folder {
+    Some(_) => WindowPermission::OpenFolders,
+    None => WindowPermission::Settings,
+  };
+
+  match check_permission(&_app, _window.label(), permission)
+    .and_then(|()| WindowsAPI::set_window_state(&_app, &label, patch)) {
+    Ok(state) => Ok(state),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_focus_history<R: Runtime>(_app: AppHandle<R>) -> Vec<String> {
+  WindowsAPI::get_focus_history(&_app)
+}
+#[command]
+fn focus_previous_window<R: Runtime>(_app: AppHandle<R>) -> Result<Option<String>> {
+  match WindowsAPI::focus_previous_window(&_app) {
+    Ok(window) => Ok(window.map(|window| window.label().to_string())),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_window_stack<R: Runtime>(_app: AppHandle<R>) -> Result<Vec<String>> {
+  match WindowsAPI::get_window_stack(&_app) {
+    Ok(stack) => Ok(stack),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn restore_stacking_order<R: Runtime>(_app: AppHandle<R>) -> Result<()> {
+  match WindowsAPI::restore_stacking_order(&_app) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn adopt_window<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String) -> Result<bool> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::adopt_window(&_app, &label)) {
+    Ok(adopted) => Ok(adopted),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn list_windows<R: Runtime>(_app: AppHandle<R>) -> Result<Vec<WindowInfo>> {
+  match WindowsAPI::list_windows(&_app) {
+    Ok(windows) => Ok(windows),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_window_group<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, group: Option<String>) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::set_window_group(&_app, &label, group)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn focus_group<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, group: String) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::focus_group(&_app, &group)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn close_group<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, group: String) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::close_group(&_app, &group)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn minimize_group<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, group: String) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::minimize_group(&_app, &group)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn save_layout<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, name: String) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::save_layout(&_app, &name)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn apply_layout<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, name: String) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::apply_layout(&_app, &name)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_monitors<R: Runtime>(_app: AppHandle<R>) -> Result<Vec<MonitorInfo>> {
+  match WindowsAPI::get_monitors(&_app) {
+    Ok(monitors) => Ok(monitors),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_window_monitor<R: Runtime>(_app: AppHandle<R>, label: String) -> Result<Option<MonitorInfo>> {
+  match WindowsAPI::get_window_monitor(&_app, &label) {
+    Ok(monitor) => Ok(monitor),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn snap_window<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, region: SnapRegion) -> Result<()> {
+  match check_window_permission(&_app, _window.label(), &label, WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::snap_window(&_app, &label, region)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn tile_windows<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, labels: Vec<String>, layout: TileLayout) -> Result<()> {
+  let permitted = labels
+    .iter()
+    .try_for_each(|label| check_window_permission(&_app, _window.label(), label, WindowPermission::Settings));
+
+  match permitted.and_then(|()| WindowsAPI::tile_windows(&_app, &labels, layout)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_theme<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: Option<String>, theme: Option<Theme>) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::set_theme(&_app, label.as_deref(), theme)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn relaunch_preserving_session<R: Runtime>(_app: AppHandle<R>, _window: Window<R>) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::relaunch_preserving_session(&_app)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_startup_info<R: Runtime>(_app: AppHandle<R>) -> Result<StartupInfo> {
+  match WindowsAPI::get_startup_info(&_app) {
+    Ok(info) => Ok(info),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
 #[command]
-fn open_window<R: Runtime>(
+fn acknowledge_quit<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, allow: bool) -> Result<()> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::acknowledge_quit(&_app, &label, allow)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn confirm_close<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, allow: bool) -> Result<()> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::confirm_close(&_app, &label, allow)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn window_ready<R: Runtime>(_app: AppHandle<R>, _window: Window<R>) -> Result<()> {
+  match WindowsAPI::window_ready(&_app, _window.label()) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_pending_work<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, pending_work: bool) -> Result<()> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::set_pending_work(&_app, &label, pending_work)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn set_dirty<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, dirty: bool) -> Result<()> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::set_dirty(&_app, &label, dirty)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn trust_folder<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, folder: PathBuf) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::trust_folder(&_app, folder)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn revoke_trust<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, folder: PathBuf) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::revoke_trust(&_app, folder)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_recents<R: Runtime>(_app: AppHandle<R>) -> Result<RecentlyOpened> {
+  match WindowsAPI::get_recents(&_app) {
+    Ok(recents) => Ok(recents),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+async fn validate_resources<R: Runtime>(
+  _app: AppHandle<R>,
+  validation: ResourceValidation,
+) -> Result<usize> {
+  // Checking every resource touches the filesystem, keep it off the async runtime
+  match run_blocking(move || WindowsAPI::validate_resources(&_app, validation)).await {
+    Ok(missing) => Ok(missing),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn send_to_focused<R: Runtime>(
   _app: AppHandle<R>,
   _window: Window<R>,
-  configuration: OpenConfiguration,
-) -> Result<()> {
-  match WindowsAPI::open_window(&_app, configuration) {
-    Ok(_created_window) => Ok(()),
+  channel: String,
+  payload: JsonValue,
+) -> Result<Option<String>> {
+  match check_permission(&_app, _window.label(), WindowPermission::Broadcast)
+    .and_then(|()| WindowsAPI::send_to_focused(&_app, &channel, payload)) {
+    Ok(label) => Ok(label),
     Err(e) => {
-      eprintln!("Error: {:?}", e);
+      log_error(&e);
       Err(e.to_string())
     },
   }
 }
 #[command]
-async fn open_empty_window<R: Runtime>(
+fn send_to_all<R: Runtime>(
   _app: AppHandle<R>,
   _window: Window<R>,
-  configuration: OpenConfiguration,
-  options: WindowOptions,
+  channel: String,
+  payload: JsonValue,
+  window_labels_to_ignore: Option<Vec<String>>,
+  sticky: Option<bool>,
 ) -> Result<()> {
-  match WindowsAPI::open_empty_window(&_app, configuration, options) {
-    Ok(_created_window) => Ok(()),
+  match check_permission(&_app, _window.label(), WindowPermission::Broadcast).and_then(|()| {
+    WindowsAPI::send_to_all(&_app, &channel, payload, &window_labels_to_ignore.unwrap_or_default(), sticky.unwrap_or(false))
+  }) {
+    Ok(()) => Ok(()),
     Err(e) => {
-      eprintln!("Error: {:?}", e);
+      log_error(&e);
       Err(e.to_string())
     },
   }
 }
 #[command]
-fn open_existing_window<R: Runtime>(
+async fn send_to_all_and_wait<R: Runtime>(
   _app: AppHandle<R>,
   _window: Window<R>,
-  configuration: OpenConfiguration,
-  _window_to_use: String,
+  channel: String,
+  payload: JsonValue,
+  window_labels_to_ignore: Option<Vec<String>>,
+  timeout_ms: Option<u64>,
+) -> Result<Vec<BroadcastReply>> {
+  if let Err(e) = check_permission(&_app, _window.label(), WindowPermission::Broadcast) {
+    log_error(&e);
+    return Err(e.to_string());
+  }
+
+  let timeout = timeout_ms.map_or(DEFAULT_BROADCAST_TIMEOUT, Duration::from_millis);
+
+  let replies = run_blocking(move || {
+    WindowsAPI::send_to_all_and_wait(&_app, &channel, payload, &window_labels_to_ignore.unwrap_or_default(), timeout)
+  }).await;
+
+  match replies {
+    Ok(replies) => Ok(replies),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn acknowledge_broadcast<R: Runtime>(
+  _app: AppHandle<R>,
+  _window: Window<R>,
+  id: u64,
+  result: Option<JsonValue>,
+  error: Option<String>,
 ) -> Result<()> {
-  match WindowsAPI::open_existing_window(&_app, &_window, configuration) {
-    Ok(_created_window) => Ok(()),
+  match WindowsAPI::acknowledge_broadcast(&_app, id, _window.label(), result, error) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn subscribe<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, channels: Vec<String>) -> Result<()> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::subscribe(&_app, &label, channels)) {
+    Ok(()) => Ok(()),
     Err(e) => {
-      eprintln!("Error: {:?}", e);
+      log_error(&e);
       Err(e.to_string())
     },
   }
 }
 #[command]
-fn send_to_focused(_channel: String) -> TauriResult<()> {
-  Ok(())
+fn unsubscribe<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, label: String, channels: Vec<String>) -> Result<()> {
+  match check_own_window(_window.label(), &label)
+    .and_then(|()| WindowsAPI::unsubscribe(&_app, &label, channels)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_focused_window<R: Runtime>(_app: AppHandle<R>) -> Option<String> {
+  WindowsAPI::get_focused_window(&_app).map(|window| window.label().to_string())
+}
+#[command]
+fn get_last_active_window<R: Runtime>(_app: AppHandle<R>) -> Result<Option<LastActiveWindow>> {
+  match WindowsAPI::get_last_active_window_state(&_app) {
+    Ok(last_active_window) => Ok(last_active_window),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
+}
+#[command]
+fn get_startup_metrics<R: Runtime>(_app: AppHandle<R>) -> Result<StartupMetrics> {
+  match WindowsAPI::get_startup_metrics(&_app) {
+    Ok(metrics) => Ok(metrics),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
 }
 #[command]
-fn send_to_all(_channel: String, _window_labels_to_ignoree: Vec<String>) -> Result<()> {
-  Ok(())
+fn get_diagnostics<R: Runtime>(_app: AppHandle<R>) -> Result<Diagnostics> {
+  match WindowsAPI::get_diagnostics(&_app) {
+    Ok(diagnostics) => Ok(diagnostics),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
 }
 #[command]
-fn get_focused_window() -> Result<()> {
-  Ok(())
+fn export_state<R: Runtime>(_app: AppHandle<R>, _window: Window<R>) -> Result<String> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::export_state(&_app)) {
+    Ok(json) => Ok(json),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
 }
 #[command]
-fn get_last_active_window() -> Result<()> {
-  Ok(())
+fn import_state<R: Runtime>(_app: AppHandle<R>, _window: Window<R>, json: String) -> Result<()> {
+  match check_permission(&_app, _window.label(), WindowPermission::Settings)
+    .and_then(|()| WindowsAPI::import_state(&_app, &json)) {
+    Ok(()) => Ok(()),
+    Err(e) => {
+      log_error(&e);
+      Err(e.to_string())
+    },
+  }
 }
 
 pub struct TauriWindows<R: Runtime> {
   invoke_handler: Box<dyn Fn(Invoke<R>) + Send + Sync>,
   // plugin state, configuration fields
+  label_strategy: Option<LabelStrategy>,
+  telemetry: Option<Box<dyn WindowsTelemetry>>,
+  open_filter: Option<Box<dyn WindowsOpenFilter>>,
+  cipher: Option<Arc<dyn StorageCipher>>,
+  permissions: BTreeMap<String, BTreeSet<WindowPermission>>,
+  in_memory: bool,
 }
 
 impl<R: Runtime> TauriWindows<R> {
@@ -121,17 +1211,134 @@ impl<R: Runtime> TauriWindows<R> {
   // see https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
   pub fn default() -> Self {
     Self {
+      label_strategy: None,
+      telemetry: None,
+      open_filter: None,
+      cipher: None,
+      permissions: BTreeMap::new(),
+      in_memory: false,
       invoke_handler: Box::new(tauri::generate_handler![
         open_window,
+        open_with_outcome,
+        open_windows,
         open_empty_window,
+        open_route,
         open_existing_window,
+        navigate_window,
+        open_deep_link,
+        open_files,
+        open_folder,
+        pick_and_open_files,
+        pick_and_open_folder,
+        get_default_open_directory,
+        duplicate_window,
+        move_resource,
+        prune_state,
+        get_recents,
+        validate_resources,
+        trust_folder,
+        revoke_trust,
+        confirm_close,
+        set_pending_work,
+        set_dirty,
+        window_ready,
+        acknowledge_quit,
+        get_startup_info,
+        relaunch_preserving_session,
+        set_theme,
+        update_window_options,
+        zoom_in,
+        zoom_out,
+        zoom_reset,
+        set_zoom,
+        set_devtools,
+        set_content_protected,
+        show_context_menu,
+        select_context_menu_entry,
+        screenshot_window,
+        get_switcher_entries,
+        get_window_state,
+        get_backup_path,
+        set_window_state,
+        get_monitors,
+        get_window_monitor,
+        snap_window,
+        tile_windows,
+        save_layout,
+        apply_layout,
+        get_focus_history,
+        focus_previous_window,
+        get_window_stack,
+        restore_stacking_order,
+        adopt_window,
+        list_windows,
+        set_window_group,
+        focus_group,
+        close_group,
+        minimize_group,
         send_to_focused,
         send_to_all,
+        send_to_all_and_wait,
+        acknowledge_broadcast,
+        subscribe,
+        unsubscribe,
         get_focused_window,
-        get_last_active_window
+        get_last_active_window,
+        get_startup_metrics,
+        get_diagnostics,
+        export_state,
+        import_state
       ]),
     }
   }
+
+  /// Set the strategy used to generate labels for new windows.
+  ///
+  /// Defaults to [`LabelStrategy::Counter`].
+  pub fn label_strategy(mut self, strategy: LabelStrategy) -> Self {
+    self.label_strategy = Some(strategy);
+    self
+  }
+
+  /// Set a hook invoked with statistics on every open request.
+  pub fn telemetry<T: WindowsTelemetry + 'static>(mut self, telemetry: T) -> Self {
+    self.telemetry = Some(Box::new(telemetry));
+    self
+  }
+
+  /// Set a filter rewriting or rejecting the paths of open requests before windows are created.
+  ///
+  /// Accepts a [`WindowsOpenFilter`] or a closure, e.g. leaving `.zip` files out to import them instead.
+  pub fn register_open_filter<F: WindowsOpenFilter + 'static>(mut self, filter: F) -> Self {
+    self.open_filter = Some(Box::new(filter));
+    self
+  }
+
+  /// Set the commands a window may invoke, see [`PermissionSettings`].
+  ///
+  /// Overrides the permissions of the label in the plugin config.
+  pub fn permissions(mut self, label: &str, permissions: &[WindowPermission]) -> Self {
+    self.permissions.insert(label.to_string(), permissions.iter().copied().collect());
+    self
+  }
+
+  /// Encrypt the files of window states, recents, backups, trusted folders and layouts.
+  ///
+  /// The key lives in the cipher, e.g. read from the OS keychain by the app.
+  /// Plain files of earlier versions are read and rewritten encrypted,
+  /// files encrypted with another key are left untouched and the caches start empty.
+  pub fn encryption<C: StorageCipher + 'static>(mut self, cipher: C) -> Self {
+    self.cipher = Some(Arc::new(cipher));
+    self
+  }
+
+  /// Keep window states, recents, backups, trusted folders and layouts in memory only.
+  ///
+  /// Nothing is read from or written to the data directory, e.g. for tests.
+  pub fn in_memory(mut self) -> Self {
+    self.in_memory = true;
+    self
+  }
 }
 
 impl<R: Runtime> Plugin<R> for TauriWindows<R> {
@@ -149,32 +1356,271 @@ impl<R: Runtime> Plugin<R> for TauriWindows<R> {
   }
 
   /// initialize plugin with the config provided on `tauri.conf.json > plugins > $yourPluginName` or the default value.
-  fn initialize(&mut self, app: &AppHandle<R>, _config: JsonValue) -> PluginResult<()> {
-    app.manage(WindowsSettings::default());
-    app.manage(WindowsStateCache::default());
-    app.manage(WindowsBackupCache::default());
-    app.manage(WindowsRecentsCache::default());
+  fn initialize(&mut self, app: &AppHandle<R>, config: JsonValue) -> PluginResult<()> {
+    let mut settings = WindowsSettings::from_config(config);
+
+    if let Ok(settings) = settings.0.get_mut() {
+      settings.permissions.windows.append(&mut self.permissions);
+    }
+
+    app.manage(settings);
+
+    // Files are scoped to the app, other apps using the plugin have their own
+    let storage_dir = match self.in_memory {
+      true => None,
+      false => app.path_resolver().app_data_dir(),
+    };
+
+    match storage_dir {
+      Some(dir) => {
+        let legacy_dir = migrate_legacy_files(&dir);
+        let cipher = self.cipher.take();
+
+        let mut state_cache = WindowsStateCache::open(&dir, cipher.clone());
+
+        // Backups moved along with the state, window states still point at the old ones
+        if let Some(legacy_dir) = legacy_dir {
+          let res = state_cache.0.get_mut()
+            .map_err(|e| Error::RwLock(e.to_string()))
+            .and_then(|cache| cache.rebase_backup_paths(&legacy_dir, &dir));
+
+          if let Err(e) = res {
+            log_error(&e);
+          }
+        }
+
+        app.manage(state_cache);
+        #[cfg(feature = "backups")]
+        app.manage(WindowsBackupCache::open(&dir, cipher.clone()));
+        #[cfg(feature = "recents")]
+        app.manage(WindowsRecentsCache::open(&dir, cipher.clone()));
+        app.manage(WindowsTrustCache::open(&dir, cipher.clone()));
+        app.manage(WindowsStartupInfo::detect(&dir));
+        app.manage(WindowsLayoutCache::open(&dir, cipher));
+      },
+      None => {
+        app.manage(WindowsStateCache::in_memory());
+        #[cfg(feature = "backups")]
+        app.manage(WindowsBackupCache::in_memory());
+        #[cfg(feature = "recents")]
+        app.manage(WindowsRecentsCache::in_memory());
+        app.manage(WindowsTrustCache::in_memory());
+        app.manage(WindowsStartupInfo::default());
+        app.manage(WindowsLayoutCache::in_memory());
+      },
+    }
+
+    app.manage(SplashState::default());
+    app.manage(FolderWatchers::default());
+    app.manage(WindowsCloseState::default());
+    app.manage(WindowsCreating::default());
+    app.manage(WindowsQuitState::default());
+    app.manage(SystemTheme::default());
+    app.manage(WindowsStackState::default());
+    app.manage(WindowsReadyQueue::default());
+    app.manage(WindowsSubscriptions::default());
+    app.manage(WindowsBroadcasts::default());
+    app.manage(WindowsStickyEvents::default());
+    app.manage(WindowsOpenQueue::start());
+    app.manage(WindowsOpenRequests::default());
+    app.manage(WindowsContextMenus::default());
+    app.manage(WindowLabels::new(self.label_strategy.take().unwrap_or_default()));
+    app.manage(WindowsTelemetryHook(self.telemetry.take()));
+    app.manage(WindowsOpenFilterHook(self.open_filter.take()));
 
     Ok(())
   }
 
   /// Callback invoked when the Window is created.
-  fn created(&mut self, _window: Window<R>) {
+  fn created(&mut self, window: Window<R>) {
+    if is_excluded(&window, window.label()) {
+      return;
+    }
+
+    // Windows built by the app directly
+    if let Err(e) = handle_window_created(&window) {
+      log_error(&e);
+    }
+
+    // Zoom menu items act on the window of the menu
+    let menu_window = window.clone();
+
+    window.on_menu_event(move |event| {
+      if let Err(e) = handle_zoom_menu_event(&menu_window, event.menu_item_id()) {
+        log_error(&e);
+      }
+
+      #[cfg(feature = "menu")]
+      if let Err(e) = handle_window_menu_event(&menu_window, event.menu_item_id()) {
+        log_error(&e);
+      }
+    });
+
+    #[cfg(feature = "menu")]
+    if let Err(e) = refresh_window_list(&window, None) {
+      log_error(&e);
+    }
   }
 
   /// Callback invoked when the webview performs a navigation.
-  fn on_page_load(&mut self, _window: Window<R>, _payloadd: PageLoadPayload) {}
+  fn on_page_load(&mut self, window: Window<R>, _payloadd: PageLoadPayload) {
+    if is_excluded(&window, window.label()) {
+      return;
+    }
+
+    if let Err(e) = record_page_load(&window, window.label()) {
+      report_window_error(&window, window.label(), &e);
+    }
+
+    // Zoom is lost with the page, apply it again
+    if let Err(e) = restore_zoom(&window) {
+      log_error(&e);
+    }
+
+    // Pages usually set the title of the window
+    #[cfg(feature = "menu")]
+    if let Err(e) = refresh_window_list(&window, None) {
+      log_error(&e);
+    }
+
+    // First restored window is ready, splash is no longer needed
+    if let Err(e) = close_splash(&window, window.label()) {
+      log_error(&e);
+    }
+
+    // Backup path and state of the window
+    if let Err(e) = emit_window_created(&window, window.label()) {
+      log_error(&e);
+    }
+
+    // Frontend may have missed the trust request while loading
+    if let Err(e) = emit_trust_request(&window, window.label()) {
+      log_error(&e);
+    }
+  }
 
   fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
+    // Excluded windows are left to the app
+    if let RunEvent::WindowEvent { label, .. } = event {
+      if is_excluded(app, label) {
+        return;
+      }
+    }
+
     match event {
+      RunEvent::Ready => {
+        // Backup ids of earlier versions collide with new empty windows
+        if let Err(e) = migrate_empty_window_backups(app) {
+          log_error(&e);
+        }
+
+        // Drop stale window states before anything gets restored
+        if let Err(e) = WindowsAPI::prune_state(app) {
+          log_error(&e);
+        }
+
+        // Check recents and backups without blocking startup
+        let validation = match app.state::<WindowsSettings>().0.read() {
+          Ok(settings) => settings.validate_resources,
+          Err(_e) => ResourceValidation::Off,
+        };
+
+        if validation != ResourceValidation::Off {
+          let handle = app.clone();
+          tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = WindowsAPI::validate_resources(&handle, validation) {
+              log_error(&e);
+            }
+          });
+        }
+
+        // Long sessions may see no window events for hours
+        start_auto_save(app);
+
+        let (splash_settings, prompt_restore) = match app.state::<WindowsSettings>().0.read() {
+          Ok(settings) => (settings.splash.clone(), settings.prompt_restore_after_crash),
+          Err(e) => {
+            log_error(&e);
+            return;
+          }
+        };
+
+        let crashed = match app.state::<WindowsStartupInfo>().0.read() {
+          Ok(info) => info.crashed,
+          Err(_e) => false,
+        };
+
+        if crashed {
+          if let Ok(info) = WindowsAPI::get_startup_info(app) {
+            // Windows opened later still get it
+            if let Err(e) = emit_sticky(app, WINDOW_RESTORE_AVAILABLE_EVENT, info, &[]) {
+              log_error(&e);
+            }
+          }
+        }
+
+        // The app decides whether to restore after a crash
+        if crashed && prompt_restore {
+          return;
+        }
+
+        if splash_settings.enabled {
+          if let Err(e) = show_splash(app, &splash_settings) {
+            log_error(&e);
+          }
+        }
+
+        // Restore previous session in the background, in turn with other open requests
+        let handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+          let app = handle.clone();
+
+          let restore_res = queue_open(&handle, move || {
+            WindowsAPI::open_window(
+              &app,
+              OpenConfiguration {
+                initial_startup: true,
+                ..Default::default()
+              }
+            )
+            .map(|_opened_window| ())
+          }).await;
+
+          if let Err(e) = restore_res {
+            log_error(&e);
+
+            // Nothing will report ready, don't leave the splash hanging
+            if let Err(e) = close_splash(&handle, "") {
+              log_error(&e);
+            }
+          }
+        });
+      },
       RunEvent::WindowEvent {
         label,
         event: WindowEvent::CloseRequested { api, .. },
         ..
       } => {
+        // Let the frontend veto closing
+        match should_confirm_close(app, label) {
+          Ok(true) => {
+            api.prevent_close();
+
+            if let Err(e) = request_close(app, label) {
+              log_error(&e);
+            }
+
+            return;
+          },
+          Ok(false) => {},
+          Err(e) => {
+            log_error(&e);
+          }
+        };
+
         let windows_state_cache = app.state::<WindowsStateCache>();
 
-        match windows_state_cache.0.write() {
+        match write_lock(&windows_state_cache.0) {
           Ok(mut cache) => {
             cache.handle_close_window(label)
           },
@@ -192,10 +1638,14 @@ impl<R: Runtime> Plugin<R> for TauriWindows<R> {
           match window.emit("windows://resize", size) {
             Ok(_res) => {}
             Err(e) => {
-              eprintln!("Error: {:?}", e)
+              log_error(&e)
             }
           }
         }
+
+        if let Err(e) = handle_window_bounds_changed(app, label) {
+          report_window_error(app, label, &e);
+        }
       },
       RunEvent::WindowEvent {
         label,
@@ -204,7 +1654,7 @@ impl<R: Runtime> Plugin<R> for TauriWindows<R> {
       } => {
         let windows_state_cache = app.state::<WindowsStateCache>();
 
-        match windows_state_cache.0.write() {
+        match write_lock(&windows_state_cache.0) {
           Ok(mut cache) => {
             cache.handle_destroyed_window(label)
           },
@@ -212,6 +1662,52 @@ impl<R: Runtime> Plugin<R> for TauriWindows<R> {
 
           }
         };
+
+        if let Err(e) = unwatch_folder(app, label) {
+          log_error(&e);
+        }
+
+        if let Ok(mut cache) = app.state::<WindowsTrustCache>().0.write() {
+          cache.set_pending(label, None);
+        }
+
+        if let Err(e) = clear_ready_queue(app, label) {
+          log_error(&e);
+        }
+
+        if let Err(e) = clear_subscriptions(app, label) {
+          log_error(&e);
+        }
+
+        if let Err(e) = remove_broadcast_window(app, label) {
+          log_error(&e);
+        }
+
+        if let Err(e) = clear_sticky_deliveries(app, label) {
+          log_error(&e);
+        }
+
+        if let Err(e) = clear_context_menu(app, label) {
+          log_error(&e);
+        }
+
+        // Backups are only kept for windows with pending work
+        if let Err(e) = remove_clean_window_backups(app, label) {
+          log_error(&e);
+        }
+
+        if let Ok(mut state) = app.state::<WindowsCloseState>().0.write() {
+          state.remove(label);
+        }
+
+        if let Err(e) = remove_quit_window(app, label) {
+          log_error(&e);
+        }
+
+        #[cfg(feature = "menu")]
+        if let Err(e) = refresh_window_list(app, Some(label)) {
+          log_error(&e);
+        }
       },
       RunEvent::WindowEvent {
         label,
@@ -220,7 +1716,7 @@ impl<R: Runtime> Plugin<R> for TauriWindows<R> {
       } => {
         let windows_state_cache = app.state::<WindowsStateCache>();
 
-        match windows_state_cache.0.write() {
+        match write_lock(&windows_state_cache.0) {
           Ok(mut cache) => {
             cache.handle_focused_window(label, focus)
           },
@@ -228,11 +1724,66 @@ impl<R: Runtime> Plugin<R> for TauriWindows<R> {
 
           }
         };
+
+        if *focus {
+          if let Err(e) = handle_activated_window(app, label) {
+            log_error(&e);
+          }
+        }
+      },
+      RunEvent::WindowEvent {
+        label,
+        event: WindowEvent::Moved(_),
+        ..
+      } => {
+        // Snap first, the bounds recorded are the final ones
+        if let Err(e) = handle_window_moved(app, label) {
+          log_error(&e);
+        }
+
+        if let Err(e) = handle_window_bounds_changed(app, label) {
+          report_window_error(app, label, &e);
+        }
+      },
+      RunEvent::WindowEvent {
+        label,
+        event: WindowEvent::ScaleFactorChanged { .. },
+        ..
+      } => {
+        if let Err(e) = handle_window_bounds_changed(app, label) {
+          report_window_error(app, label, &e);
+        }
+      },
+      RunEvent::WindowEvent {
+        event: WindowEvent::ThemeChanged(theme),
+        ..
+      } => {
+        if let Err(e) = handle_system_theme_changed(app, *theme) {
+          log_error(&e);
+        }
       },
       RunEvent::ExitRequested { api, .. } => {
-        // Prevents the app from exiting.
-        // This will cause the core thread to continue running in the background even without any open windows.
-        // api.prevent_exit();
+        // Let windows finish their work, exit happens once they all answered
+        if is_graceful_exit_enabled(app) {
+          api.prevent_exit();
+
+          if let Err(e) = request_quit(app) {
+            log_error(&e);
+          }
+        }
+        // Make sure state files are written
+        else if let Err(e) = WindowsAPI::flush(app) {
+          log_error(&e);
+        }
+      },
+      RunEvent::Exit => {
+        clear_running_sentinel(app);
+      },
+      RunEvent::MainEventsCleared => {
+        // Recents and window state changes of this iteration
+        if let Err(e) = emit_cache_changes(app) {
+          log_error(&e);
+        }
       },
       // Ignore all other cases.
       _ => {}
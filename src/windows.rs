@@ -3,35 +3,346 @@
 //! This module contains methods and types for window creation. 
 
 use std::{
+  collections::{HashMap, HashSet},
   convert::Into,
-  path::PathBuf,
+  env::temp_dir,
+  fs::{self, create_dir_all},
+  path::{Component, Path, PathBuf},
   result::Result as StdResult,
-  sync::{
-    atomic::{AtomicUsize, Ordering},
-    RwLock
-  },
+  sync::RwLock,
   time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
-use serde_json::to_string;
+use serde_json::{from_value, Value as JsonValue};
 
 use tauri::{
-  window::WindowBuilder, Error as TauriError, Manager, Runtime, State, Theme, Window, WindowUrl,
+  api::path::home_dir, window::WindowBuilder, Error as TauriError, LogicalPosition, LogicalSize, Manager, Runtime, State,
+  Theme, Url, UserAttentionType, Window, WindowUrl,
 };
 
+mod activation;
+pub use activation::{
+  handle_activated_window,
+  is_click_through_inactive_enabled,
+  WindowActivatedPayload
+};
+
+mod layout;
+pub use layout::{
+  WindowLayout,
+  WindowLayoutEntry,
+  WindowsLayoutCache
+};
+
+#[cfg(feature = "menu")]
 mod menu;
-pub use menu::Menu;
+#[cfg(feature = "menu")]
+pub use menu::{
+  handle_window_menu_event,
+  refresh_window_list,
+  DefaultMenuOptions,
+  Menu,
+  MenuLabels,
+  WINDOW_LIST_SIZE
+};
+
+mod autosave;
+pub use autosave::{start_auto_save, AutoSaveSettings};
+
+mod broadcast;
+pub use broadcast::{
+  remove_broadcast_window,
+  DEFAULT_BROADCAST_TIMEOUT,
+  BroadcastPayload,
+  BroadcastReply,
+  BroadcastStatus,
+  WindowsBroadcasts
+};
+use broadcast::{acknowledge_broadcast, send_and_wait};
+
+mod changes;
+pub use changes::{
+  emit_cache_changes,
+  StateChangedPayload
+};
+
+mod close;
+pub use close::{
+  close_when_empty,
+  confirm_close,
+  is_dirty,
+  remove_clean_window_backups,
+  request_close,
+  should_confirm_close,
+  CloseRequestedPayload,
+  ConfirmClose,
+  ConfirmCloseSettings,
+  WindowsCloseState
+};
+
+mod deep_link;
+pub use deep_link::parse_deep_link;
+
+mod diagnostics;
+pub use diagnostics::{
+  log_error,
+  report_window_error,
+  write_lock,
+  Diagnostics,
+  RecordedError,
+  StorageDiagnostics,
+  WindowErrorPayload
+};
+
+mod dialog;
+pub use dialog::{
+  DialogFilter,
+  PickOptions
+};
+use dialog::pick_paths;
+
+mod group;
+pub use group::WindowInfo;
+
+mod index;
+pub use index::FolderIndex;
+use index::FolderLookup;
+
+mod label;
+pub use label::{
+  sanitize_label,
+  validate_label,
+  LabelStrategy,
+  WindowLabels
+};
+use label::{new_window_label, validate_new_label};
+
+mod ready;
+pub use ready::{
+  clear_ready_queue,
+  handle_window_ready,
+  WindowReadyPayload,
+  WindowsReadyQueue
+};
+use ready::emit_when_ready;
+
+mod metrics;
+pub use metrics::{
+  record_page_load,
+  LatencyStats,
+  StartupMetrics,
+  WindowStartupMetrics
+};
+use metrics::record_window_created;
+
+mod permission;
+pub use permission::{
+  check_open_permissions,
+  check_own_window,
+  check_permission,
+  check_window_permission,
+  PermissionSettings,
+  WindowPermission
+};
+
+mod stack;
+pub use stack::{
+  SessionStack,
+  WindowStackEntry,
+  WindowsStackState
+};
+use stack::{restore_stacking_order, save_session_stack};
+
+mod startup;
+pub use startup::{
+  clear_running_sentinel,
+  StartupInfo,
+  WindowsStartupInfo
+};
+
+mod storage;
+pub use storage::{migrate_legacy_files, StorageCipher, StorageData, StorageFile, StorageStatus};
+
+mod quit;
+pub use quit::{
+  acknowledge_quit,
+  is_graceful_exit_enabled,
+  remove_quit_window,
+  request_quit,
+  BeforeQuitPayload,
+  GracefulExitSettings,
+  WindowsQuitState
+};
+
+mod magnetism;
+pub use magnetism::{get_magnetic_position, handle_window_moved, MagnetismSettings};
+
+mod monitor;
+pub use monitor::MonitorInfo;
+use monitor::{get_monitors, get_window_monitor_info};
+
+mod snap;
+pub use snap::{
+  SnapRegion,
+  TileLayout
+};
+
+mod snapshot;
+pub use snapshot::StateSnapshot;
+use snapshot::{export_state, import_state};
+
+mod splash;
+pub use splash::{
+  close_splash,
+  show_splash,
+  SplashSettings,
+  SplashState,
+  SPLASH_WINDOW_LABEL
+};
+
+mod trust;
+pub use trust::{
+  emit_trust_request,
+  TrustRequestPayload,
+  TrustSettings,
+  TrustedFolders,
+  WindowsTrustCache
+};
+use trust::{is_folder_trusted, request_trust};
+
+mod expand;
+pub use expand::FolderExpansion;
+use expand::expand_openables;
+
+mod filter;
+pub use filter::{WindowsOpenFilter, WindowsOpenFilterHook};
+use filter::filter_paths_to_open;
+
+mod missing;
+pub use missing::{FileMissingPayload, MissingFile};
+use missing::{report_missing_files, validate_open_files};
+
+mod routing;
+pub use routing::{OpenRoute, RoutedFilesPayload, RoutingRule};
+use routing::route_paths;
+
+mod queue;
+pub use queue::{queue_open, WindowsOpenQueue};
+
+mod route;
+pub use route::route_url;
+use route::{navigate_to_route, open_route};
+
+mod singleton;
+pub use singleton::{find_singleton_window, SingletonPayload};
+use singleton::open_singleton_window;
+
+mod screenshot;
+pub use screenshot::{Screenshot, SCREENSHOT_TIMEOUT};
+use screenshot::{screenshot_from_reply, screenshot_window};
+
+mod switcher;
+pub use switcher::{SwitcherEntry, THUMBNAIL_TIMEOUT};
+use switcher::get_switcher_entries;
+
+mod context_menu;
+pub use context_menu::{
+  clear_context_menu,
+  ContextMenuAction,
+  ContextMenuEntry,
+  ContextMenuPayload,
+  ContextMenuSelection,
+  WindowsContextMenus
+};
+use context_menu::{reopen_closed_window, select_context_menu_entry, show_context_menu};
+
+mod navigation;
+pub use navigation::{handle_before_navigate, BeforeNavigatePayload};
+
+mod zoom;
+pub use zoom::{handle_zoom_menu_event, restore_zoom, ZoomSettings};
+use zoom::{set_zoom, zoom_in, zoom_out, zoom_reset};
+
+mod exclude;
+pub use exclude::is_excluded;
+
+mod adopt;
+pub use adopt::{handle_window_created, is_managed, WindowsCreating};
+use adopt::adopt_window;
+
+mod retry;
+pub use retry::CreateRetrySettings;
+use retry::create_with_retry;
+
+mod outcome;
+pub use outcome::OpenOutcome;
+use outcome::open_with_outcome;
+
+mod idempotency;
+pub use idempotency::{OpenedRequest, WindowsOpenRequests, REQUEST_ID_TTL};
+use idempotency::{find_opened_request, record_opened_request};
+
+mod subscription;
+pub use subscription::{clear_subscriptions, emit_to_subscribers, WindowsSubscriptions};
+use subscription::{subscribe, subscribers, unsubscribe};
+
+mod sticky;
+pub use sticky::{
+  clear_sticky_deliveries,
+  emit_sticky,
+  StickyEvent,
+  WindowsStickyEvents
+};
+use sticky::replay_sticky_events;
+
+mod telemetry;
+pub use telemetry::{
+  OpenStats,
+  WindowsTelemetry,
+  WindowsTelemetryHook
+};
+use telemetry::report_open;
+
+mod trace;
+use trace::OpenTrace;
+
+mod theme;
+pub use theme::{
+  handle_system_theme_changed,
+  SystemTheme,
+  ThemeChangedPayload
+};
+
+mod watcher;
+pub use watcher::{
+  unwatch_folder,
+  watch_folder,
+  FolderChangedPayload,
+  FolderWatchers,
+  RenamedEntry,
+  WatchSettings
+};
 
 mod window;
 pub use window::{
   EmptyWindowBackupInfo,
   FolderBackupInfo,
+  FolderPreferences,
+  handle_window_bounds_changed,
   LastActiveWindow,
+  LogicalWindowBounds,
+  ReadyState,
   RecentPath,
   RecentFile,
   RecentFolder,
+  RecentlyOpened,
+  RecentsChangedPayload,
   WindowConfiguration,
+  WebviewOptions,
+  WindowMode,
+  WindowsBackup,
+  WindowsState,
   WindowsStateCache,
   WindowsBackupCache,
   WindowsRecentsCache,
@@ -39,18 +350,27 @@ pub use window::{
   WindowStateTrait,
   WindowTrait
 };
+use window::{get_window_display, normalize_path};
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 
 use crate::event::{
+  WINDOW_ADD_FOLDERS_EVENT,
+  WINDOW_CREATED_EVENT,
   WINDOW_OPEN_FILES_EVENT,
-  WINDOW_ADD_FOLDERS_EVENT
+  WINDOW_OPEN_FILES_PROGRESS_EVENT,
+  WINDOW_CLOSE_FILE_EVENT,
+  WINDOW_CLOSE_FOLDER_EVENT
 };
 
-static COUNTER: AtomicUsize = AtomicUsize::new(1);
+/// Offset in logical pixels applied to duplicated windows.
+const DUPLICATE_WINDOW_OFFSET: f64 = 30.0;
+
+/// Global of the frontend holding the init payload of its window.
+pub const INIT_PAYLOAD_GLOBAL: &str = "__WINDOWS_INIT__";
 
 /// Open window request source.
-#[derive(Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum OpenContext {
   /// Opening through the API.
   Api,
@@ -69,6 +389,12 @@ pub enum OpenContext {
 
   /// Opening from the OS's UI.
   Desktop,
+
+  /// Opening by dropping resources on a window.
+  Drop,
+
+  /// Opening from a deep link (custom uri scheme) activation.
+  Url,
 }
 
 impl Default for OpenContext {
@@ -77,10 +403,55 @@ impl Default for OpenContext {
   }
 }
 
+/// Where resources opened through the convenience methods go.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum OpenBehavior {
+  /// Let the settings decide.
+  Default,
+  /// Always open in a new window.
+  NewWindow,
+  /// Always reuse the last active window.
+  ReuseWindow,
+}
+
+impl Default for OpenBehavior {
+  fn default() -> Self {
+    OpenBehavior::Default
+  }
+}
+
+/// How windows receiving resources are activated.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ActivationPolicy {
+  /// Bring the window to front and focus it.
+  Focus,
+  /// Leave focus where it is and request user attention instead, e.g. flash the taskbar.
+  Background,
+}
+
+impl Default for ActivationPolicy {
+  fn default() -> Self {
+    ActivationPolicy::Focus
+  }
+}
+
+impl OpenBehavior {
+  /// Build the configuration to open resources with this behavior.
+  fn configuration(&self, uris_to_open: Vec<WindowOpenable>, context: OpenContext) -> OpenConfiguration {
+    OpenConfiguration {
+      uris_to_open: Some(uris_to_open),
+      context,
+      force_new_window: *self == OpenBehavior::NewWindow,
+      force_reuse_window: *self == OpenBehavior::ReuseWindow,
+      ..Default::default()
+    }
+  }
+}
+
 /// Data type for openable resources.
 /// 
 /// Holds PathBufs for folder or file uri.
-#[derive(Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct WindowOpenable {
   pub folder: Option<PathBuf>,
   pub file: Option<PathBuf>,
@@ -95,13 +466,13 @@ impl WindowOpenable {
 /// Date type for files to be opened.
 /// 
 /// Holds vectors of PathBufs for files to open, diff, or wait.
-#[derive(Clone, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct FilesToOpen {
   pub files_to_open_or_create: Vec<PathBuf>
 }
 
 /// Data type for file type
-#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum FileType {
   Directory,
   File,
@@ -116,7 +487,7 @@ impl Default for FileType {
 /// Data type for info about path to open.
 /// 
 /// Contains info about resources available, backup, and state.
-#[derive(Clone, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct PathToOpen {
   pub folder: Option<PathBuf>,
   pub file: Option<PathBuf>,
@@ -125,6 +496,69 @@ pub struct PathToOpen {
   pub exists: bool,
   pub window: Option<String>,
   pub label: Option<String>,
+  pub url: Option<Url>,
+  /// Window of a file from the routing rules, `None` follows the configuration.
+  #[serde(skip)]
+  pub route: Option<OpenRoute>,
+}
+
+impl PathToOpen {
+  /// Get the folder or file path.
+  pub fn path(&self) -> Option<&PathBuf> {
+    self.folder.as_ref().or_else(|| self.file.as_ref())
+  }
+
+  /// Re-resolve `exists` and `path_type` against the filesystem.
+  ///
+  /// Moves the path between `folder` and `file` if it changed type since it was resolved.
+  /// Missing paths keep their last known type.
+  pub fn refresh(&mut self) {
+    let path = match self.path() {
+      Some(path) => path.clone(),
+      None => return,
+    };
+
+    if path.is_dir() {
+      self.folder = Some(path);
+      self.file = None;
+      self.path_type = FileType::Directory;
+      self.exists = true;
+    } else if path.exists() {
+      self.folder = None;
+      self.file = Some(path);
+      self.path_type = FileType::File;
+      self.exists = true;
+    } else {
+      self.exists = false;
+    }
+  }
+
+  /// Create the missing folder, or the missing file and its parent directories.
+  pub fn create_if_missing(&mut self) -> Result<()> {
+    if self.exists {
+      return Ok(());
+    }
+
+    let path = match self.path() {
+      Some(path) => path.clone(),
+      None => return Ok(()),
+    };
+
+    match self.path_type {
+      FileType::Directory => create_dir_all(&path)?,
+      FileType::File => {
+        if let Some(parent) = path.parent() {
+          create_dir_all(parent)?;
+        }
+
+        fs::OpenOptions::new().write(true).create(true).open(&path)?;
+      },
+    }
+
+    self.refresh();
+
+    Ok(())
+  }
 }
 
 /// Data type for open options.
@@ -136,24 +570,28 @@ struct OpenOptions {
   pub open_files_in_new_window: bool,
 }
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct WindowSize {
   pub width: f64,
   pub height: f64,
 }
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct WindowPosition {
   pub x: f64,
   pub y: f64,
 }
 
 /// Configuration for window creation used by api.
-#[derive(Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct OpenConfiguration {
+  /// Label of the first new window the request opens, further windows get generated labels.
   pub label: Option<String>,
   pub url: Option<WindowUrl>,
   pub uris_to_open: Option<Vec<WindowOpenable>>,
+  /// External urls to open in managed windows, must match `url_allowlist` setting.
+  #[serde(default)]
+  pub urls_to_open: Vec<Url>,
   pub context_window_label: Option<String>,
   pub context: OpenContext,
   pub force_new_window: bool,
@@ -163,10 +601,52 @@ pub struct OpenConfiguration {
   pub prefer_new_window: bool,
   pub initial_startup: bool,
   pub diff_mode: bool,
+  /// Create missing files and folders, including parent directories, instead of opening them as missing.
+  #[serde(default)]
+  pub create_if_missing: bool,
+  /// Collect the decisions made while opening, returned with the opened window.
+  #[serde(default)]
+  pub explain: bool,
+  /// Activation of windows receiving the resources.
+  #[serde(default)]
+  pub activation: ActivationPolicy,
+  /// Open the matching files of requested folders instead of the folders.
+  #[serde(default)]
+  pub expand_folders: Option<FolderExpansion>,
+  /// Id of the request, repeating it shortly after it opened returns the first result instead.
+  #[serde(default)]
+  pub request_id: Option<String>,
+  /// Data for the frontend of new windows, set as `window.__WINDOWS_INIT__` before the page loads.
+  #[serde(default)]
+  pub init_payload: Option<JsonValue>,
+}
+
+impl OpenConfiguration {
+  /// Get the configuration with the resources it resolved to.
+  ///
+  /// Restored, refreshed and created paths replace the requested ones.
+  fn resolved(&self, paths_to_open: &[PathToOpen]) -> Self {
+    let uris_to_open = paths_to_open
+      .iter()
+      .filter(|path_to_open| path_to_open.folder.is_some() || path_to_open.file.is_some())
+      .map(|path_to_open| WindowOpenable::new(path_to_open.file.clone(), path_to_open.folder.clone()))
+      .collect();
+
+    let urls_to_open = paths_to_open
+      .iter()
+      .filter_map(|path_to_open| path_to_open.url.clone())
+      .collect();
+
+    Self {
+      uris_to_open: Some(uris_to_open),
+      urls_to_open,
+      ..self.clone()
+    }
+  }
 }
 
 /// Options for window creation used by api.
-#[derive(Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct WindowOptions {
   pub label: Option<String>,
   pub url: Option<WindowUrl>,
@@ -195,23 +675,196 @@ pub struct WindowOptions {
   pub files_to_open: FilesToOpen,
   pub window_to_use: Option<String>,
   pub folder: Option<PathBuf>,
+  /// Activation of the window receiving the resources.
+  #[serde(default)]
+  pub activation: ActivationPolicy,
+  /// Webview user agent, browser args and data directory.
+  #[serde(flatten)]
+  pub webview: WebviewOptions,
+  /// Key of a window open at most once, e.g. `settings`, opening it again activates the open window.
+  #[serde(default)]
+  pub singleton_key: Option<String>,
+  /// Navigate an open singleton window to `url` when it's opened again.
+  #[serde(default)]
+  pub update_singleton: bool,
+  /// Sent to the singleton window with the singleton event, each time it's opened.
+  #[serde(default)]
+  pub singleton_payload: Option<JsonValue>,
+  /// Route of the app page the window shows, see [`WindowsAPI::open_route`].
+  #[serde(default)]
+  pub app_route: Option<String>,
+  /// Data for the frontend of a new window, set as `window.__WINDOWS_INIT__` before the page loads.
+  ///
+  /// Ignored for windows on external urls.
+  #[serde(default)]
+  pub init_payload: Option<JsonValue>,
+  /// Source of the open request, delivered with resources sent to an existing window.
+  #[serde(default)]
+  pub context: OpenContext,
+  /// Home directory of the window, the user home if not set.
+  #[serde(default)]
+  pub home_dir: Option<PathBuf>,
+  /// Temporary directory of the window, the system temp directory if not set.
+  #[serde(default)]
+  pub tmp_dir: Option<PathBuf>,
+  /// Directory the webview keeps its profile in, the app local data directory if not set.
+  ///
+  /// Used as the webview data directory unless `data_directory` is set.
+  #[serde(default)]
+  pub user_data_dir: Option<PathBuf>,
+}
+
+/// Partial [`WindowOptions`] applied to a live window.
+///
+/// Only the set fields are changed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WindowOptionsUpdate {
+  pub always_on_top: Option<bool>,
+  pub center: Option<bool>,
+  pub decorations: Option<bool>,
+  pub focus: Option<bool>,
+  pub fullscreen: Option<bool>,
+  pub inner_size: Option<WindowSize>,
+  pub max_inner_size: Option<WindowSize>,
+  pub maximized: Option<bool>,
+  pub min_inner_size: Option<WindowSize>,
+  pub position: Option<WindowPosition>,
+  pub resizable: Option<bool>,
+  pub skip_taskbar: Option<bool>,
+  pub theme: Option<Theme>,
+  pub title: Option<String>,
+  pub visible: Option<bool>,
+}
+
+/// Partial [`WindowState`] applied to a cached window state.
+///
+/// Only the set fields are changed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WindowStatePatch {
+  /// Folder open in the window, must be an existing directory.
+  pub folder: Option<PathBuf>,
+  /// Data owned by the frontend.
+  pub user_data: Option<JsonValue>,
+  /// Mode of the window, applied to the window if it is open.
+  pub mode: Option<WindowMode>,
+}
+
+/// Path taken to open resources.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum OpenPath {
+  /// A new window was created.
+  NewWindow,
+  /// An existing window was used.
+  ExistingWindow,
+  /// The `max_windows` limit was reached, the least recently focused window was reused.
+  MaxWindowsReached,
+}
+
+/// Window used to open resources.
+pub struct OpenedWindow<R: Runtime> {
+  pub window: Window<R>,
+  pub path: OpenPath,
+  /// Effective configuration of the request, with resolved resources.
+  pub configuration: Option<OpenConfiguration>,
+  /// Decisions made while opening, only in explain mode.
+  pub trace: Option<Vec<String>>,
+}
+
+impl<R: Runtime> OpenedWindow<R> {
+  fn new(window: Window<R>, path: OpenPath) -> Self {
+    Self { window, path, configuration: None, trace: None }
+  }
+
+  fn with_configuration(self, configuration: OpenConfiguration) -> Self {
+    Self { configuration: Some(configuration), ..self }
+  }
+
+  fn with_trace(self, trace: Option<Vec<String>>) -> Self {
+    Self { trace, ..self }
+  }
+
+  /// Get serializable info about the opened window.
+  pub fn info(&self) -> OpenedWindowInfo {
+    let state = match self.window.state::<WindowsStateCache>().0.read() {
+      Ok(cache) => cache.get_item(self.window.label()).unwrap_or_default(),
+      Err(_e) => WindowState::default(),
+    };
+
+    OpenedWindowInfo {
+      label: self.window.label().to_string(),
+      id: state.id,
+      display: state.display,
+      path: self.path,
+      configuration: self.configuration.clone(),
+      trace: self.trace.clone(),
+      queue_position: None,
+    }
+  }
+}
+
+/// Serializable info about the window used to open resources.
+#[derive(Clone, Debug, Serialize)]
+pub struct OpenedWindowInfo {
+  pub label: String,
+  /// Stable id of the window.
+  pub id: u32,
+  /// Index of the monitor the window is on.
+  pub display: u32,
+  pub path: OpenPath,
+  pub configuration: Option<OpenConfiguration>,
+  /// Decisions made while opening, only in explain mode.
+  pub trace: Option<Vec<String>>,
+  /// Requests ahead of this one in the open queue, `None` when opened outside of it.
+  pub queue_position: Option<usize>,
+}
+
+impl OpenedWindowInfo {
+  /// Set the requests that were ahead of this one in the open queue.
+  pub fn with_queue_position(self, queue_position: usize) -> Self {
+    Self { queue_position: Some(queue_position), ..self }
+  }
 }
 
 // Payloads
-/// Payload for add folder global event.
+/// Payload for add folder event.
 #[derive(Clone, Serialize)]
 pub struct AddFolderPayload {
   pub folders_to_add: Vec<PathToOpen>,
+  /// Source of the open request.
+  pub context: OpenContext,
 }
 
-/// Payload for open files global event.
+/// Payload for open files event.
 #[derive(Clone, Serialize)]
 pub struct OpenFilePayload {
   pub files_to_open_or_create: Vec<PathBuf>,
+  /// Source of the open request.
+  pub context: OpenContext,
+}
+
+/// Payload for open files progress event.
+#[derive(Clone, Serialize)]
+pub struct OpenFilesProgressPayload {
+  /// Number of files sent so far.
+  pub index: usize,
+  pub total: usize,
+}
+
+/// Payload for window created event.
+#[derive(Clone, Serialize)]
+pub struct WindowCreatedPayload {
+  pub label: String,
+  /// Stable id of the window.
+  pub id: u32,
+  pub folder: Option<PathBuf>,
+  /// Folder the frontend writes hot-exit data to.
+  pub backup_path: Option<PathBuf>,
 }
 
 // Managed States
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
 pub enum OpenInNewWindow {
   On,
   Off,
@@ -224,7 +877,7 @@ impl Default for OpenInNewWindow {
   }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
 pub enum RestoreWindows {
   Preserve,
   All,
@@ -239,10 +892,73 @@ impl Default for RestoreWindows {
   }
 }
 
+/// Validation of recents and backups against the filesystem.
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize)]
+pub enum ResourceValidation {
+  /// Don't validate.
+  Off,
+  /// Mark missing entries.
+  Mark,
+  /// Remove missing entries.
+  Remove,
+}
+
+impl Default for ResourceValidation {
+  fn default() -> Self {
+    ResourceValidation::Off
+  }
+}
+
+/// Window reuse per open request source.
+///
+/// Read from `tauri.conf.json > plugins > windows > open_context_policy`.
+/// `On` always opens a new window, `Off` always reuses one, `Default` follows the other settings.
+/// Forcing a new window or reuse in the request still wins.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OpenContextPolicy {
+  pub api: OpenInNewWindow,
+  pub cli: OpenInNewWindow,
+  pub dock: OpenInNewWindow,
+  pub menu: OpenInNewWindow,
+  pub dialog: OpenInNewWindow,
+  pub desktop: OpenInNewWindow,
+  pub drop: OpenInNewWindow,
+  pub url: OpenInNewWindow,
+}
+
+impl OpenContextPolicy {
+  /// Get the policy of an open request source.
+  pub fn get(&self, context: OpenContext) -> &OpenInNewWindow {
+    match context {
+      OpenContext::Api => &self.api,
+      OpenContext::Cli => &self.cli,
+      OpenContext::Dock => &self.dock,
+      OpenContext::Menu => &self.menu,
+      OpenContext::Dialog => &self.dialog,
+      OpenContext::Desktop => &self.desktop,
+      OpenContext::Drop => &self.drop,
+      OpenContext::Url => &self.url,
+    }
+  }
+}
+
+/// Retention policy for cached window states.
+///
+/// States of open windows and the last active window are never pruned.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StateRetention {
+  /// Drop states of windows closed more than this many days ago.
+  pub max_age_days: Option<u64>,
+  /// Drop states of the oldest closed windows beyond this many entries.
+  pub max_entries: Option<usize>,
+}
+
 /// Dimension type for new windows.
 /// 
 /// Flags for new window dimensions.
-#[derive(PartialEq)]
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
 pub enum NewWindowDimensions {
   /// Use system default dimensions for window size.
   Default,
@@ -262,7 +978,8 @@ impl Default for NewWindowDimensions {
   }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct InnerWindowsSettings {
   pub open_files_in_new_window: OpenInNewWindow,
   pub open_folders_in_new_window: OpenInNewWindow,
@@ -274,7 +991,49 @@ pub struct InnerWindowsSettings {
   pub native_tabs: bool,
   pub native_full_screen: bool,
   pub close_when_empty: bool,
+  /// Whether the first click on an inactive window only focuses it.
   pub click_through_inactive: bool,
+  pub max_windows: Option<usize>,
+  /// Window reuse per open request source, overrides `open_files_in_new_window` and `open_folders_in_new_window`.
+  pub open_context_policy: OpenContextPolicy,
+  /// Retries of failed window creation.
+  pub create_retry: CreateRetrySettings,
+  /// Prefix of generated window labels, reserved for them, `windows_` if not set.
+  pub label_prefix: Option<String>,
+  /// Bring windows created by the app under plugin management as they're created.
+  pub auto_adopt: bool,
+  /// Labels of windows the plugin must not manage, a label ending with `*` matches labels starting with the rest.
+  pub exclude: Vec<String>,
+  /// Files sent per `windows://open_files` event, all at once if not set.
+  pub open_files_batch_size: Option<usize>,
+  pub state_retention: StateRetention,
+  /// Validation of recents and backups run in the background on startup.
+  pub validate_resources: ResourceValidation,
+  /// Origins allowed for external urls, e.g. `https://tauri.app`.
+  pub url_allowlist: Vec<String>,
+  pub splash: SplashSettings,
+  pub watch: WatchSettings,
+  /// Step and bounds of window zoom.
+  pub zoom: ZoomSettings,
+  /// Snapping of moved windows to nearby edges, off by default.
+  pub magnetism: MagnetismSettings,
+  pub trust: TrustSettings,
+  pub confirm_close: ConfirmCloseSettings,
+  pub graceful_exit: GracefulExitSettings,
+  /// Saving of caches on an interval, off by default.
+  pub auto_save: AutoSaveSettings,
+  /// Let the app ask before restoring a session that ended unexpectedly.
+  pub prompt_restore_after_crash: bool,
+  /// Broadcast system theme changes to windows following the system theme.
+  pub follow_system_theme: bool,
+  /// Focus the window focused last time after restoring a session.
+  pub restore_focus: bool,
+  /// Commands windows may invoke, all by default.
+  pub permissions: PermissionSettings,
+  /// Hold events to windows until their frontend invokes `window_ready`.
+  pub ready_handshake: bool,
+  /// Window treatment of opened files by glob pattern, the first matching rule wins.
+  pub routing: Vec<RoutingRule>,
 }
 
 /// Managed state for window settings.
@@ -284,54 +1043,89 @@ pub struct InnerWindowsSettings {
 #[derive(Default)]
 pub struct WindowsSettings(pub RwLock<InnerWindowsSettings>);
 
+impl WindowsSettings {
+  /// Create settings from the plugin config.
+  ///
+  /// Falls back to default settings when the config is missing or invalid.
+  pub fn from_config(config: JsonValue) -> Self {
+    if config.is_null() {
+      return WindowsSettings::default();
+    }
+
+    match from_value::<InnerWindowsSettings>(config) {
+      Ok(settings) => WindowsSettings(RwLock::new(settings)),
+      Err(e) => {
+        log_error(&e);
+        WindowsSettings::default()
+      }
+    }
+  }
+}
+
 pub type Result<T> = StdResult<T, Error>;
 
 // Endpoints for creating resourse data structures
 
 /// Create [`PathToOpen`] from [`PathBuf`].
 fn resolve_file_path(path: &PathBuf) -> Option<PathToOpen> {
-  if !path.exists() {
-    return Some(PathToOpen {
-      file: Some(path.clone()),
-      path_type: FileType::File,
-      exists: false,
-      ..Default::default()
-    });
-  } else if path.is_file() {
-    return Some(PathToOpen {
-      file: Some(path.clone()),
-      path_type: FileType::File,
-      exists: true,
-      ..Default::default()
-    });
-  } else if path.is_dir() {
-    return Some(PathToOpen {
-      folder: Some(path.clone()),
-      path_type: FileType::Directory,
-      exists: true,
-      ..Default::default()
-    });
-  }
-
-  return Some(PathToOpen {
+  let mut path_to_open = PathToOpen {
     file: Some(path.clone()),
     path_type: FileType::File,
-    exists: true,
+    exists: false,
     ..Default::default()
-  });
+  };
+
+  path_to_open.refresh();
+
+  Some(path_to_open)
 }
 
-/// Create [`PathToOpen`] from [`WindowOpenable`].
-fn resolve_openable(openable: &WindowOpenable) -> Option<PathToOpen> {
-  let uri: &PathBuf;
+/// Create [`PathToOpen`] for a folder.
+///
+/// Missing folders keep the folder type.
+fn resolve_folder_path(path: &PathBuf) -> PathToOpen {
+  let mut path_to_open = PathToOpen {
+    folder: Some(path.clone()),
+    path_type: FileType::Directory,
+    exists: false,
+    ..Default::default()
+  };
+
+  path_to_open.refresh();
 
-  if openable.folder.is_some() {
-    uri = openable.folder.as_ref().unwrap();
-  } else {
-    uri = openable.file.as_ref().unwrap();
-  }
+  path_to_open
+}
+
+/// Create [`PathToOpen`]s from [`WindowOpenable`].
+///
+/// A folder with a file opens the folder and the file inside it, relative files are joined to the folder.
+/// An empty openable resolves to nothing.
+fn resolve_openable(openable: &WindowOpenable) -> Result<Vec<PathToOpen>> {
+  match (&openable.folder, &openable.file) {
+    (None, None) => Ok(Vec::new()),
+    (Some(folder), None) => Ok(vec![resolve_folder_path(folder)]),
+    (None, Some(file)) => Ok(resolve_file_path(file).into_iter().collect()),
+    (Some(folder), Some(file)) => {
+      let file = match file.is_relative() {
+        true => folder.join(file),
+        false => file.clone(),
+      };
+
+      let escapes_folder = file.components().any(|component| component == Component::ParentDir);
+
+      if escapes_folder || !(file.starts_with(folder) || is_path_in_folder(&file, folder)) {
+        return Err(Error::FileOutsideFolder(
+          file.to_string_lossy().to_string(),
+          folder.to_string_lossy().to_string(),
+        ));
+      }
+
+      let mut paths_to_open = vec![resolve_folder_path(folder)];
+      paths_to_open.extend(resolve_file_path(&file));
 
-  resolve_file_path(uri)
+      Ok(paths_to_open)
+    },
+  }
 }
 
 /// Extracts PathToOpen from uris.
@@ -340,8 +1134,9 @@ fn extract_paths(uris_to_open: &Option<Vec<WindowOpenable>>) -> Vec<PathToOpen>
 
   if let Some(uris) = uris_to_open {
     for path_to_open in uris {
-      if let Some(path) =resolve_openable(path_to_open) {
-        paths_to_open.push(path);
+      match resolve_openable(path_to_open) {
+        Ok(paths) => paths_to_open.extend(paths),
+        Err(e) => log_error(&e),
       }
     }
   }
@@ -349,23 +1144,17 @@ fn extract_paths(uris_to_open: &Option<Vec<WindowOpenable>>) -> Vec<PathToOpen>
   paths_to_open
 }
 
-// Endpoint for creating window ids/labels
-
-/// Create a random number.
-fn get_id() -> usize {
-  return COUNTER.fetch_add(1, Ordering::Relaxed);
-}
-
-/// Create a randon window label.
-/// 
-/// Attaches windows_ to a random number, making sure labels are unique.
-fn new_window_label() -> String {
-  let id = get_id().to_string();
-  let mut label = String::from("windows_");
-
-  label.push_str(&id);
+/// Extracts PathToOpen from uris, routed by the `routing` setting.
+fn extract_routed_paths<'a, R: Runtime, M: Manager<R>>(manager: &'a M, uris_to_open: &Option<Vec<WindowOpenable>>) -> Vec<PathToOpen> {
+  let paths_to_open = extract_paths(uris_to_open);
 
-  return label;
+  match route_paths(manager, &paths_to_open) {
+    Ok(routed_paths) => routed_paths,
+    Err(e) => {
+      log_error(&e);
+      paths_to_open
+    },
+  }
 }
 
 /// Get new window creation options.
@@ -375,147 +1164,174 @@ fn new_window_label() -> String {
 fn should_open_new_window<'a, R: Runtime, M: Manager<R>>(
   manager: &'a M,
   configuration: &OpenConfiguration,
+  trace: &mut OpenTrace,
 ) -> OpenOptions {
-  // let the user settings override how folders are open in a new window or same window unless we are forced
   let window_settings = manager.state::<WindowsSettings>();
 
-  let open_options = match window_settings.0.read() {
-    Ok(settings) => {
-      let mut open_folder_in_new_window = (configuration.prefer_new_window || configuration.force_new_window) && !configuration.force_reuse_window;
-      let mut open_files_in_new_window = false;
+  let [folders, files] = match window_settings.0.read() {
+    Ok(settings) => get_open_decisions(configuration, &settings),
+    Err(_e) => get_open_decisions(configuration, &InnerWindowsSettings::default()),
+  };
 
-      if !configuration.force_new_window
-        && !configuration.force_reuse_window
-        && (settings.open_folders_in_new_window == OpenInNewWindow::On || settings.open_folders_in_new_window == OpenInNewWindow::Off)
-      {
-        open_folder_in_new_window = settings.open_folders_in_new_window == OpenInNewWindow::On
-      }
+  trace.step(|| format!("{:?} request, folders open in a new window: {} ({})", configuration.context, folders.0, folders.1));
+  trace.step(|| format!("{:?} request, files open in a new window: {} ({})", configuration.context, files.0, files.1));
 
-      if configuration.force_new_window || configuration.force_reuse_window {
-        open_files_in_new_window = !!configuration.force_new_window && !configuration.force_reuse_window;
-      }
-      else {
-        // Linux/Windows: by default we open files in the new window unless triggered via DIALOG / MENU context
-        // or from the integrated terminal where we assume the user prefers to open in the current window
-        if configuration.context != OpenContext::Dialog && configuration.context != OpenContext::Menu {
-          open_files_in_new_window = true;
-        }
-        // finally check for overrides of default
-        if settings.open_files_in_new_window == OpenInNewWindow::On || settings.open_files_in_new_window == OpenInNewWindow::Off {
-          open_files_in_new_window = settings.open_files_in_new_window == OpenInNewWindow::On;
-        }
-      }
+  OpenOptions {
+    open_folder_in_new_window: folders.0,
+    open_files_in_new_window: files.0,
+  }
+}
 
-      OpenOptions {
-        open_folder_in_new_window,
-        open_files_in_new_window
-      } 
-    },
-    Err(e) => {
-      OpenOptions {
-        open_folder_in_new_window: (configuration.prefer_new_window || configuration.force_new_window) && !configuration.force_reuse_window,
-        open_files_in_new_window: false
-      }    
+/// Whether to open in a new window and what decided it.
+type OpenDecision = (bool, &'static str);
+
+/// Decide whether folders and files open in a new window.
+///
+/// Precedence is `force_new_window`, `force_reuse_window`, `prefer_new_window`,
+/// the `open_context_policy` of the request source, the new window settings, then the context defaults.
+fn get_open_decisions(configuration: &OpenConfiguration, settings: &InnerWindowsSettings) -> [OpenDecision; 2] {
+  let context_policy = ("open_context_policy", settings.open_context_policy.get(configuration.context));
+
+  // Linux/Windows: by default we open files in the new window unless triggered via DIALOG / MENU / DROP context
+  let open_files_by_default = configuration.context != OpenContext::Dialog
+    && configuration.context != OpenContext::Menu
+    && configuration.context != OpenContext::Drop;
+
+  [
+    open_in_new_window(
+      configuration,
+      &[context_policy, ("open_folders_in_new_window", &settings.open_folders_in_new_window)],
+      false,
+    ),
+    open_in_new_window(
+      configuration,
+      &[context_policy, ("open_files_in_new_window", &settings.open_files_in_new_window)],
+      open_files_by_default,
+    ),
+  ]
+}
+
+/// Decide whether to open in a new window.
+///
+/// The first policy that is not `Default` wins over `default`, forcing and preferring in the configuration win over policies.
+fn open_in_new_window(
+  configuration: &OpenConfiguration,
+  policies: &[(&'static str, &OpenInNewWindow)],
+  default: bool,
+) -> OpenDecision {
+  if configuration.force_new_window {
+    return (true, "force_new_window");
+  }
+
+  if configuration.force_reuse_window {
+    return (false, "force_reuse_window");
+  }
+
+  if configuration.prefer_new_window {
+    return (true, "prefer_new_window");
+  }
+
+  for &(name, policy) in policies {
+    match policy {
+      OpenInNewWindow::On => return (true, name),
+      OpenInNewWindow::Off => return (false, name),
+      OpenInNewWindow::Default => {},
     }
-  };
+  }
 
-  open_options
+  (default, "context default")
 }
 
 // Endpoints for get window by resource type
 
-/// Check if folder uri is open in an existing window.
-/// 
-/// Uses [`WindowsStateCache`] to find if a window containing the current folder uri.
-fn find_window_on_folder<'a, R: Runtime, M: Manager<R>>(
-  manager: &'a M,
-  folder: Option<&PathBuf>,
-) -> Option<String> {
-  if let Some(cmp_uri) = folder {
-    let windows_state_cache = manager.state::<WindowsStateCache>();
-  
-    let res = match windows_state_cache.0.read() {
-      Ok(cache) => {
-        // Then go with single folder windows that are parent of the provided file path
-        match cache.state().opened_windows
-          .iter()
-          .find(|(_label, window_state)| {
-            if let Some(uri) = &window_state.folder {
-              cmp_uri.eq(uri)
-            }
-            else {
-              false
-            }
-          }) {
-            Some((label, _window_state)) => Some(label.to_string()),
-            None => None
-          }        
-      },
-      Err(e) => {
-        None
-      }
-    };
+/// Check if a path is a folder or inside it.
+///
+/// Paths are compared in their [`normalize_path`] form, so trailing separators, `.` components
+/// and symlinks don't hide a match.
+fn is_path_in_folder(path: &Path, folder: &Path) -> bool {
+  normalize_path(path).starts_with(normalize_path(folder))
+}
 
-    res
-  }
-  else {
-    None
-  }
+// Endpoints for getting managed state
+
+/// Get window settings.
+///
+/// Gets window settings from managed state.
+fn get_window_config<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> State<'_, WindowsSettings> {
+  return manager.state::<WindowsSettings>();
 }
 
-/// Check if file uri is open in an existing window.
+/// Check if an external url can be opened in a managed window.
 ///
-/// Uses [`WindowsStateCache`] to find if a window containing the current file uri.
-fn find_window_on_file<'a, R: Runtime, M: Manager<R>>(
-  manager: &'a M,
-  file: &PathBuf,
-) -> Option<Window<R>> {
-  let windows_state_cache = manager.state::<WindowsStateCache>();
+/// Compares the url origin against the `url_allowlist` setting.
+fn is_url_allowed<'a, R: Runtime, M: Manager<R>>(manager: &'a M, url: &Url) -> bool {
+  let window_settings = manager.state::<WindowsSettings>();
 
-  let res = match windows_state_cache.0.read() {
-    Ok(cache) => {
-      // Then go with single folder windows that are parent of the provided file path
-      let single_folder_windows_on_file_path: Vec<(&String, &WindowState)> = cache.state().opened_windows
-      .iter()
-      .filter(|(_label, window_state)| {
-        if let Some(parent) = window_state.folder.as_ref() {
-          // check if equal or parent
-          file.eq(parent) || file.starts_with(parent)
-        }
-        else {
-          false
-        }
-      })
-      .collect();
+  let res = match window_settings.0.read() {
+    Ok(settings) => {
+      let origin = url.origin().ascii_serialization();
 
-      if single_folder_windows_on_file_path.len() > 0 {
-        manager.get_window(&single_folder_windows_on_file_path[0].0)
-      }
-      else {
-        None
-      }
+      settings.url_allowlist
+        .iter()
+        .any(|allowed| allowed.trim_end_matches('/') == origin)
     },
-    Err(e) => {
-      None
-    }
+    Err(_e) => false
   };
 
   res
 }
 
-// Endpoints for getting managed state
+// Endpoints for resource paths
 
-/// Get window settings.
+
+/// Get paths of the windows open when the app was relaunched.
 ///
-/// Gets window settings from managed state.
-fn get_window_config<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> State<'_, WindowsSettings> {
-  return manager.state::<WindowsSettings>();
-}
+/// Restores every window regardless of the `restore_windows` setting,
+/// ordered by focus time so the last active window ends up focused.
+fn get_paths_from_restart<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Option<Vec<PathToOpen>> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
 
-// Endpoints for resource paths
+  let cache = windows_state_cache.0.read().ok()?;
+
+  if !cache.state().was_restarted {
+    return None;
+  }
+
+  let mut states = cache.state().restart_windows
+    .iter()
+    .filter_map(|label| cache.get_item(label))
+    .collect::<Vec<WindowState>>();
+
+  states.sort_by(|a, b| a.last_focus_time.cmp(&b.last_focus_time));
+
+  let paths_to_open = states
+    .into_iter()
+    .filter_map(|state| {
+      if let Some(folder) = &state.folder {
+        Some(resolve_folder_path(folder))
+      } else if let Some(url) = &state.configuration.url {
+        Some(PathToOpen {
+          url: Some(url.clone()),
+          ..Default::default()
+        })
+      } else {
+        Some(PathToOpen {
+          backup_path: state.backup_path.clone(),
+          ..Default::default()
+        })
+      }
+    })
+    .collect();
 
+  Some(paths_to_open)
+}
 
 fn get_paths_from_last_session<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Vec<PathToOpen> {
+  // Relaunch restores exactly the same windows
+  if let Some(paths_to_open) = get_paths_from_restart(manager) {
+    return paths_to_open;
+  }
+
   let window_settings = manager.state::<WindowsSettings>();
 
   let paths = match window_settings.0.read() {
@@ -561,14 +1377,16 @@ fn get_paths_from_last_session<'a, R: Runtime, M: Manager<R>>(manager: &'a M) ->
 
                 if let Some(state) = window_state {
                   // Folders
-                  if state.folder.is_some() {
-                    let path_to_open: Option<PathToOpen> = resolve_openable(&WindowOpenable {
-                      folder: state.folder.clone(),
-                      ..Default::default()
-                    });
-    
-                    if path_to_open.is_some() {
-                      paths_to_open.push(path_to_open.unwrap());
+                  if let Some(folder) = &state.folder {
+                    paths_to_open.push(resolve_folder_path(folder));
+                  }
+                  // External url, restored without file/folder semantics
+                  else if let Some(url) = &state.configuration.url {
+                    if settings.restore_windows != RestoreWindows::Folders && is_url_allowed(manager, url) {
+                      paths_to_open.push(PathToOpen {
+                        url: Some(url.clone()),
+                        ..Default::default()
+                      });
                     }
                   }
                   // Empty window, potentially editors open to be restored
@@ -600,6 +1418,7 @@ fn get_paths_from_last_session<'a, R: Runtime, M: Manager<R>>(manager: &'a M) ->
   paths
 }
 
+#[cfg(feature = "backups")]
 fn get_empty_window_backup_paths<'a, R: Runtime, M: Manager<R>>(
   manager: &'a M,
 ) -> Vec<EmptyWindowBackupInfo> {
@@ -617,9 +1436,17 @@ fn get_empty_window_backup_paths<'a, R: Runtime, M: Manager<R>>(
   empty_windows
 }
 
+#[cfg(not(feature = "backups"))]
+fn get_empty_window_backup_paths<'a, R: Runtime, M: Manager<R>>(
+  _manager: &'a M,
+) -> Vec<EmptyWindowBackupInfo> {
+  Vec::new()
+}
+
 fn get_paths_to_open<'a, R: Runtime, M: Manager<R>>(
   manager: &'a M,
   uris_to_open: &Option<Vec<WindowOpenable>>,
+  urls_to_open: &[Url],
   force_empty_window: &bool,
   initial_startup: &bool,
 ) -> Vec<PathToOpen> {
@@ -627,14 +1454,21 @@ fn get_paths_to_open<'a, R: Runtime, M: Manager<R>>(
   let mut restored_windows = false;
 
   // Extract paths: from API
-  if uris_to_open.is_some() {
-    if uris_to_open.as_ref().unwrap().len() > 0 {
-      paths_to_open = extract_paths(uris_to_open);
+  if uris_to_open.is_some() || !urls_to_open.is_empty() {
+    if uris_to_open.is_some() && uris_to_open.as_ref().unwrap().len() > 0 {
+      paths_to_open = extract_routed_paths(manager, uris_to_open);
     }
+
+    paths_to_open.extend(urls_to_open.iter().map(|url| PathToOpen {
+      url: Some(url.clone()),
+      ..Default::default()
+    }));
   }
   // Check for force empty
   else if *force_empty_window {
-    paths_to_open = Vec::new();
+    paths_to_open = vec![PathToOpen {
+      ..Default::default()
+    }];
   }
   // Extract paths: from previous session
   else {
@@ -665,27 +1499,93 @@ fn get_paths_to_open<'a, R: Runtime, M: Manager<R>>(
 }
 
 // Endpoints for retriving stateful windows
+/// Get the most recently focused open window, the focused one while the app is active.
 fn get_focused_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Option<Window<R>> {
+  get_focus_history(manager)
+    .first()
+    .and_then(|label| manager.get_window(label))
+}
+
+/// Get labels of open windows by focus order, most recently focused first.
+fn get_focus_history<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Vec<String> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let res = match windows_state_cache.0.read() {
+    Ok(cache) => {
+      cache.focus_history()
+        .iter()
+        .filter(|label| manager.get_window(label).is_some())
+        .cloned()
+        .collect()
+    },
+    Err(_e) => Vec::new()
+  };
+
+  res
+}
+
+/// Get the directory where opening resources should start.
+///
+/// Picks the folder of the last active window, else the most recent folder, else the home directory.
+fn get_default_open_directory<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Option<PathBuf> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let window_folder = get_focus_history(manager)
+    .first()
+    .and_then(|label| {
+      let cache = windows_state_cache.0.read().ok()?;
+      let state = cache.get_item(label)?;
+
+      state.folder.or(state.configuration.folder)
+    })
+    .filter(|folder| folder.is_dir());
+
+  if window_folder.is_some() {
+    return window_folder;
+  }
+
+  get_recent_folder(manager).or_else(home_dir)
+}
+
+/// Get the most recently opened folder that still exists.
+#[cfg(feature = "recents")]
+fn get_recent_folder<R: Runtime, M: Manager<R>>(manager: &M) -> Option<PathBuf> {
+  let windows_recents_cache = manager.state::<WindowsRecentsCache>();
+
+  let recent_folder = match windows_recents_cache.0.read() {
+    Ok(cache) => {
+      cache.recents().folders
+        .iter()
+        .rev()
+        .map(|recent| recent.folder.clone())
+        .find(|folder| folder.is_dir())
+    },
+    Err(_e) => None
+  };
+
+  recent_folder
+}
+
+#[cfg(not(feature = "recents"))]
+fn get_recent_folder<R: Runtime, M: Manager<R>>(_manager: &M) -> Option<PathBuf> {
   None
 }
 
 fn get_last_active_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Option<Window<R>> {
+  if let Some(label) = get_focus_history(manager).first() {
+    return manager.get_window(label);
+  }
+
   let windows_state_cache = manager.state::<WindowsStateCache>();
 
   let res = match windows_state_cache.0.read() {
     Ok(cache) => {
-      let last_focused_date = cache.state().opened_windows
-      .iter()
-      .map(|(_label, window)| window.last_focus_time)
-      .max()
-      .unwrap_or(Duration::new(0, 0));
-  
       let state = cache.state().opened_windows
-        .iter()
-        .find(|(_label, window_state)| window_state.last_focus_time == last_focused_date);
+        .keys()
+        .max_by_key(|label| cache.last_focus_time(label));
     
-      if let Some(s) = state {
-        manager.get_window(&s.0)
+      if let Some(label) = state {
+        manager.get_window(label)
       }
       else {
         None
@@ -699,51 +1599,197 @@ fn get_last_active_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Opti
   res
 }
 
+/// Activate a window receiving resources.
+fn activate_window<R: Runtime>(window: &Window<R>, activation: ActivationPolicy) -> Result<()> {
+  match activation {
+    ActivationPolicy::Focus => window.set_focus()?,
+    ActivationPolicy::Background => window.request_user_attention(Some(UserAttentionType::Informational))?,
+  };
+
+  Ok(())
+}
+
+/// Open or close the devtools of a window.
+#[cfg(any(debug_assertions, feature = "devtools"))]
+fn toggle_devtools<R: Runtime>(window: &Window<R>, open: bool) -> Result<()> {
+  match open {
+    true => window.open_devtools(),
+    false => window.close_devtools(),
+  };
+
+  Ok(())
+}
+
+/// Release builds only have devtools with the `devtools` feature.
+#[cfg(not(any(debug_assertions, feature = "devtools")))]
+fn toggle_devtools<R: Runtime>(_window: &Window<R>, _open: bool) -> Result<()> {
+  Err(Error::FeatureDisabled("devtools".to_string()))
+}
+
 // Endpoints for adding resources to windows
 fn open_files_in_existing_window<'a, R: Runtime>(
-  _configuration: &OpenConfiguration,
+  configuration: &OpenConfiguration,
   window: &Window<R>,
   files_to_open: &FilesToOpen,
 ) -> Result<()> {
-  window.set_focus().map_err(|e| Error::Tauri(e))?; // make sure window has focus
+  activate_window(window, configuration.activation)?; // make sure window has focus, or asks for it
 
-  match to_string(&OpenFilePayload {
-    files_to_open_or_create: files_to_open.files_to_open_or_create.clone(),
-  }) {
-    Ok(serialized_payload) => {
-      window.trigger_global(WINDOW_OPEN_FILES_EVENT, Some(serialized_payload));
-      Ok(())
-    },
-    Err(e) => Err(Error::SerdeJson(e))
+  let files = &files_to_open.files_to_open_or_create;
+
+  let batch_size = match window.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.open_files_batch_size.filter(|size| *size > 0),
+    Err(_e) => None,
+  };
+
+  // Large opens are sent in chunks so the frontend can show progress
+  let batch_size = match batch_size {
+    Some(size) if files.len() > size => size,
+    _ => files.len().max(1),
+  };
+
+  let is_batched = batch_size < files.len();
+
+  // Opens without files still get an event
+  let chunks = match files.is_empty() {
+    true => vec![&files[..]],
+    false => files.chunks(batch_size).collect::<Vec<&[PathBuf]>>(),
+  };
+
+  for (chunk_index, chunk) in chunks.into_iter().enumerate() {
+    emit_when_ready(window, WINDOW_OPEN_FILES_EVENT, OpenFilePayload {
+      files_to_open_or_create: chunk.to_vec(),
+      context: configuration.context,
+    })?;
+
+    if is_batched {
+      emit_when_ready(window, WINDOW_OPEN_FILES_PROGRESS_EVENT, OpenFilesProgressPayload {
+        index: chunk_index * batch_size + chunk.len(),
+        total: files.len(),
+      })?;
+    }
   }
+
+  Ok(())
 }
 
 fn add_folders_to_existing_window<'a, R: Runtime>(
   window: &Window<R>,
   folders_to_add: &Vec<PathToOpen>,
+  activation: ActivationPolicy,
+  context: OpenContext,
 ) -> Result<()> {
-  window.set_focus().map_err(|e| Error::Tauri(e))?; // make sure window has focus
+  activate_window(window, activation)?; // make sure window has focus, or asks for it
 
-  match to_string(&AddFolderPayload {
+  emit_when_ready(window, WINDOW_ADD_FOLDERS_EVENT, AddFolderPayload {
     folders_to_add: folders_to_add.clone(),
-  }) {
-    Ok(serialized_payload) => {
-      window.trigger_global(WINDOW_ADD_FOLDERS_EVENT, Some(serialized_payload));
-      Ok(())
+    context,
+  })?;
+
+  Ok(())
+}
+
+/// Get state cached for the resources of a window.
+///
+/// Looks for a cached window state with the same folder or url.
+fn get_cached_state<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  configuration: &WindowConfiguration,
+) -> Option<WindowState> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let res = match windows_state_cache.0.read() {
+    Ok(cache) => {
+      cache.state().opened_windows
+        .values()
+        .find(|window_state| {
+          let cached = &window_state.configuration;
+
+          if configuration.folder.is_some() {
+            cached.folder == configuration.folder
+          } else if configuration.url.is_some() {
+            cached.url == configuration.url
+          } else {
+            false
+          }
+        })
+        .cloned()
+    },
+    Err(_e) => None
+  };
+
+  res
+}
+
+/// Get the preferences of the last window a folder was open in.
+fn get_folder_preferences<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  folder: &Path,
+) -> Option<FolderPreferences> {
+  match manager.state::<WindowsStateCache>().0.read() {
+    Ok(cache) => cache.folder_preferences(folder),
+    Err(_e) => None
+  }
+}
+
+/// Record the zoom of a folder for a new window, unless the window is already zoomed.
+fn restore_folder_zoom<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, zoom: f64) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  match cache.get_item(label) {
+    Some(mut state) if state.zoom.is_none() => {
+      state.zoom = Some(zoom);
+      cache.set_item(label, state)
     },
-    Err(e) => Err(Error::SerdeJson(e))
+    _ => Ok(()),
   }
+}
+
+/// Get window to reuse when the `max_windows` limit is reached.
+///
+/// Returns the least recently focused managed window, or `None` if the limit is not reached.
+fn get_window_to_reuse_at_limit<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+) -> Option<Window<R>> {
+  let max_windows = match manager.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.max_windows?,
+    Err(_e) => return None,
+  };
+
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let res = match windows_state_cache.0.read() {
+    Ok(cache) => {
+      let mut open_windows = cache.state().opened_windows
+        .iter()
+        .filter(|(label, _window_state)| manager.get_window(label).is_some())
+        .collect::<Vec<(&String, &WindowState)>>();
+
+      if open_windows.len() < max_windows {
+        None
+      } else {
+        open_windows.sort_by_key(|(label, _window_state)| cache.last_focus_time(label));
+        open_windows.first().and_then(|(label, _window_state)| manager.get_window(label))
+      }
+    },
+    Err(_e) => None
+  };
+
+  res
+}
 
+/// Get the script setting the init payload of a window.
+fn init_script(init_payload: &JsonValue) -> Result<String> {
+  Ok(format!("window.{} = {};", INIT_PAYLOAD_GLOBAL, serde_json::to_string(init_payload)?))
 }
 
 // Endpoints for creating different window states
 fn open_in_webview_window<'a, R: Runtime, M: Manager<R>>(
   manager: &'a M,
-  options: WindowOptions,
-) -> Result<Window<R>> {
-  let _window_config = manager.state::<WindowsSettings>();
-  let windows_backup_cache = manager.state::<WindowsBackupCache>();
-
+  mut options: WindowOptions,
+) -> Result<OpenedWindow<R>> {
   // Build up the window configuration from provided options, config and environment
   let mut configuration: WindowConfiguration = WindowConfiguration {
     backup_path: None,
@@ -753,12 +1799,69 @@ fn open_in_webview_window<'a, R: Runtime, M: Manager<R>>(
     full_screen: options.fullscreen.unwrap_or(false),
     maximized: options.maximized.unwrap_or(false),
     folder: options.folder,
-    // home_dir: ,
-    // tmp_dir: ,
-    // user_data_dir: ,   
+    url: match &options.url {
+      Some(WindowUrl::External(url)) => Some(url.clone()),
+      _ => None,
+    },
+    home_dir: options.home_dir,
+    tmp_dir: options.tmp_dir,
+    user_data_dir: options.user_data_dir,
     ..Default::default() 
   };
 
+  // Restored windows get the webview profile, theme, route and bounds they were created with
+  let cached_state = match options.initial_startup {
+    true => get_cached_state(manager, &configuration)
+      .or_else(|| options.empty_window_backup_info.as_ref().and_then(|info| get_window_state(manager, &info.window))),
+    false => None,
+  };
+
+  configuration.webview = match (options.webview.is_empty(), &cached_state) {
+    (true, Some(cached)) => cached.configuration.webview.clone(),
+    _ => options.webview,
+  };
+
+  // User data is where the webview keeps its profile, a data directory set with the webview options wins
+  if configuration.webview.data_directory.is_none() {
+    configuration.webview.data_directory = configuration.user_data_dir.clone();
+  }
+
+  configuration.user_data_dir = configuration.webview.data_directory.clone()
+    .or_else(|| manager.app_handle().path_resolver().app_local_data_dir());
+
+  configuration.home_dir = configuration.home_dir.take()
+    .or_else(|| cached_state.as_ref().and_then(|cached| cached.configuration.home_dir.clone()))
+    .or_else(home_dir);
+
+  configuration.tmp_dir = configuration.tmp_dir.take()
+    .or_else(|| cached_state.as_ref().and_then(|cached| cached.configuration.tmp_dir.clone()))
+    .or_else(|| Some(temp_dir()));
+
+  // Folders opened again come back with the zoom, theme and always on top of their last window
+  let folder_preferences = configuration.folder.as_deref().and_then(|folder| get_folder_preferences(manager, folder));
+
+  configuration.theme = options.theme
+    .or_else(|| cached_state.as_ref().and_then(|cached| cached.configuration.theme))
+    .or_else(|| folder_preferences.as_ref().and_then(|preferences| preferences.theme));
+
+  configuration.always_on_top = options.always_on_top
+    .or_else(|| cached_state.as_ref().map(|cached| cached.configuration.always_on_top))
+    .or_else(|| folder_preferences.as_ref().map(|preferences| preferences.always_on_top))
+    .unwrap_or(false);
+
+  configuration.app_route = options.app_route.clone()
+    .or_else(|| cached_state.as_ref().and_then(|cached| cached.configuration.app_route.clone()));
+
+  configuration.content_protected = cached_state.as_ref().map_or(false, |cached| cached.configuration.content_protected);
+
+  // Logical bounds are converted with the scale factor of the monitor the window opens on
+  let cached_bounds = cached_state.and_then(|cached| cached.logical_bounds);
+
+  if let Some(bounds) = cached_bounds {
+    options.position = options.position.or(Some(WindowPosition { x: bounds.x, y: bounds.y }));
+    options.inner_size = options.inner_size.or(Some(WindowSize { width: bounds.width, height: bounds.height }));
+  }
+
   let mut window: Option<Window<R>> = None;
 
   if !options.force_new_window && !options.force_new_tabbed_window {
@@ -768,73 +1871,487 @@ fn open_in_webview_window<'a, R: Runtime, M: Manager<R>>(
     };
 
     if let Some(window_to_focus) = &window {
-      window_to_focus.set_focus()?;
+      activate_window(window_to_focus, options.activation)?;
     }
   }
 
   // Existing window
   if let Some(existing_window) = window {
-    match windows_backup_cache.0.write() {
-      Ok(mut cache) => {
-        if let Some(folder) = &configuration.folder {
-          configuration.backup_path = Some(cache.add_folder_backup(folder, existing_window.label()));
-        } else {
-          let backup_folder  = options.empty_window_backup_info
-          .and_then(|info| info.backup_folder);
-    
-          configuration.backup_path = Some(cache.add_empty_window_backup(&backup_folder, existing_window.label()));
-        }
+    save_existing_window_state(manager, existing_window.label(), configuration, options.empty_window_backup_info)?;
 
-        Ok(existing_window)
-      },
-      Err(e) => {
-        Err(Error::RwLock(e.to_string()))
-      }
+    Ok(OpenedWindow::new(existing_window, OpenPath::ExistingWindow))
+  }
+  // Limit reached, reuse the least recently focused window
+  else if let Some(reused_window) = get_window_to_reuse_at_limit(manager) {
+    if let Some(folder) = &configuration.folder {
+      add_folders_to_existing_window(&reused_window, &vec![PathToOpen {
+        folder: Some(folder.clone()),
+        path_type: FileType::Directory,
+        exists: folder.exists(),
+        ..Default::default()
+      }], options.activation, options.context)?;
+    }
+
+    if !configuration.files_to_open_or_create.is_empty() {
+      open_files_in_existing_window(
+        &OpenConfiguration {
+          activation: options.activation,
+          context: options.context,
+          ..Default::default()
+        },
+        &reused_window,
+        &FilesToOpen {
+          files_to_open_or_create: configuration.files_to_open_or_create.clone(),
+        },
+      )?;
     }
+
+    // Opening only files keeps the folder of the reused window
+    if configuration.folder.is_none() {
+      configuration.folder = get_window_state(manager, reused_window.label()).and_then(|window_state| window_state.folder);
+    }
+
+    save_existing_window_state(manager, reused_window.label(), configuration, options.empty_window_backup_info)?;
+
+    Ok(OpenedWindow::new(reused_window, OpenPath::MaxWindowsReached))
   }
   // New window
   else {
-    let unique_label = options.label.unwrap_or(new_window_label());
-    let url = options.url.unwrap_or(WindowUrl::App("index.html".into()));
+    // Supplied labels go to the window builder as is, they are checked before
+    let unique_label = match options.label {
+      Some(label) => {
+        validate_new_label(manager, &label)?;
+        label
+      },
+      None => new_window_label(manager, configuration.folder.as_deref()),
+    };
+    let url = options.url
+      .or_else(|| configuration.app_route.as_deref().map(route_url))
+      .unwrap_or(WindowUrl::App("index.html".into()));
     let handle = manager.app_handle();
-    // Create the window
-    let window_builder = WindowBuilder::new(&handle, unique_label, url)
-      .always_on_top(options.always_on_top.unwrap_or(false)) // Whether the window should always be on top of other windows.
-      .decorations(options.decorations.unwrap_or(true)) // Whether the window should have borders and bars.
-      .fullscreen(options.fullscreen.unwrap_or(false)) // Whether to start the window in fullscreen or not.
-      .maximized(options.maximized.unwrap_or(false)) // Whether the window should be maximized upon creation.
-      .resizable(options.resizable.unwrap_or(true)) // Whether the window is resizable or not.
-      .skip_taskbar(options.skip_taskbar.unwrap_or(false)) // Sets whether or not the window icon should be added to the taskbar.
-      .theme(options.theme) // Forces a theme or uses the system settings if None was provided.
-      .title(options.title.unwrap_or(String::from(""))) // The title of the window in the title bar.
-      .transparent(options.transparent.unwrap_or(false)) // Whether the the window should be transparent.
-      .visible(options.visible.unwrap_or(true)); // Whether the window should be immediately visible upon creation.
-
-    match window_builder.build() {
+    let click_through_inactive = is_click_through_inactive_enabled(manager);
+    let init_script = match (&options.init_payload, &configuration.url) {
+      (Some(init_payload), None) => Some(init_script(init_payload)?),
+      _ => None,
+    };
+
+    // Create the window, the builder is consumed so each attempt gets a new one
+    let build_window = || {
+      let navigation_handle = handle.clone();
+      let navigation_label = unique_label.clone();
+      let mut window_builder = WindowBuilder::new(&handle, unique_label.clone(), url.clone())
+        .accept_first_mouse(!click_through_inactive) // Whether clicking an inactive window also clicks through to the webview (macOS).
+        .always_on_top(configuration.always_on_top) // Whether the window should always be on top of other windows.
+        .decorations(options.decorations.unwrap_or(true)) // Whether the window should have borders and bars.
+        .fullscreen(options.fullscreen.unwrap_or(false)) // Whether to start the window in fullscreen or not.
+        .maximized(options.maximized.unwrap_or(false)) // Whether the window should be maximized upon creation.
+        .resizable(options.resizable.unwrap_or(true)) // Whether the window is resizable or not.
+        .skip_taskbar(options.skip_taskbar.unwrap_or(false)) // Sets whether or not the window icon should be added to the taskbar.
+        .theme(configuration.theme) // Forces a theme or uses the system settings if None was provided.
+        .title(options.title.clone().unwrap_or_default()) // The title of the window in the title bar.
+        .transparent(options.transparent.unwrap_or(false)) // Whether the the window should be transparent.
+        .visible(options.visible.unwrap_or(true)) // Whether the window should be immediately visible upon creation.
+        .content_protected(configuration.content_protected) // Whether the contents are hidden from screen capture.
+        .focused(options.activation == ActivationPolicy::Focus) // Whether the window should be focused upon creation.
+        .on_navigation(move |url| {
+          // Capture state before the page is left, navigation is never blocked
+          if let Err(e) = handle_before_navigate(&navigation_handle, &navigation_label, &url) {
+            log_error(&e);
+          }
+
+          true
+        });
+
+      if let Some(position) = &options.position {
+        window_builder = window_builder.position(position.x, position.y); // The initial position of the window.
+      }
+
+      if let Some(size) = &options.inner_size {
+        window_builder = window_builder.inner_size(size.width, size.height); // The initial size of the window.
+      }
+
+      if let Some(user_agent) = &configuration.webview.user_agent {
+        window_builder = window_builder.user_agent(user_agent); // The user agent for the webview.
+      }
+
+      if let Some(args) = &configuration.webview.additional_browser_args {
+        window_builder = window_builder.additional_browser_args(args); // Additional arguments for the webview.
+      }
+
+      if let Some(data_directory) = &configuration.webview.data_directory {
+        window_builder = window_builder.data_directory(data_directory.clone()); // Data directory for the webview.
+      }
+
+      if let Some(init_script) = &init_script {
+        window_builder = window_builder.initialization_script(init_script); // Runs before the page scripts, on each load.
+      }
+
+      window_builder.build()
+    };
+
+    match create_with_retry(manager, &unique_label, build_window) {
       Ok(created_window) => {
-        match windows_backup_cache.0.write() {
-          Ok(mut cache) => {
-            if let Some(folder) = &configuration.folder {
-              configuration.backup_path = Some(cache.add_folder_backup(folder, created_window.label()));
-            } else {
-              let backup_folder  = options.empty_window_backup_info
-              .and_then(|info| info.backup_folder);
-        
-              configuration.backup_path = Some(cache.add_empty_window_backup(&backup_folder, created_window.label()));
-            }
-    
-            Ok(created_window)
-          },
+        // The window is already open, it goes on without hot exit
+        configuration.backup_path = match add_window_backup(
+          manager,
+          &configuration.folder,
+          options.empty_window_backup_info,
+          created_window.label(),
+        ) {
+          Ok(backup_path) => backup_path,
           Err(e) => {
-            Err(Error::RwLock(e.to_string()))
-          }
+            report_window_error(manager, created_window.label(), &e);
+            None
+          },
+        };
+
+        save_window_state(manager, created_window.label(), configuration)?;
+        record_window_created(manager, created_window.label())?;
+
+        // Applied to the page once it loads
+        if let Some(zoom) = folder_preferences.and_then(|preferences| preferences.zoom) {
+          restore_folder_zoom(manager, created_window.label(), zoom)?;
         }
+
+        if options.activation == ActivationPolicy::Background {
+          activate_window(&created_window, options.activation)?;
+        }
+
+        Ok(OpenedWindow::new(created_window, OpenPath::NewWindow))
       },
-      Err(e) => {
-        Err(Error::Tauri(e))
-      }
+      Err(e) => Err(e),
+    }
+  }
+}
+
+/// Register the backup folder of a window and create it.
+///
+/// Without the `backups` feature windows get no backup folder.
+#[cfg(feature = "backups")]
+fn add_window_backup<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  folder: &Option<PathBuf>,
+  empty_window_backup_info: Option<EmptyWindowBackupInfo>,
+  label: &str,
+) -> Result<Option<PathBuf>> {
+  let windows_backup_cache = manager.state::<WindowsBackupCache>();
+
+  let mut cache = windows_backup_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let backup_path = match folder {
+    Some(folder) => cache.add_folder_backup(folder, label)?,
+    None => {
+      let backup_folder = empty_window_backup_info.and_then(|info| info.backup_folder);
+
+      cache.add_empty_window_backup(&backup_folder, label)?
+    },
+  };
+
+  Ok(Some(backup_path))
+}
+
+#[cfg(not(feature = "backups"))]
+fn add_window_backup<R: Runtime, M: Manager<R>>(
+  _manager: &M,
+  _folder: &Option<PathBuf>,
+  _empty_window_backup_info: Option<EmptyWindowBackupInfo>,
+  _label: &str,
+) -> Result<Option<PathBuf>> {
+  Ok(None)
+}
+
+/// Save the state of an existing window a request opened its resources in.
+///
+/// Webview and theme of an existing window can't be reconfigured, they are kept from its state.
+fn save_existing_window_state<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  label: &str,
+  mut configuration: WindowConfiguration,
+  empty_window_backup_info: Option<EmptyWindowBackupInfo>,
+) -> Result<()> {
+  // The resources still open, without hot exit
+  configuration.backup_path = match add_window_backup(manager, &configuration.folder, empty_window_backup_info, label) {
+    Ok(backup_path) => backup_path,
+    Err(e) => {
+      report_window_error(manager, label, &e);
+      None
+    },
+  };
+
+  let existing_configuration = get_window_state(manager, label)
+    .map(|window_state| window_state.configuration)
+    .unwrap_or_default();
+
+  configuration.webview = existing_configuration.webview;
+  configuration.home_dir = existing_configuration.home_dir;
+  configuration.tmp_dir = existing_configuration.tmp_dir;
+  configuration.user_data_dir = existing_configuration.user_data_dir;
+  configuration.theme = existing_configuration.theme;
+  configuration.app_route = existing_configuration.app_route;
+  configuration.content_protected = existing_configuration.content_protected;
+  configuration.always_on_top = existing_configuration.always_on_top;
+
+  save_window_state(manager, label, configuration)
+}
+
+/// Get cached window state for the window label.
+fn get_window_state<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  label: &str,
+) -> Option<WindowState> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let res = match windows_state_cache.0.read() {
+    Ok(cache) => cache.get_item(label),
+    Err(_e) => None
+  };
+
+  res
+}
+
+/// Give empty window backups of earlier versions unique ids.
+///
+/// Updates the backup registry and the cached window states restored from them.
+#[cfg(feature = "backups")]
+pub fn migrate_empty_window_backups<R: Runtime, M: Manager<R>>(manager: &M) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut state_cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let windows_backup_cache = manager.state::<WindowsBackupCache>();
+
+  let mut backup_cache = windows_backup_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut backup_paths = backup_cache.backups.empty_windows
+    .iter()
+    .filter_map(|info| info.backup_folder.as_ref().map(|folder| backup_cache.get_backup_path(folder)))
+    .collect::<Vec<PathBuf>>();
+
+  backup_paths.extend(
+    state_cache.state().opened_windows
+      .values()
+      .filter(|state| state.folder.is_none())
+      .filter_map(|state| state.backup_path.clone())
+  );
+
+  backup_paths.sort();
+  backup_paths.dedup();
+
+  let mut migrated = HashMap::new();
+
+  for backup_path in backup_paths {
+    if let Some(migrated_path) = backup_cache.migrate_empty_window_id(&backup_path)? {
+      migrated.insert(backup_path, migrated_path);
+    }
+  }
+
+  if migrated.is_empty() {
+    return Ok(());
+  }
+
+  let mut changed = Vec::new();
+
+  for (label, state) in state_cache.state_mut().opened_windows.iter_mut() {
+    if let Some(migrated_path) = state.backup_path.as_ref().and_then(|path| migrated.get(path)) {
+      state.backup_path = Some(migrated_path.clone());
+      state.configuration.backup_path = Some(migrated_path.clone());
+      changed.push(label.clone());
+    }
+  }
+
+  for label in changed {
+    state_cache.mark_changed(&label);
+  }
+
+  state_cache.save()
+}
+
+#[cfg(not(feature = "backups"))]
+pub fn migrate_empty_window_backups<R: Runtime, M: Manager<R>>(_manager: &M) -> Result<()> {
+  Ok(())
+}
+
+/// Send the cached state of a managed window to its webview.
+///
+/// Called on page load, events emitted while the webview is still loading are lost.
+pub fn emit_window_created<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  match (get_window_state(manager, label), manager.get_window(label)) {
+    (Some(state), Some(window)) => {
+      window.emit(WINDOW_CREATED_EVENT, WindowCreatedPayload {
+        label: label.to_string(),
+        id: state.id,
+        folder: state.folder,
+        backup_path: state.backup_path,
+      })?;
+      Ok(())
+    },
+    _ => Ok(())
+  }
+}
+
+/// Cache window state for the window label.
+///
+/// Keeps the state cache in sync with the resources opened in the window.
+fn save_window_state<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  label: &str,
+  configuration: WindowConfiguration,
+) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mode = if configuration.full_screen {
+    WindowMode::Fullscreen
+  } else if configuration.maximized {
+    WindowMode::Maximized
+  } else {
+    WindowMode::Normal
+  };
+
+  // Keep runtime info like focus time of reused windows
+  let previous_state = cache.get_item(label).unwrap_or_default();
+
+  let id = match previous_state.id {
+    0 => cache.get_window_id(&configuration),
+    id => id,
+  };
+
+  let display = match manager.get_window(label) {
+    Some(window) => get_window_display(&window)?,
+    None => previous_state.display,
+  };
+
+  let folder = configuration.folder.clone();
+
+  // Untrusted folders only open in restricted mode
+  let restricted = match &folder {
+    Some(folder) => !is_folder_trusted(manager, folder)?,
+    None => false,
+  };
+
+  cache.set_item(label, WindowState {
+    folder: configuration.folder.clone(),
+    backup_path: configuration.backup_path.clone(),
+    configuration,
+    mode,
+    display,
+    id,
+    closed_time: None,
+    restricted,
+    ..previous_state
+  })?;
+
+  drop(cache);
+
+  match folder {
+    Some(folder) if restricted => {
+      watch_folder(manager, label, None)?;
+      request_trust(manager, label, &folder)
+    },
+    folder => watch_folder(manager, label, folder.as_ref()),
+  }
+}
+
+/// Update windows with a folder inside `folder` after its trust changed.
+///
+/// Trusted windows leave restricted mode, others get a new trust request.
+fn update_window_trust<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  folder: &PathBuf,
+  trusted: bool,
+) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let windows = cache.state().opened_windows
+    .iter()
+    .filter(|(label, state)| {
+      state.closed_time.is_none()
+        && state.restricted == trusted
+        && manager.get_window(label).is_some()
+        && state.folder.as_ref().map_or(false, |window_folder| {
+          // Folder may still be trusted through a parent
+          window_folder.starts_with(folder) && is_folder_trusted(manager, window_folder).unwrap_or(true) == trusted
+        })
+    })
+    .map(|(label, state)| (label.clone(), state.clone()))
+    .collect::<Vec<(String, WindowState)>>();
+
+  cache.set_items(windows.iter().map(|(label, state)| (label.clone(), WindowState {
+    restricted: !trusted,
+    ..state.clone()
+  })))?;
+
+  drop(cache);
+
+  for (label, state) in windows {
+    match state.folder {
+      Some(window_folder) if !trusted => {
+        watch_folder(manager, &label, None)?;
+        request_trust(manager, &label, &window_folder)?;
+      },
+      window_folder => watch_folder(manager, &label, window_folder.as_ref())?,
+    }
+  }
+
+  Ok(())
+}
+
+/// Find the window the request id of an open request already opened, otherwise validate its label.
+fn find_repeated_open<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  configuration: &OpenConfiguration,
+) -> Result<Option<OpenedWindow<R>>> {
+  if let Some(request_id) = &configuration.request_id {
+    if let Some(opened_window) = find_opened_request(manager, request_id)? {
+      return Ok(Some(opened_window));
     }
   }
+
+  // Taken labels fail the request before anything is opened
+  if let Some(label) = &configuration.label {
+    validate_new_label(manager, label)?;
+  }
+
+  Ok(None)
+}
+
+/// Get the label requested for a new window, until a window of the request took it.
+fn requested_label<'a, R: Runtime, M: Manager<R>>(manager: &'a M, configuration: &OpenConfiguration) -> Option<String> {
+  configuration.label.clone().filter(|label| manager.get_window(label).is_none())
+}
+
+/// Report the error of an open request to the window it came from, or record its window by request id.
+fn finish_open<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  context_window_label: Option<&str>,
+  request_id: Option<&str>,
+  open_res: Result<OpenedWindow<R>>,
+  trace: OpenTrace,
+) -> Result<OpenedWindow<R>> {
+  // Window the request came from learns why its paths weren't opened
+  if let (Err(e), Some(label)) = (&open_res, context_window_label) {
+    if matches!(e.kind(), ErrorKind::Rejected | ErrorKind::NotAllowed) {
+      report_window_error(manager, label, e);
+    }
+  }
+
+  let steps = trace.finish();
+
+  let opened_window = open_res?.with_trace(steps);
+
+  if let Some(request_id) = request_id {
+    record_opened_request(manager, request_id, &opened_window)?;
+  }
+
+  Ok(opened_window)
 }
 
 fn open_folder_in_window<'a, R: Runtime, M: Manager<R>>(
@@ -844,16 +2361,20 @@ fn open_folder_in_window<'a, R: Runtime, M: Manager<R>>(
   force_new_window: bool,
   files_to_open: Option<FilesToOpen>,
   window_to_use: Option<String>,
-) -> Result<Window<R>> {
+) -> Result<OpenedWindow<R>> {
   open_in_webview_window(
     manager,
     WindowOptions {
+      label: requested_label(manager, configuration),
       folder: folder_to_open,
       initial_startup: configuration.initial_startup,
       force_new_window,
       force_new_tabbed_window: configuration.force_new_tabbed_window,
       files_to_open: files_to_open.unwrap_or(FilesToOpen::default()),
       window_to_use,
+      activation: configuration.activation,
+      init_payload: configuration.init_payload.clone(),
+      context: configuration.context,
       ..Default::default()
     },
   )
@@ -865,7 +2386,7 @@ fn open_in_empty_window<'a, R: Runtime, M: Manager<R>>(
   force_new_window: bool,
   files_to_open: FilesToOpen,
   empty_window_backup_info: Option<EmptyWindowBackupInfo>,
-) -> Result<Window<R>> {
+) -> Result<OpenedWindow<R>> {
   let window_to_use = match &configuration.context_window_label {
     Some(label) => Some(label.to_string()),
     None => None
@@ -874,54 +2395,382 @@ fn open_in_empty_window<'a, R: Runtime, M: Manager<R>>(
   open_in_webview_window(
     manager,
     WindowOptions {
+      label: requested_label(manager, configuration),
       initial_startup: configuration.initial_startup,
       force_new_window,
       force_new_tabbed_window: configuration.force_new_tabbed_window,
       files_to_open,
       window_to_use,
       empty_window_backup_info,
+      activation: configuration.activation,
+      init_payload: configuration.init_payload.clone(),
+      context: configuration.context,
       ..Default::default()
     }
   )
 }
 
-fn is_single_folder<'a, R: Runtime, M: Manager<R>>(
+fn empty_files_to_open(files_to_open: &mut FilesToOpen) -> Result<()> {
+  files_to_open.files_to_open_or_create.clear();
+  files_to_open.files_to_open_or_create.shrink_to_fit();
+
+  Ok(())
+}
+
+fn is_empty_files_to_open(files_to_open: &FilesToOpen) -> bool {
+  files_to_open.files_to_open_or_create.is_empty()
+}
+
+// Implement window creation
+/// Resolve the paths to open for a configuration.
+///
+/// Validates urls and refreshes paths against the filesystem.
+fn resolve_paths_to_open<'a, R: Runtime, M: Manager<R>>(
   manager: &'a M,
-  label: &str
-) -> Result<bool> {
-  let windows_state_cache = manager.state::<WindowsStateCache>();
+  configuration: &OpenConfiguration,
+) -> Result<Vec<PathToOpen>> {
+  // Requested urls must be allowed
+  if let Some(url) = configuration.urls_to_open.iter().find(|url| !is_url_allowed(manager, url)) {
+    return Err(Error::UrlNotAllowed(url.to_string()));
+  }
 
-  let res = match windows_state_cache.0.read() {
-    Ok(cache) => {
-      if let Some(window_state) = cache.get_item(label) {
-        match &window_state.folder {
-          Some(_uri) => Ok(true),
-          None => Ok(false)
-        }
-      } else {
-        Err(Error::WindowStateWithLabelNotFound(label.to_string()))
+  // Folders expand into their files
+  let uris_to_open = match &configuration.expand_folders {
+    Some(expansion) => expand_openables(&configuration.uris_to_open, expansion)?,
+    None => configuration.uris_to_open.clone(),
+  };
+
+  // Identify things to open from open config
+  let paths_to_open = get_paths_to_open(
+    manager,
+    &uris_to_open,
+    &configuration.urls_to_open,
+    &configuration.force_empty_window,
+    &configuration.initial_startup,
+  );
+
+  // Every file was routed to an event or ignored, or folders had no matching files
+  if paths_to_open.is_empty() && configuration.uris_to_open.as_ref().map_or(false, |uris| !uris.is_empty()) {
+    return Err(Error::OpenRejected("nothing left to open".to_string()));
+  }
+
+  // App may rewrite or reject the request before any window is created
+  let mut paths_to_open = filter_paths_to_open(manager, configuration, paths_to_open)?;
+
+  // Relaunched session is restored once
+  if configuration.initial_startup {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    if cache.state().was_restarted {
+      cache.state_mut().was_restarted = false;
+      cache.state_mut().restart_windows.clear();
+      cache.save()?;
+
+      manager.state::<WindowsStartupInfo>().0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?
+      .was_restarted = true;
+    }
+  }
+
+  // Stacking of the last session, re-applied once windows are restored
+  if configuration.initial_startup {
+    save_session_stack(manager)?;
+  }
+
+  // Paths may have changed since they were resolved, e.g. restored from last session
+  for path_to_open in paths_to_open.iter_mut() {
+    path_to_open.refresh();
+
+    if configuration.create_if_missing {
+      path_to_open.create_if_missing()?;
+    }
+  }
+
+  Ok(paths_to_open)
+}
+
+/// Apply the `open_without_arguments_in_new_window` setting to a request with nothing to open.
+///
+/// `On` opens a new empty window instead of restoring the last session, `Off` focuses the last active window.
+/// Returns the focused window, the initial startup always restores.
+fn open_without_arguments<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  configuration: &mut OpenConfiguration,
+  trace: &mut OpenTrace,
+) -> Result<Option<OpenedWindow<R>>> {
+  let has_arguments = configuration.uris_to_open.is_some()
+    || !configuration.urls_to_open.is_empty()
+    || configuration.force_empty_window;
+
+  if has_arguments || configuration.initial_startup {
+    return Ok(None);
+  }
+
+  let open_without_arguments_in_new_window = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .open_without_arguments_in_new_window
+  .clone();
+
+  match open_without_arguments_in_new_window {
+    OpenInNewWindow::On => {
+      trace.step(|| "nothing to open, new empty window (open_without_arguments_in_new_window)".to_string());
+
+      configuration.force_empty_window = true;
+      configuration.force_new_window = true;
+      configuration.force_reuse_window = false;
+
+      Ok(None)
+    },
+    OpenInNewWindow::Off => {
+      match get_last_active_window(manager) {
+        Some(window) => {
+          if window.is_minimized()? {
+            window.unminimize()?;
+          }
+
+          window.set_focus()?;
+
+          trace.step(|| format!("nothing to open, focused last active window `{}` (open_without_arguments_in_new_window)", window.label()));
+
+          Ok(Some(OpenedWindow::new(window, OpenPath::ExistingWindow)))
+        },
+        None => {
+          trace.step(|| "nothing to open and no last active window, restoring the last session".to_string());
+
+          Ok(None)
+        },
       }
     },
-    Err(e) => {
-      Err(Error::RwLock(e.to_string()))
+    OpenInNewWindow::Default => {
+      trace.step(|| "nothing to open, restoring the last session".to_string());
+
+      Ok(None)
+    },
+  }
+}
+
+/// Open resolved paths in windows per the configuration.
+///
+/// Files with a route open first, in a request of their own.
+fn open_paths<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  configuration: &OpenConfiguration,
+  paths_to_open: &[PathToOpen],
+  trace: &mut OpenTrace,
+) -> Result<OpenedWindow<R>> {
+  let (routed, paths_to_open): (Vec<PathToOpen>, Vec<PathToOpen>) = paths_to_open
+    .iter()
+    .cloned()
+    .partition(|path_to_open| path_to_open.route.is_some());
+
+  let mut routed_window = None;
+
+  for route in [OpenRoute::OpenInNewWindow, OpenRoute::OpenInExisting] {
+    let files = routed
+      .iter()
+      .filter(|path_to_open| path_to_open.route.as_ref() == Some(&route))
+      .map(|path_to_open| PathToOpen { route: None, ..path_to_open.clone() })
+      .collect::<Vec<PathToOpen>>();
+
+    if files.is_empty() {
+      continue;
     }
+
+    trace.step(|| format!("{} files routed ({:?})", files.len(), route));
+
+    // Nothing to reuse, routed files open like the rest of the request
+    let reuse_window = route == OpenRoute::OpenInExisting && get_last_active_window(manager).is_some();
+
+    let routed_configuration = OpenConfiguration {
+      force_new_window: route == OpenRoute::OpenInNewWindow,
+      force_reuse_window: reuse_window,
+      prefer_new_window: false,
+      ..configuration.clone()
+    };
+
+    routed_window = Some(open_unrouted_paths(manager, &routed_configuration, &files, trace)?);
+  }
+
+  match routed_window {
+    Some(opened_window) if paths_to_open.is_empty() => Ok(opened_window),
+    _ => open_unrouted_paths(manager, configuration, &paths_to_open, trace),
+  }
+}
+
+/// Open resolved paths without a route in windows per the configuration.
+fn open_unrouted_paths<'a, R: Runtime, M: Manager<R>>(
+  manager: &'a M,
+  configuration: &OpenConfiguration,
+  paths_to_open: &[PathToOpen],
+  trace: &mut OpenTrace,
+) -> Result<OpenedWindow<R>> {
+  let mut folders_to_open: Vec<PathToOpen> = Vec::new();
+  let folders_to_add: Vec<PathToOpen> = Vec::new();
+  let mut empty_windows_with_backups_to_restore: Vec<EmptyWindowBackupInfo> = Vec::new();
+  let mut files_to_open: FilesToOpen = FilesToOpen {
+    ..Default::default()
   };
-  
-  res
+  let mut urls_to_open: Vec<Url> = Vec::new();
+  let mut empty_to_open: u8 = 0;
+
+
+  for path_to_open in paths_to_open {
+    if path_to_open.folder.is_some() {
+      folders_to_open.push(path_to_open.clone());
+    } else if path_to_open.file.is_some() {
+      let file = path_to_open.file.as_ref().unwrap();
+      files_to_open
+        .files_to_open_or_create
+        .push(file.to_path_buf());
+    } else if let Some(url) = &path_to_open.url {
+      urls_to_open.push(url.clone());
+    } else if path_to_open.backup_path.is_some() {
+      let backup = path_to_open.backup_path.as_ref().unwrap();
+      empty_windows_with_backups_to_restore.push(EmptyWindowBackupInfo {
+        backup_folder: Some(backup.to_path_buf()),
+        ..Default::default()
+      }); // get basename of path for folder name
+    } else {
+      empty_to_open += 1;
+    }
+  }
+
+  // These are windows to restore because of hot-exit or from previous session (only performed once on startup!)
+  if configuration.initial_startup {
+    // Empty windows with backups are always restored
+    empty_windows_with_backups_to_restore.extend(get_empty_window_backup_paths(manager));
+  } else {
+    empty_windows_with_backups_to_restore.clear();
+  }
+
+  let windows_before = manager.windows().into_keys().collect::<HashSet<String>>();
+
+  let mut stats = OpenStats {
+    context: configuration.context,
+    initial_startup: configuration.initial_startup,
+    files: files_to_open.files_to_open_or_create.len(),
+    folders: folders_to_open.len(),
+    urls: urls_to_open.len(),
+    new_windows: 0,
+    reused_windows: 0,
+    restored_windows: 0,
+    restored_backups: empty_windows_with_backups_to_restore.len(),
+    error: None,
+  };
+
+  trace.step(|| format!(
+    "resolved {} folders, {} files, {} urls, {} empty windows, {} backups to restore",
+    stats.folders,
+    stats.files,
+    stats.urls,
+    empty_to_open,
+    stats.restored_backups,
+  ));
+
+  // Open based on config
+  let open_res = open(
+    manager,
+    configuration,
+    folders_to_open,
+    empty_windows_with_backups_to_restore,
+    empty_to_open,
+    files_to_open,
+    folders_to_add,
+    urls_to_open,
+    trace,
+  );
+
+  match &open_res {
+    Ok((_opened_window, used_windows)) => {
+      let used_windows = used_windows.iter().collect::<HashSet<_>>();
+
+      stats.reused_windows = used_windows.iter().filter(|label| windows_before.contains(**label)).count();
+      stats.new_windows = used_windows.len() - stats.reused_windows;
+
+      if configuration.initial_startup {
+        stats.restored_windows = stats.new_windows;
+      }
+    },
+    Err(e) => stats.error = Some(e.to_string()),
+  };
+
+  report_open(manager, &stats);
+
+  if configuration.initial_startup && open_res.is_ok() {
+    restore_stacking_order(manager)?;
+  }
+
+  open_res.map(|(opened_window, _used_windows)| {
+    opened_window.with_configuration(configuration.resolved(paths_to_open))
+  })
 }
 
-fn empty_files_to_open(files_to_open: &mut FilesToOpen) -> Result<()> {
-  files_to_open.files_to_open_or_create.clear();
-  files_to_open.files_to_open_or_create.shrink_to_fit();
+/// Add opened files and folders to the recents.
+#[cfg(feature = "recents")]
+fn add_recents<'a, R: Runtime, M: Manager<R>>(manager: &'a M, paths_to_open: &[PathToOpen]) -> Result<()> {
+  let recents = paths_to_open
+    .iter()
+    .filter(|path_to_open| path_to_open.folder.is_some() || path_to_open.file.is_some())
+    .cloned()
+    .collect::<Vec<PathToOpen>>();
+
+  manager.state::<WindowsRecentsCache>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .add_recents(recents)
+}
 
+#[cfg(not(feature = "recents"))]
+fn add_recents<'a, R: Runtime, M: Manager<R>>(_manager: &'a M, _paths_to_open: &[PathToOpen]) -> Result<()> {
   Ok(())
 }
 
-fn is_empty_files_to_open(files_to_open: &FilesToOpen) -> bool {
-  files_to_open.files_to_open_or_create.is_empty()
+/// Read recently opened files and folders.
+#[cfg(feature = "recents")]
+fn read_recents<R: Runtime, M: Manager<R>>(manager: &M) -> Result<RecentlyOpened> {
+  let windows_recents_cache = manager.state::<WindowsRecentsCache>();
+
+  let cache = windows_recents_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  Ok(cache.get_recents())
+}
+
+#[cfg(not(feature = "recents"))]
+fn read_recents<R: Runtime, M: Manager<R>>(_manager: &M) -> Result<RecentlyOpened> {
+  Err(Error::FeatureDisabled("recents".to_string()))
+}
+
+/// Check recents against the filesystem, returns the number of missing entries.
+#[cfg(feature = "recents")]
+fn validate_recents<R: Runtime, M: Manager<R>>(manager: &M, remove: bool) -> Result<usize> {
+  manager.state::<WindowsRecentsCache>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .validate(remove)
+}
+
+#[cfg(not(feature = "recents"))]
+fn validate_recents<R: Runtime, M: Manager<R>>(_manager: &M, _remove: bool) -> Result<usize> {
+  Ok(0)
+}
+
+/// Check folder backups against the filesystem, returns the number of missing entries.
+#[cfg(feature = "backups")]
+fn validate_backups<R: Runtime, M: Manager<R>>(manager: &M, remove: bool) -> Result<usize> {
+  Ok(
+    manager.state::<WindowsBackupCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .validate(remove)
+  )
+}
+
+#[cfg(not(feature = "backups"))]
+fn validate_backups<R: Runtime, M: Manager<R>>(_manager: &M, _remove: bool) -> Result<usize> {
+  Ok(0)
 }
 
-// Implement window creation
 fn open<'a, R: Runtime, M: Manager<R>>(
   manager: &'a M,
   configuration: &OpenConfiguration,
@@ -930,21 +2779,28 @@ fn open<'a, R: Runtime, M: Manager<R>>(
   mut empty_to_open: u8,
   mut files_to_open: FilesToOpen,
   folders_to_add: Vec<PathToOpen>,
-) -> Result<Window<R>> {
+  urls_to_open: Vec<Url>,
+  trace: &mut OpenTrace,
+) -> Result<(OpenedWindow<R>, Vec<String>)> {
   let mut used_windows: Vec<String> = Vec::new();
-  let mut files_opened_in_window: Option<Window<R>> = None;
+  let mut files_opened_in_window: Option<OpenedWindow<R>> = None;
+
+  // Lookups below share one read of the state cache
+  let mut index = FolderLookup::read(manager)?;
   
   // Settings can decide if files/folders open in new window or not
   let OpenOptions {
     mut open_folder_in_new_window,
     open_files_in_new_window,
-  } = should_open_new_window(manager, configuration);
+  } = should_open_new_window(manager, configuration, trace);
 
   // Handle folders to add by looking for the last active window (not on initial startup)
   if !configuration.initial_startup && folders_to_add.len() > 0 {
     if let Some(active_window) = get_last_active_window(manager) {
-      add_folders_to_existing_window(&active_window, &folders_to_add)?;
+      add_folders_to_existing_window(&active_window, &folders_to_add, configuration.activation, configuration.context)?;
       used_windows.push(active_window.label().to_string());
+
+      trace.step(|| format!("added {} folders to last active window `{}`", folders_to_add.len(), active_window.label()));
     }
   }
 
@@ -960,22 +2816,43 @@ fn open<'a, R: Runtime, M: Manager<R>>(
     let mut window_to_use_for_files: Option<Window<R>> = None;
   
     if file_to_check.is_some() && !open_files_in_new_window {
+      // Dropped files open in the window they were dropped on
+      if configuration.context == OpenContext::Drop {
+        window_to_use_for_files = configuration.context_window_label
+          .as_ref()
+          .and_then(|label| manager.get_window(label));
+
+        if let Some(window) = &window_to_use_for_files {
+          trace.step(|| format!("files use window `{}` they were dropped on", window.label()));
+        }
+      }
+
       if configuration.context == OpenContext::Desktop
         || configuration.context == OpenContext::Cli
         || configuration.context == OpenContext::Dock
+        || configuration.context == OpenContext::Url
       {
-        window_to_use_for_files = find_window_on_file(manager, file_to_check.as_ref().unwrap());
+        window_to_use_for_files = index.window_on_file(file_to_check.as_ref().unwrap())
+          .and_then(|label| manager.get_window(label));
+
+        if let Some(window) = &window_to_use_for_files {
+          trace.step(|| format!("files match the folder of window `{}`", window.label()));
+        }
       }
   
       if !window_to_use_for_files.is_some() {
         window_to_use_for_files = get_last_active_window(manager);
-      }
+
+        if let Some(window) = &window_to_use_for_files {
+          trace.step(|| format!("files use last active window `{}`", window.label()));
+        }
+      }
     }
 
     // We found a window to open the files in
     if let Some(window_to_use) = window_to_use_for_files {
       // Window is single folder
-      let is_single_folder_res = is_single_folder(manager, window_to_use.label())?;
+      let is_single_folder_res = index.is_single_folder(window_to_use.label())?;
 
       if is_single_folder_res {
         folders_to_open.push(PathToOpen {
@@ -992,27 +2869,39 @@ fn open<'a, R: Runtime, M: Manager<R>>(
         ) {
           Ok(_res) => {
             used_windows.push(window_to_use.label().to_string());
-            files_opened_in_window = Some(window_to_use);
+            files_opened_in_window = Some(OpenedWindow::new(window_to_use, OpenPath::ExistingWindow));
             empty_files_to_open(&mut files_to_open)
           },
           Err(e) => Err(e)
         }?;
       }
     }
+    // Forced reuse without a window to reuse
+    else if configuration.force_reuse_window {
+      trace.step(|| "reuse forced but no window is open".to_string());
+
+      return Err(Error::NoWindowToReuse);
+    }
     // Finally, if no window or folder is found, just open the files in an empty window
     else {
+      trace.step(|| "no window to use, opening a new window".to_string());
+
       let open_in_webview_window_res = open_in_webview_window(
         manager,
         WindowOptions {
+          label: requested_label(manager, configuration),
           initial_startup: configuration.initial_startup,
           files_to_open: files_to_open.clone(),
           force_new_window: true,
           force_new_tabbed_window: configuration.force_new_tabbed_window,
+          activation: configuration.activation,
+          init_payload: configuration.init_payload.clone(),
+          context: configuration.context,
           ..Default::default()
         },
       )?;
       
-      used_windows.push(open_in_webview_window_res.label().to_string());
+      used_windows.push(open_in_webview_window_res.window.label().to_string());
       files_opened_in_window = Some(open_in_webview_window_res);
       empty_files_to_open(&mut files_to_open)?;
     } 
@@ -1024,8 +2913,9 @@ fn open<'a, R: Runtime, M: Manager<R>>(
     // Check for existing instances
     let windows_on_folder_path = folders_to_open.iter()
     .filter_map(|folder_to_open| {
-      find_window_on_folder(manager, folder_to_open.folder.as_ref())
+      folder_to_open.folder.as_ref().and_then(|folder| index.window_on_folder(folder))
     })
+    .map(str::to_string)
     .collect::<Vec<String>>();
 
     if windows_on_folder_path.len() > 0 {
@@ -1033,8 +2923,10 @@ fn open<'a, R: Runtime, M: Manager<R>>(
       if let Some(window_on_folder) = manager.get_window(&windows_on_folder_path[0]) {
         open_files_in_existing_window(configuration, &window_on_folder, &files_to_open)?;
 
+        trace.step(|| format!("folder already open in window `{}`", window_on_folder.label()));
+
         used_windows.push(window_on_folder.label().to_string());
-        files_opened_in_window = Some(window_on_folder);
+        files_opened_in_window = Some(OpenedWindow::new(window_on_folder, OpenPath::ExistingWindow));
         empty_files_to_open(&mut files_to_open)?;
       }
 
@@ -1043,16 +2935,13 @@ fn open<'a, R: Runtime, M: Manager<R>>(
 
     // Open remaining ones
     for folder_to_open in folders_to_open {
-      let window_already_opened = windows_on_folder_path.iter().find(|window| {
-        if let Some(label) = find_window_on_folder(manager, folder_to_open.folder.as_ref()) {
-          window.as_str().eq(&label)
-        }
-        else {
-          false
-        }
-      });
-      // ignore folders that are already open
-      if window_already_opened.is_none() {
+      let window_already_opened = folder_to_open.folder.as_ref()
+        .and_then(|folder| index.window_on_folder(folder))
+        .is_some();
+      // ignore folders that are already open, or opened earlier in this request
+      if !window_already_opened {
+        let folder = folder_to_open.folder.clone();
+
         // Do open folder
         let open_folder_in_window_res = open_folder_in_window(
           manager,
@@ -1063,7 +2952,15 @@ fn open<'a, R: Runtime, M: Manager<R>>(
           None
         )?;
 
-        used_windows.push(open_folder_in_window_res.label().to_string());
+        index.insert(open_folder_in_window_res.window.label(), folder.as_deref());
+
+        trace.step(|| format!(
+          "folder opened in window `{}` ({:?})",
+          open_folder_in_window_res.window.label(),
+          open_folder_in_window_res.path,
+        ));
+
+        used_windows.push(open_folder_in_window_res.window.label().to_string());
         files_opened_in_window = Some(open_folder_in_window_res);
         empty_files_to_open(&mut files_to_open)?;
 
@@ -1083,7 +2980,9 @@ fn open<'a, R: Runtime, M: Manager<R>>(
         Some(empty_window_backup_info.clone())
       )?;
 
-      used_windows.push(empty_window_res.label().to_string());
+      trace.step(|| format!("empty window backup restored in window `{}`", empty_window_res.window.label()));
+
+      used_windows.push(empty_window_res.window.label().to_string());
       files_opened_in_window = Some(empty_window_res);
       empty_files_to_open(&mut files_to_open)?;
 
@@ -1091,6 +2990,26 @@ fn open<'a, R: Runtime, M: Manager<R>>(
     }
   }
 
+  // Handle external urls, always in their own window
+  for url_to_open in urls_to_open {
+    let url_window_res = open_in_webview_window(
+      manager,
+      WindowOptions {
+        label: requested_label(manager, configuration),
+        url: Some(WindowUrl::External(url_to_open)),
+        initial_startup: configuration.initial_startup,
+        force_new_window: true,
+        activation: configuration.activation,
+        ..Default::default()
+      },
+    )?;
+
+    trace.step(|| format!("url opened in new window `{}`", url_window_res.window.label()));
+
+    used_windows.push(url_window_res.window.label().to_string());
+    files_opened_in_window = Some(url_window_res);
+  }
+
   // Handle empty to open (only if no other window opened)
   if used_windows.len() == 0 || !is_empty_files_to_open(&files_to_open) {
     if !is_empty_files_to_open(&files_to_open) && empty_to_open == 0 {
@@ -1107,6 +3026,13 @@ fn open<'a, R: Runtime, M: Manager<R>>(
         None
       )?;
 
+      trace.step(|| format!(
+        "empty window opened in window `{}` ({:?})",
+        empty_window_res.window.label(),
+        empty_window_res.path,
+      ));
+
+      used_windows.push(empty_window_res.window.label().to_string());
       files_opened_in_window = Some(empty_window_res);
       empty_files_to_open(&mut files_to_open)?;
 
@@ -1115,7 +3041,7 @@ fn open<'a, R: Runtime, M: Manager<R>>(
   }
 
   match files_opened_in_window {
-    Some(window) => Ok(window),
+    Some(opened_window) => Ok((opened_window, used_windows)),
     None => Err(Error::Tauri(TauriError::CreateWindow))
   }
 }
@@ -1123,114 +3049,236 @@ fn open<'a, R: Runtime, M: Manager<R>>(
 // API for window creation
 pub struct WindowsAPI {}
 impl WindowsAPI {
-  pub fn get_focused_window<'a, R: Runtime, M: Manager<R>>(_manager: &'a M) -> Option<Window<R>> {
-    None
+  /// Get the focused window, the most recently focused one while the app is in the background.
+  ///
+  /// Returns `None` if no managed window is open.
+  pub fn get_focused_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Option<Window<R>> {
+    get_focused_window(manager)
   }
-  
+
   pub fn get_last_active_window<'a, R: Runtime, M: Manager<R>>(
     manager: &'a M,
   ) -> Option<Window<R>> {
     get_last_active_window(manager)
   }
 
+  /// Get the label and cached state of the last active window.
+  ///
+  /// Falls back to any open window, `None` if no managed window is open.
+  pub fn get_last_active_window_state<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+  ) -> Result<Option<LastActiveWindow>> {
+    let label = match get_last_active_window(manager) {
+      Some(window) => Some(window.label().to_string()),
+      None => {
+        let mut labels = manager.windows()
+          .into_keys()
+          .filter(|label| label != SPLASH_WINDOW_LABEL)
+          .collect::<Vec<String>>();
+
+        labels.sort();
+        labels.into_iter().next()
+      },
+    };
+
+    let label = match label {
+      Some(label) => label,
+      None => return Ok(None),
+    };
+
+    let state = WindowsAPI::get_window_state(manager, &label).unwrap_or_default();
+
+    Ok(Some(LastActiveWindow { label, state }))
+  }
+
   pub fn open_window<'a, R: Runtime, M: Manager<R>>(
     manager: &'a M,
     configuration: OpenConfiguration
-  ) -> Result<Window<R>> {
-    let mut folders_to_open: Vec<PathToOpen> = Vec::new();
-    let folders_to_add: Vec<PathToOpen> = Vec::new();
-    let mut empty_windows_with_backups_to_restore: Vec<EmptyWindowBackupInfo> = Vec::new();
-    let mut files_to_open: FilesToOpen = FilesToOpen {
-      ..Default::default()
-    };
-    let mut empty_to_open: u8 = 0;
+  ) -> Result<OpenedWindow<R>> {
+    if let Some(opened_window) = find_repeated_open(manager, &configuration)? {
+      return Ok(opened_window);
+    }
 
-    // Identify things to open from open config
-    let paths_to_open = get_paths_to_open(
-      manager,
-      &configuration.uris_to_open,
-      &configuration.force_empty_window,
-      &configuration.initial_startup,
-    );
-
-    for path_to_open in &paths_to_open {
-      if path_to_open.folder.is_some() {
-        folders_to_open.push(path_to_open.clone());
-      } else if path_to_open.file.is_some() {
-        let file = path_to_open.file.as_ref().unwrap();
-        files_to_open
-          .files_to_open_or_create
-          .push(file.to_path_buf());
-      } else if path_to_open.backup_path.is_some() {
-        let backup = path_to_open.backup_path.as_ref().unwrap();
-        empty_windows_with_backups_to_restore.push(EmptyWindowBackupInfo {
-          backup_folder: Some(backup.to_path_buf()),
-          ..Default::default()
-        }); // get basename of path for folder name
-      } else {
-        empty_to_open += 1;
-      }
+    let mut trace = OpenTrace::new(configuration.explain);
+
+    let context_window_label = configuration.context_window_label.clone();
+    let request_id = configuration.request_id.clone();
+
+    let open_res = WindowsAPI::open_window_traced(manager, configuration, &mut trace);
+
+    finish_open(manager, context_window_label.as_deref(), request_id.as_deref(), open_res, trace)
+  }
+
+  fn open_window_traced<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    configuration: OpenConfiguration,
+    trace: &mut OpenTrace,
+  ) -> Result<OpenedWindow<R>> {
+    let mut configuration = configuration;
+
+    if let Some(opened_window) = open_without_arguments(manager, &mut configuration, trace)? {
+      return Ok(opened_window.with_configuration(configuration));
     }
 
-    // These are windows to restore because of hot-exit or from previous session (only performed once on startup!)
-    if configuration.initial_startup {
-      // Empty windows with backups are always restored
-      let windows_backup_cache = manager.state::<WindowsBackupCache>();
+    let paths_to_open = resolve_paths_to_open(manager, &configuration)?;
 
-      match windows_backup_cache.0.read() {
-        Ok(cache) => {
-          empty_windows_with_backups_to_restore
-          .extend(cache.backups.empty_windows.iter().cloned());
-        },
-        Err(e) => {
+    let open_res = open_paths(manager, &configuration, &paths_to_open, trace);
 
-        }
-      };
-    } else {
-      empty_windows_with_backups_to_restore.clear();
+    // The paths are open, failing to save them to the recents shouldn't fail the request
+    if let Err(e) = add_recents(manager, &paths_to_open) {
+      log_error(&e);
     }
 
-    // Open based on config
-    let open_res = open(
-      manager,
-      &configuration,
-      folders_to_open,
-      empty_windows_with_backups_to_restore,
-      empty_to_open,
-      files_to_open,
-      folders_to_add,
-    );
+    open_res
+  }
 
-    let windows_recents_cache = manager.state::<WindowsRecentsCache>();
+  /// Open a configuration, reporting the paths that failed instead of failing as a whole.
+  ///
+  /// A failed request is retried per openable and url.
+  pub fn open_with_outcome<'a, R: Runtime, M: Manager<R>>(manager: &'a M, configuration: OpenConfiguration) -> OpenOutcome {
+    open_with_outcome(manager, configuration)
+  }
 
-    let res = match windows_recents_cache.0.write() {
-      Ok(mut cache) => {
-        let recents = paths_to_open.iter().filter_map(|path_to_open| {
-          if path_to_open.folder.is_some() || path_to_open.file.is_some() {
-            Some(path_to_open.clone())
-          }
-          else {
-            None
-          }
-        }).collect::<Vec<PathToOpen>>();      
-        
-        cache.add_recents(recents);
+  /// Open many configurations in one call.
+  ///
+  /// Paths of all configurations are resolved first. Folders already open, or opened by an earlier
+  /// configuration, focus their window instead of opening again. Returns one result per configuration.
+  pub fn open_windows<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    configurations: Vec<OpenConfiguration>,
+  ) -> Vec<Result<OpenedWindow<R>>> {
+    let mut configurations = configurations;
 
-        open_res
-      },
-      Err(e) => {
-        Err(Error::RwLock(e.to_string()))
-      }
-    };
+    let mut traces = configurations
+      .iter()
+      .map(|configuration| OpenTrace::new(configuration.explain))
+      .collect::<Vec<OpenTrace>>();
+
+    // Same checks as a single request, repeated requests get their first window
+    let repeated = configurations
+      .iter()
+      .map(|configuration| find_repeated_open(manager, configuration))
+      .collect::<Vec<Result<Option<OpenedWindow<R>>>>>();
+
+    // Invalid and repeated requests return as they are, like a single request
+    let finished = repeated
+      .iter()
+      .map(|repeated| !matches!(repeated, Ok(None)))
+      .collect::<Vec<bool>>();
+
+    let focused = configurations
+      .iter_mut()
+      .zip(traces.iter_mut())
+      .zip(repeated.iter())
+      .map(|((configuration, trace), repeated)| match repeated {
+        Ok(None) => open_without_arguments(manager, configuration, trace),
+        _ => Ok(None),
+      })
+      .collect::<Vec<Result<Option<OpenedWindow<R>>>>>();
+
+    let resolved = configurations
+      .iter()
+      .zip(focused.iter())
+      .map(|(configuration, focused)| match focused {
+        Ok(None) => resolve_paths_to_open(manager, configuration),
+        _ => Ok(Vec::new()),
+      })
+      .collect::<Vec<Result<Vec<PathToOpen>>>>();
+
+    // Windows by folder, read once for the whole batch
+    let mut folder_lookup = FolderLookup::read(manager).unwrap_or_default();
+
+    let mut recents = Vec::new();
+
+    let results = configurations
+      .iter()
+      .zip(traces.iter_mut())
+      .zip(repeated)
+      .zip(focused)
+      .zip(resolved)
+      .map(|((((configuration, trace), repeated), focused), paths_to_open)| {
+        if let Some(opened_window) = repeated? {
+          return Ok(opened_window);
+        }
+
+        if let Some(opened_window) = focused? {
+          return Ok(opened_window.with_configuration(configuration.clone()));
+        }
+
+        let paths_to_open = paths_to_open?;
+
+        let folders = paths_to_open
+          .iter()
+          .map(|path_to_open| path_to_open.folder.clone())
+          .collect::<Option<Vec<PathBuf>>>()
+          .filter(|folders| !folders.is_empty() && !configuration.force_new_window);
+
+        // Only folders that are already open
+        let existing_window = folders
+          .as_ref()
+          .and_then(|folders| {
+            let labels = folders
+              .iter()
+              .map(|folder| folder_lookup.window_on_folder(folder))
+              .collect::<Option<Vec<&str>>>()?;
+
+            manager.get_window(labels.first()?)
+          });
+
+        recents.extend(paths_to_open.iter().cloned());
+
+        if let Some(window) = existing_window {
+          activate_window(&window, configuration.activation)?;
+
+          trace.step(|| format!("folders already open in window `{}`, activated ({:?})", window.label(), configuration.activation));
+
+          return Ok(
+            OpenedWindow::new(window, OpenPath::ExistingWindow)
+            .with_configuration(configuration.resolved(&paths_to_open))
+          );
+        }
+
+        let opened_window = open_paths(manager, configuration, &paths_to_open, trace)?;
+
+        for folder in folders.into_iter().flatten() {
+          folder_lookup.insert(opened_window.window.label(), Some(&folder));
+        }
+
+        Ok(opened_window)
+      })
+      .collect::<Vec<Result<OpenedWindow<R>>>>();
 
-    res
+    if let Err(e) = add_recents(manager, &recents) {
+      log_error(&e);
+    }
+
+    results
+      .into_iter()
+      .zip(traces)
+      .zip(configurations.iter())
+      .zip(finished)
+      .map(|(((open_res, trace), configuration), finished)| match finished {
+        true => open_res,
+        false => finish_open(
+          manager,
+          configuration.context_window_label.as_deref(),
+          configuration.request_id.as_deref(),
+          open_res,
+          trace,
+        ),
+      })
+      .collect()
   }
   
   pub fn open_empty_window<'a, R: Runtime, M: Manager<R>>(
     manager: &'a M,
     configuration: OpenConfiguration,
     options: WindowOptions
-  ) -> Result<Window<R>> {
+  ) -> Result<OpenedWindow<R>> {
+    if let Some(key) = options.singleton_key.clone() {
+      return open_singleton_window(manager, &key, options);
+    }
+
     let empty_configuration = OpenConfiguration {
       force_empty_window: true,
       force_reuse_window: options.force_reuse_window,
@@ -1243,13 +3291,44 @@ impl WindowsAPI {
       &empty_configuration,
       Vec::new(),
       Vec::new(),
-      0,
+      1,
       FilesToOpen {
         ..Default::default()
       },
       Vec::new(),
+      Vec::new(),
+      &mut OpenTrace::default(),
     )
+    .map(|(opened_window, _used_windows)| opened_window)
+  }
 
+  /// Open a window on a route of the app, loaded as `index.html#/<route>`.
+  ///
+  /// Reuses the window with the singleton key of the options, or else the window on the folder of the options.
+  /// The route is recorded in the window state and restored with the session.
+  pub fn open_route<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    route: &str,
+    options: WindowOptions
+  ) -> Result<OpenedWindow<R>> {
+    open_route(manager, route, options)
+  }
+
+  /// Load an app page or external url in an open window.
+  ///
+  /// External urls must be in the `url_allowlist` setting. With the ready handshake, events to the window
+  /// are held until the new page invokes `window_ready`.
+  pub fn navigate_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, url: WindowUrl) -> Result<()> {
+    let window = manager.get_window(label)
+    .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+    if let WindowUrl::External(url) = &url {
+      if !is_url_allowed(manager, url) {
+        return Err(Error::UrlNotAllowed(url.to_string()));
+      }
+    }
+
+    window.load(&url)
   }
 
   pub fn open_existing_window<'a, R: Runtime, M: Manager<R>>(
@@ -1263,9 +3342,2326 @@ impl WindowsAPI {
     Ok(())
   }
 
-  pub fn send_to_focused() -> () {}
-  pub fn send_to_all() -> () {}
-}
+  /// Open resources from an app deep link.
+  ///
+  /// Parses the link with [`parse_deep_link`] and opens it like any other request,
+  /// reusing existing windows per the settings.
+  pub fn open_deep_link<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    url: &Url,
+  ) -> Result<OpenedWindow<R>> {
+    let configuration = parse_deep_link(url)
+    .ok_or_else(|| Error::InvalidDeepLink(url.to_string()))?;
 
-#[cfg(test)]
-mod tests {}
+    WindowsAPI::open_window(manager, configuration)
+  }
+
+  /// Open files without assembling an [`OpenConfiguration`].
+  pub fn open_files<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    paths: &[PathBuf],
+    behavior: OpenBehavior,
+  ) -> Result<OpenedWindow<R>> {
+    let uris_to_open = paths
+      .iter()
+      .map(|path| WindowOpenable::new(Some(path.clone()), None))
+      .collect();
+
+    WindowsAPI::open_window(manager, behavior.configuration(uris_to_open, OpenContext::Api))
+  }
+
+  /// Open a folder without assembling an [`OpenConfiguration`].
+  pub fn open_folder<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    path: &Path,
+    behavior: OpenBehavior,
+  ) -> Result<OpenedWindow<R>> {
+    let uris_to_open = vec![WindowOpenable::new(None, Some(path.to_path_buf()))];
+
+    WindowsAPI::open_window(manager, behavior.configuration(uris_to_open, OpenContext::Api))
+  }
+
+  /// Pick files with the native dialog and open them.
+  ///
+  /// Returns `None` if the dialog was cancelled.
+  pub fn pick_and_open_files<R: Runtime>(
+    window: &Window<R>,
+    options: PickOptions,
+  ) -> Result<Option<OpenedWindow<R>>> {
+    WindowsAPI::pick_and_open(window, options, false)
+  }
+
+  /// Pick folders with the native dialog and open them.
+  ///
+  /// Returns `None` if the dialog was cancelled.
+  pub fn pick_and_open_folder<R: Runtime>(
+    window: &Window<R>,
+    options: PickOptions,
+  ) -> Result<Option<OpenedWindow<R>>> {
+    WindowsAPI::pick_and_open(window, options, true)
+  }
+
+  fn pick_and_open<R: Runtime>(
+    window: &Window<R>,
+    options: PickOptions,
+    directory: bool,
+  ) -> Result<Option<OpenedWindow<R>>> {
+    match WindowsAPI::pick(window, options, directory)? {
+      Some(configuration) => WindowsAPI::open_window(window, configuration).map(Some),
+      None => Ok(None),
+    }
+  }
+
+  /// Pick files with the native dialog, without opening them.
+  ///
+  /// Returns the configuration opening the picked files, `None` if the dialog was cancelled.
+  pub fn pick_files<R: Runtime>(
+    window: &Window<R>,
+    options: PickOptions,
+  ) -> Result<Option<OpenConfiguration>> {
+    WindowsAPI::pick(window, options, false)
+  }
+
+  /// Pick folders with the native dialog, without opening them.
+  ///
+  /// Returns the configuration opening the picked folders, `None` if the dialog was cancelled.
+  pub fn pick_folder<R: Runtime>(
+    window: &Window<R>,
+    options: PickOptions,
+  ) -> Result<Option<OpenConfiguration>> {
+    WindowsAPI::pick(window, options, true)
+  }
+
+  fn pick<R: Runtime>(
+    window: &Window<R>,
+    options: PickOptions,
+    directory: bool,
+  ) -> Result<Option<OpenConfiguration>> {
+    let paths = match pick_paths(window, &options, directory)? {
+      Some(paths) => paths,
+      None => return Ok(None),
+    };
+
+    let uris_to_open = paths
+      .into_iter()
+      .map(|path| match directory {
+        true => WindowOpenable::new(None, Some(path)),
+        false => WindowOpenable::new(Some(path), None),
+      })
+      .collect();
+
+    Ok(Some(OpenConfiguration {
+      context_window_label: Some(window.label().to_string()),
+      ..options.behavior.configuration(uris_to_open, OpenContext::Dialog)
+    }))
+  }
+
+  /// Get recently opened files and folders.
+  ///
+  /// Entries are annotated with whether they still exist.
+  pub fn get_recents<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<RecentlyOpened> {
+    read_recents(manager)
+  }
+
+  /// Check files of open windows, recents and folder backups against the filesystem.
+  ///
+  /// Windows are told about their missing files with the file missing event.
+  /// Does nothing for [`ResourceValidation::Off`].
+  /// Returns the number of missing entries.
+  pub fn validate_resources<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    validation: ResourceValidation,
+  ) -> Result<usize> {
+    if validation == ResourceValidation::Off {
+      return Ok(0);
+    }
+
+    let remove = validation == ResourceValidation::Remove;
+
+    // Windows learn about their missing files before the recents are validated
+    let missing_files = validate_open_files(manager)?;
+
+    Ok(missing_files + validate_recents(manager, remove)? + validate_backups(manager, remove)?)
+  }
+
+  /// Open or close the devtools of a window.
+  ///
+  /// Release builds need the `devtools` feature.
+  pub fn set_devtools<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, open: bool) -> Result<()> {
+    let window = manager.get_window(label)
+    .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+    toggle_devtools(&window, open)
+  }
+
+  /// Capture the contents of a window to PNG, rendered by its frontend.
+  ///
+  /// Blocks until the window answers the `windows://screenshot-request` event or [`SCREENSHOT_TIMEOUT`] passed.
+  pub fn screenshot_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, to_file: bool) -> Result<Screenshot> {
+    screenshot_window(manager, label, to_file)
+  }
+
+  /// Hide the contents of a window from screen capture, kept when the window is restored.
+  pub fn set_content_protected<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, protected: bool) -> Result<()> {
+    let window = manager.get_window(label)
+    .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+    window.set_content_protected(protected)?;
+
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let mut state = cache.get_item(label)
+    .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+    state.configuration.content_protected = protected;
+
+    cache.set_item(label, state)
+  }
+
+  /// Zoom a window in by the step of the settings, returns the new zoom factor.
+  pub fn zoom_in<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<f64> {
+    zoom_in(manager, label)
+  }
+
+  /// Zoom a window out by the step of the settings, returns the new zoom factor.
+  pub fn zoom_out<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<f64> {
+    zoom_out(manager, label)
+  }
+
+  /// Reset the zoom of a window, returns the new zoom factor.
+  pub fn zoom_reset<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<f64> {
+    zoom_reset(manager, label)
+  }
+
+  /// Set the zoom factor of a window within the bounds of the settings, kept when the window is restored.
+  pub fn set_zoom<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, zoom: f64) -> Result<f64> {
+    set_zoom(manager, label, zoom)
+  }
+
+  /// Show a context menu in a window, rendered by its frontend from the `windows://context-menu` event.
+  ///
+  /// Returns the id the frontend answers `select_context_menu_entry` with.
+  pub fn show_context_menu<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+    entries: Vec<ContextMenuEntry>,
+    position: Option<WindowPosition>,
+  ) -> Result<u64> {
+    show_context_menu(manager, label, entries, position)
+  }
+
+  /// Answer the context menu of a window, `entry_id` is `None` if it was dismissed.
+  ///
+  /// Runs the action of the selected entry and emits `windows://context-menu-selected` to the window.
+  pub fn select_context_menu_entry<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+    id: u64,
+    entry_id: Option<&str>,
+  ) -> Result<()> {
+    select_context_menu_entry(manager, label, id, entry_id)
+  }
+
+  /// Open the window closed last again, returns `None` if no closed window is kept.
+  pub fn reopen_closed_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<Option<OpenedWindow<R>>> {
+    reopen_closed_window(manager)
+  }
+
+  /// Reconfigure a live window.
+  ///
+  /// Applies each set option through the window setters and updates the cached state.
+  pub fn update_window_options<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+    options: WindowOptionsUpdate,
+  ) -> Result<()> {
+    let window = manager.get_window(label)
+    .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+    if let Some(always_on_top) = options.always_on_top {
+      window.set_always_on_top(always_on_top)?;
+    }
+
+    if let Some(decorations) = options.decorations {
+      window.set_decorations(decorations)?;
+    }
+
+    if let Some(resizable) = options.resizable {
+      window.set_resizable(resizable)?;
+    }
+
+    if let Some(skip_taskbar) = options.skip_taskbar {
+      window.set_skip_taskbar(skip_taskbar)?;
+    }
+
+    if let Some(title) = &options.title {
+      window.set_title(title)?;
+    }
+
+    if let Some(size) = &options.min_inner_size {
+      window.set_min_size(Some(LogicalSize::new(size.width, size.height)))?;
+    }
+
+    if let Some(size) = &options.max_inner_size {
+      window.set_max_size(Some(LogicalSize::new(size.width, size.height)))?;
+    }
+
+    if let Some(size) = &options.inner_size {
+      window.set_size(LogicalSize::new(size.width, size.height))?;
+    }
+
+    if let Some(position) = &options.position {
+      window.set_position(LogicalPosition::new(position.x, position.y))?;
+    }
+
+    if options.center == Some(true) {
+      window.center()?;
+    }
+
+    match options.maximized {
+      Some(true) => window.maximize()?,
+      Some(false) => window.unmaximize()?,
+      None => {},
+    };
+
+    if let Some(fullscreen) = options.fullscreen {
+      window.set_fullscreen(fullscreen)?;
+    }
+
+    match options.visible {
+      Some(true) => window.show()?,
+      Some(false) => window.hide()?,
+      None => {},
+    };
+
+    if options.focus == Some(true) {
+      window.set_focus()?;
+    }
+
+    // Keep restore info in sync
+    if options.maximized.is_some() || options.fullscreen.is_some() || options.always_on_top.is_some() {
+      let windows_state_cache = manager.state::<WindowsStateCache>();
+
+      let mut cache = windows_state_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      if let Some(mut state) = cache.get_item(label) {
+        if let Some(maximized) = options.maximized {
+          state.configuration.maximized = maximized;
+        }
+
+        if let Some(fullscreen) = options.fullscreen {
+          state.configuration.full_screen = fullscreen;
+        }
+
+        if let Some(always_on_top) = options.always_on_top {
+          state.configuration.always_on_top = always_on_top;
+        }
+
+        state.mode = if state.configuration.full_screen {
+          WindowMode::Fullscreen
+        } else if state.configuration.maximized {
+          WindowMode::Maximized
+        } else {
+          WindowMode::Normal
+        };
+
+        cache.set_item(label, state)?;
+      }
+    }
+
+    if options.theme.is_some() {
+      theme::set_theme(manager, Some(label), options.theme)?;
+    }
+
+    Ok(())
+  }
+
+  /// Get the cached state of a window.
+  pub fn get_window_state<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<WindowState> {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    cache.get_item(label)
+    .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))
+  }
+
+  /// Get the folder a window writes its hot-exit data to.
+  ///
+  /// Returns `None` if the window has no backup.
+  pub fn get_backup_path<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<Option<PathBuf>> {
+    if cfg!(not(feature = "backups")) {
+      return Err(Error::FeatureDisabled("backups".to_string()));
+    }
+
+    WindowsAPI::get_window_state(manager, label).map(|state| state.backup_path)
+  }
+
+  /// Patch the cached state of a window.
+  ///
+  /// The patch is validated before anything changes. Returns the updated state.
+  pub fn set_window_state<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+    patch: WindowStatePatch,
+  ) -> Result<WindowState> {
+    let mut state = WindowsAPI::get_window_state(manager, label)?;
+
+    if let Some(folder) = &patch.folder {
+      if !folder.is_absolute() || !folder.is_dir() {
+        return Err(Error::FolderNotFound(folder.display().to_string()));
+      }
+    }
+
+    let window = manager.get_window(label);
+
+    // Apply the mode first, the window may refuse it
+    if let (Some(mode), Some(window)) = (&patch.mode, &window) {
+      match mode {
+        WindowMode::Fullscreen => window.set_fullscreen(true)?,
+        WindowMode::Maximized => window.maximize()?,
+        WindowMode::Minimized => window.minimize()?,
+        WindowMode::Normal => {
+          window.set_fullscreen(false)?;
+          window.unmaximize()?;
+          window.unminimize()?;
+        },
+      };
+    }
+
+    {
+      let windows_state_cache = manager.state::<WindowsStateCache>();
+
+      let mut cache = windows_state_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      if let Some(user_data) = patch.user_data {
+        state.user_data = Some(user_data);
+      }
+
+      if let Some(mode) = patch.mode {
+        state.configuration.full_screen = mode == WindowMode::Fullscreen;
+        state.configuration.maximized = mode == WindowMode::Maximized;
+        state.mode = mode;
+      }
+
+      cache.set_item(label, state)?;
+    }
+
+    // Folder changes go through the usual trust and watch handling
+    if let Some(folder) = patch.folder {
+      let configuration = WindowConfiguration {
+        folder: Some(folder),
+        ..WindowsAPI::get_window_state(manager, label)?.configuration
+      };
+
+      if window.is_some() {
+        save_window_state(manager, label, configuration)?;
+      } else {
+        let windows_state_cache = manager.state::<WindowsStateCache>();
+
+        let mut cache = windows_state_cache.0.write()
+        .map_err(|e| Error::RwLock(e.to_string()))?;
+
+        cache.set_item(label, WindowState {
+          folder: configuration.folder.clone(),
+          configuration,
+          ..WindowsAPI::get_window_state(manager, label)?
+        })?;
+      }
+    }
+
+    WindowsAPI::get_window_state(manager, label)
+  }
+
+  /// Get labels of open windows by focus order, most recently focused first.
+  pub fn get_focus_history<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Vec<String> {
+    get_focus_history(manager)
+  }
+
+  /// Get labels of open windows, front to back.
+  pub fn get_window_stack<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<Vec<String>> {
+    stack::get_window_stack(manager)
+  }
+
+  /// Restack open windows in the order of the last session.
+  ///
+  /// Applied automatically after the session is restored.
+  pub fn restore_stacking_order<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<()> {
+    restore_stacking_order(manager)
+  }
+
+  /// Get the directory where opening resources should start.
+  ///
+  /// Picks the folder of the last active window, else the most recent folder, else the home directory.
+  pub fn get_default_open_directory<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Option<PathBuf> {
+    get_default_open_directory(manager)
+  }
+
+  /// Focus the window that was focused before the current one.
+  ///
+  /// Returns `None` if there is no other window.
+  pub fn focus_previous_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<Option<Window<R>>> {
+    let previous_window = get_focus_history(manager)
+      .get(1)
+      .and_then(|label| manager.get_window(label));
+
+    if let Some(window) = &previous_window {
+      window.set_focus()?;
+    }
+
+    Ok(previous_window)
+  }
+
+  /// Bring a window created by the app under plugin management.
+  ///
+  /// Returns whether the window was adopted, `false` if it was already managed.
+  pub fn adopt_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<bool> {
+    adopt_window(manager, label)
+  }
+
+  /// List windows that are open, managed or not, with their folder and group.
+  pub fn list_windows<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<Vec<WindowInfo>> {
+    group::list_windows(manager)
+  }
+
+  /// Get the open windows for a window switcher, most recently focused first.
+  ///
+  /// With `thumbnails` each entry has the screenshot its window answered within [`THUMBNAIL_TIMEOUT`].
+  pub fn get_switcher_entries<'a, R: Runtime, M: Manager<R>>(manager: &'a M, thumbnails: bool) -> Result<Vec<SwitcherEntry>> {
+    get_switcher_entries(manager, thumbnails)
+  }
+
+  /// Assign a window to a group, or remove it from its group if `group` is `None`.
+  pub fn set_window_group<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+    group: Option<String>,
+  ) -> Result<()> {
+    group::set_window_group(manager, label, group)
+  }
+
+  /// Bring all windows of a group to front.
+  pub fn focus_group<'a, R: Runtime, M: Manager<R>>(manager: &'a M, group: &str) -> Result<()> {
+    group::focus_group(manager, group)
+  }
+
+  /// Close all windows of a group.
+  pub fn close_group<'a, R: Runtime, M: Manager<R>>(manager: &'a M, group: &str) -> Result<()> {
+    group::close_group(manager, group)
+  }
+
+  /// Minimize all windows of a group.
+  pub fn minimize_group<'a, R: Runtime, M: Manager<R>>(manager: &'a M, group: &str) -> Result<()> {
+    group::minimize_group(manager, group)
+  }
+
+  /// Save the monitor, bounds and stacking of all windows as a named layout.
+  pub fn save_layout<'a, R: Runtime, M: Manager<R>>(manager: &'a M, name: &str) -> Result<()> {
+    layout::save_layout(manager, name)
+  }
+
+  /// Re-apply a named layout to the windows that are still open.
+  pub fn apply_layout<'a, R: Runtime, M: Manager<R>>(manager: &'a M, name: &str) -> Result<()> {
+    layout::apply_layout(manager, name)
+  }
+
+  /// Get the work areas, scale factors and names of all available monitors.
+  pub fn get_monitors<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<Vec<MonitorInfo>> {
+    get_monitors(manager)
+  }
+
+  /// Get the info of the monitor a window is on.
+  pub fn get_window_monitor<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<Option<MonitorInfo>> {
+    get_window_monitor_info(manager, label)
+  }
+
+  /// Snap a window to a region of its monitor, e.g. the left half.
+  pub fn snap_window<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, region: SnapRegion) -> Result<()> {
+    snap::snap_window(manager, label, region)
+  }
+
+  /// Arrange windows in a layout on the monitor of the first window.
+  pub fn tile_windows<'a, R: Runtime, M: Manager<R>>(manager: &'a M, labels: &[String], layout: TileLayout) -> Result<()> {
+    snap::tile_windows(manager, labels, layout)
+  }
+
+  /// Set the theme of a window, or of all windows if `label` is `None`.
+  ///
+  /// Delivered as a `windows://theme-changed` event, a `None` theme follows the system theme.
+  pub fn set_theme<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: Option<&str>,
+    theme: Option<Theme>,
+  ) -> Result<()> {
+    theme::set_theme(manager, label, theme)
+  }
+
+  /// Get info about how the previous session ended.
+  pub fn get_startup_info<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<StartupInfo> {
+    let startup_info = manager.state::<WindowsStartupInfo>();
+
+    let mut info = startup_info.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .clone();
+
+    // Session may not be restored yet
+    if !info.was_restarted {
+      info.was_restarted = manager.state::<WindowsStateCache>().0.read()
+      .map_err(|e| Error::RwLock(e.to_string()))?
+      .state()
+      .was_restarted;
+    }
+
+    Ok(info)
+  }
+
+  /// Restart the app and restore the same windows on the next launch.
+  ///
+  /// Flushes window states and backups first, e.g. for settings changes or updates.
+  pub fn relaunch_preserving_session<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<()> {
+    let labels = manager.windows()
+      .into_keys()
+      .filter(|label| label != SPLASH_WINDOW_LABEL)
+      .collect::<Vec<String>>();
+
+    {
+      let windows_state_cache = manager.state::<WindowsStateCache>();
+
+      let mut cache = windows_state_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      let state = cache.state_mut();
+
+      state.was_restarted = true;
+      state.restart_windows = labels
+        .into_iter()
+        .filter(|label| state.opened_windows.contains_key(label))
+        .collect();
+    }
+
+    WindowsAPI::flush(manager)?;
+
+    // Relaunch is a clean exit
+    clear_running_sentinel(manager);
+
+    manager.app_handle().restart();
+
+    Ok(())
+  }
+
+  /// Write window states, recents, backups, trusted folders and layouts to disk.
+  pub fn flush<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<()> {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    cache.fold_volatile();
+    cache.update_last_active_window();
+    cache.save()?;
+
+    drop(cache);
+
+    #[cfg(feature = "recents")]
+    manager.state::<WindowsRecentsCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .save()?;
+
+    #[cfg(feature = "backups")]
+    manager.state::<WindowsBackupCache>().0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .save();
+
+    manager.state::<WindowsTrustCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .save()?;
+
+    manager.state::<WindowsLayoutCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .save()
+  }
+
+  /// Get the time windows took to load their page and declare readiness, to track spawn latency.
+  pub fn get_startup_metrics<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<StartupMetrics> {
+    metrics::get_startup_metrics(manager)
+  }
+
+  /// Get storage status, cache sizes, lock contention and recent internal errors for issue reports.
+  pub fn get_diagnostics<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<Diagnostics> {
+    diagnostics::get_diagnostics(manager)
+  }
+
+  /// Export window states, backup metadata, recents and settings as a json snapshot.
+  pub fn export_state<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<String> {
+    export_state(manager)
+  }
+
+  /// Replace window states, backup metadata, recents and settings with a json snapshot.
+  ///
+  /// Fails without changing anything if the snapshot is invalid.
+  pub fn import_state<'a, R: Runtime, M: Manager<R>>(manager: &'a M, json: &str) -> Result<()> {
+    import_state(manager, json)
+  }
+
+  /// Answer a `windows://before-quit` event.
+  ///
+  /// A veto cancels the exit, the app exits once every window allowed it.
+  pub fn acknowledge_quit<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, allow: bool) -> Result<()> {
+    acknowledge_quit(manager, label, allow)
+  }
+
+  /// Answer a `windows://close-requested` event.
+  ///
+  /// Closes the window if `allow` is set, otherwise the window stays open.
+  pub fn confirm_close<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, allow: bool) -> Result<()> {
+    confirm_close(manager, label, allow)
+  }
+
+  /// Declare the frontend of a window ready to handle events.
+  ///
+  /// Sends the events held for the window with the `ready_handshake` setting and emits `windows://ready`.
+  pub fn window_ready<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str) -> Result<()> {
+    handle_window_ready(manager, label)
+  }
+
+  /// Report whether a window has pending work, e.g. unsaved changes.
+  ///
+  /// Used by the [`ConfirmClose::PendingWork`] mode.
+  pub fn set_pending_work<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, pending_work: bool) -> Result<()> {
+    manager.state::<WindowsCloseState>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .set_pending_work(label, pending_work);
+
+    Ok(())
+  }
+
+  /// Mark the document of a window as having unsaved changes, or as saved.
+  ///
+  /// Dirty windows always confirm closing, keep their backups and aren't closed by `close_when_empty`.
+  pub fn set_dirty<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, dirty: bool) -> Result<()> {
+    close::set_dirty(manager, label, dirty)
+  }
+
+  /// Trust a folder and the folders inside it.
+  ///
+  /// Windows waiting on the folder leave restricted mode.
+  pub fn trust_folder<'a, R: Runtime, M: Manager<R>>(manager: &'a M, folder: PathBuf) -> Result<()> {
+    manager.state::<WindowsTrustCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .trust(&folder)?;
+
+    update_window_trust(manager, &folder, true)
+  }
+
+  /// Revoke trust of a folder and of trusted folders inside it.
+  ///
+  /// Windows on the folder go back to restricted mode and get a new trust request.
+  pub fn revoke_trust<'a, R: Runtime, M: Manager<R>>(manager: &'a M, folder: PathBuf) -> Result<()> {
+    manager.state::<WindowsTrustCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .revoke(&folder)?;
+
+    update_window_trust(manager, &folder, false)
+  }
+
+  /// Prune cached window states per the `state_retention` setting.
+  ///
+  /// Returns the number of removed states.
+  pub fn prune_state<'a, R: Runtime, M: Manager<R>>(manager: &'a M) -> Result<usize> {
+    let retention = manager.state::<WindowsSettings>().0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .state_retention
+    .clone();
+
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let pruned = cache.prune(
+      retention.max_age_days.map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+      retention.max_entries,
+    )?;
+
+    // Cached before the windows were excluded, they must not be restored
+    let excluded = cache.state().opened_windows
+      .keys()
+      .filter(|label| is_excluded(manager, label))
+      .cloned()
+      .collect::<Vec<String>>();
+
+    for label in &excluded {
+      cache.remove_item(label)?;
+    }
+
+    if !excluded.is_empty() {
+      cache.save()?;
+    }
+
+    Ok(pruned + excluded.len())
+  }
+
+  /// Open a copy of an existing window.
+  ///
+  /// Uses the cached [`WindowState`] of the source window to open the same folder and files
+  /// in a new window, offset from the source window position, with the same route, theme,
+  /// webview options, group, zoom and user data.
+  pub fn duplicate_window<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+  ) -> Result<OpenedWindow<R>> {
+    let source_window = manager.get_window(label)
+    .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+    let source_state = {
+      let windows_state_cache = manager.state::<WindowsStateCache>();
+
+      let cache = windows_state_cache.0.read()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      cache.get_item(label)
+      .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?
+    };
+
+    let scale_factor = source_window.scale_factor()?;
+    let position = source_window.outer_position()?.to_logical::<f64>(scale_factor);
+    let size = source_window.inner_size()?.to_logical::<f64>(scale_factor);
+
+    let opened_window = open_in_webview_window(
+      manager,
+      WindowOptions {
+        folder: source_state.configuration.folder.clone(),
+        files_to_open: FilesToOpen {
+          files_to_open_or_create: source_state.configuration.files_to_open_or_create.clone(),
+        },
+        fullscreen: Some(source_state.configuration.full_screen),
+        maximized: Some(source_state.configuration.maximized),
+        position: Some(WindowPosition {
+          x: position.x + DUPLICATE_WINDOW_OFFSET,
+          y: position.y + DUPLICATE_WINDOW_OFFSET,
+        }),
+        inner_size: Some(WindowSize {
+          width: size.width,
+          height: size.height,
+        }),
+        theme: source_state.configuration.theme,
+        webview: source_state.configuration.webview.clone(),
+        app_route: source_state.configuration.app_route.clone(),
+        force_new_window: true,
+        ..Default::default()
+      },
+    )?;
+
+    let window = &opened_window.window;
+
+    // Carry over the state the options don't cover
+    if source_state.user_data.is_some() {
+      WindowsAPI::set_window_state(manager, window.label(), WindowStatePatch {
+        user_data: source_state.user_data,
+        ..Default::default()
+      })?;
+    }
+
+    if source_state.group.is_some() {
+      group::set_window_group(manager, window.label(), source_state.group)?;
+    }
+
+    if let Some(zoom) = source_state.zoom {
+      set_zoom(manager, window.label(), zoom)?;
+    }
+
+    Ok(opened_window)
+  }
+
+  /// Move a file or folder from one window to another.
+  ///
+  /// Removes the resource from the source window state, delivers it to the destination
+  /// window through the open files/add folders events and hands over the folder backup.
+  ///
+  /// Fails if the resource isn't open in the source window, or if it's a folder and the
+  /// destination window has another folder open.
+  pub fn move_resource<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    from_label: &str,
+    to_label: &str,
+    path: PathBuf,
+  ) -> Result<OpenedWindow<R>> {
+    if from_label == to_label {
+      return Err(Error::MoveRejected(format!("`{}` is already open in window `{}`", path.display(), to_label)));
+    }
+
+    let destination_window = manager.get_window(to_label)
+    .ok_or_else(|| Error::WindowNotFound(to_label.to_string()))?;
+
+    let is_folder = path.is_dir();
+
+    // Update source and destination states
+    {
+      let windows_state_cache = manager.state::<WindowsStateCache>();
+
+      let mut cache = windows_state_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      let mut source_state = cache.get_item(from_label)
+      .ok_or_else(|| Error::WindowStateWithLabelNotFound(from_label.to_string()))?;
+      let mut destination_state = cache.get_item(to_label).unwrap_or_default();
+
+      let in_source = match is_folder {
+        true => source_state.folder.as_ref() == Some(&path),
+        false => source_state.configuration.files_to_open_or_create.contains(&path),
+      };
+
+      if !in_source {
+        return Err(Error::ResourceNotInWindow(path.display().to_string(), from_label.to_string()));
+      }
+
+      if is_folder {
+        // A window has a single folder, the destination can't take a second one
+        if let Some(folder) = &destination_state.folder {
+          return Err(Error::MoveRejected(format!("window `{}` already has folder `{}` open", to_label, folder.display())));
+        }
+
+        source_state.folder = None;
+        source_state.configuration.folder = None;
+
+        destination_state.folder = Some(path.clone());
+        destination_state.configuration.folder = Some(path.clone());
+      } else {
+        source_state.configuration.files_to_open_or_create.retain(|file| file != &path);
+
+        if !destination_state.configuration.files_to_open_or_create.contains(&path) {
+          destination_state.configuration.files_to_open_or_create.push(path.clone());
+        }
+      }
+
+      let source_folder = source_state.folder.clone();
+      let destination_folder = destination_state.folder.clone();
+
+      cache.set_item(from_label, source_state)?;
+      cache.set_item(to_label, destination_state)?;
+
+      drop(cache);
+
+      watch_folder(manager, from_label, source_folder.as_ref())?;
+      watch_folder(manager, to_label, destination_folder.as_ref())?;
+    }
+
+    // Keep backups consistent with the updated states
+    #[cfg(feature = "backups")]
+    if is_folder {
+      let windows_backup_cache = manager.state::<WindowsBackupCache>();
+
+      let mut cache = windows_backup_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      cache.move_folder_backup(&path, to_label);
+    }
+
+    // Let the source window drop the resource
+    if let Some(source_window) = manager.get_window(from_label) {
+      let event = if is_folder { WINDOW_CLOSE_FOLDER_EVENT } else { WINDOW_CLOSE_FILE_EVENT };
+
+      source_window.emit(event, &path)?;
+    }
+
+    // Deliver to destination, also brings it to front
+    if is_folder {
+      let folders_to_add = resolve_file_path(&path).into_iter().collect::<Vec<PathToOpen>>();
+
+      add_folders_to_existing_window(&destination_window, &folders_to_add, ActivationPolicy::Focus, OpenContext::Api)?;
+    } else {
+      open_files_in_existing_window(
+        &OpenConfiguration {
+          context: OpenContext::Api,
+          ..Default::default()
+        },
+        &destination_window,
+        &FilesToOpen {
+          files_to_open_or_create: vec![path],
+        },
+      )?;
+    }
+
+    Ok(OpenedWindow::new(destination_window, OpenPath::ExistingWindow))
+  }
+
+  /// Send a message to the focused window, if it listens to the channel.
+  ///
+  /// Returns the label of the window the message was sent to.
+  pub fn send_to_focused<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    channel: &str,
+    payload: JsonValue,
+  ) -> Result<Option<String>> {
+    let window = match get_focused_window(manager) {
+      Some(window) => window,
+      None => return Ok(None),
+    };
+
+    if !subscribers(manager, channel, &[])?.iter().any(|label| label == window.label()) {
+      return Ok(None);
+    }
+
+    window.emit(channel, payload)?;
+
+    Ok(Some(window.label().to_string()))
+  }
+
+  /// Send a message to windows subscribed to the channel, except the ignored ones.
+  ///
+  /// A sticky message is kept as the latest of its channel and replayed to windows once they are ready.
+  pub fn send_to_all<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    channel: &str,
+    payload: JsonValue,
+    window_labels_to_ignore: &[String],
+    sticky: bool,
+  ) -> Result<()> {
+    match sticky {
+      true => emit_sticky(manager, channel, payload, window_labels_to_ignore),
+      false => emit_to_subscribers(manager, channel, payload, window_labels_to_ignore),
+    }
+  }
+
+  /// Send a message to windows subscribed to the channel and wait for each to acknowledge it.
+  ///
+  /// Windows get a [`BroadcastPayload`] and answer with [`WindowsAPI::acknowledge_broadcast`].
+  /// Blocks until every window answered or the timeout passed, returns one reply per window.
+  pub fn send_to_all_and_wait<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    channel: &str,
+    payload: JsonValue,
+    window_labels_to_ignore: &[String],
+    timeout: Duration,
+  ) -> Result<Vec<BroadcastReply>> {
+    send_and_wait(manager, channel, payload, window_labels_to_ignore, timeout)
+  }
+
+  /// Answer a broadcast sent with [`WindowsAPI::send_to_all_and_wait`], failed if `error` is set.
+  pub fn acknowledge_broadcast<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    id: u64,
+    label: &str,
+    result: Option<JsonValue>,
+    error: Option<String>,
+  ) -> Result<()> {
+    acknowledge_broadcast(manager, id, label, result, error)
+  }
+
+  /// Declare plugin event channels a window listens to.
+  ///
+  /// Broadcasts reach every window until it subscribes, then only its channels.
+  pub fn subscribe<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, channels: Vec<String>) -> Result<()> {
+    subscribe(manager, label, channels)
+  }
+
+  /// Stop listening to channels, all of them if `channels` is empty.
+  pub fn unsubscribe<'a, R: Runtime, M: Manager<R>>(manager: &'a M, label: &str, channels: Vec<String>) -> Result<()> {
+    unsubscribe(manager, label, channels)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    env::temp_dir,
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+  };
+
+  use super::{
+    expand::expand_folder,
+    extract_paths,
+    get_open_decisions,
+    init_script,
+    routing::route_of,
+    screenshot::decode_png,
+    is_path_in_folder,
+    resolve_file_path,
+    resolve_openable,
+    FileType,
+    FolderExpansion,
+    InnerWindowsSettings,
+    OpenConfiguration,
+    OpenContext,
+    OpenContextPolicy,
+    OpenInNewWindow,
+    OpenRoute,
+    PermissionSettings,
+    RoutingRule,
+    WindowOpenable,
+    WindowPermission
+  };
+
+  fn missing(path: &str) -> PathBuf {
+    temp_dir().join("tauri-plugin-windows-missing").join(path)
+  }
+
+  /// Path components with unicode, spaces, dots and shared prefixes.
+  const COMPONENTS: [&str; 6] = ["a", "ab", "ünïcødé", "日本語", "with space", "dots.in.name"];
+
+  /// Relative paths of one to three components.
+  fn relative_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    for a in COMPONENTS {
+      paths.push(PathBuf::from(a));
+
+      for b in COMPONENTS {
+        paths.push(Path::new(a).join(b));
+
+        for c in COMPONENTS {
+          paths.push(Path::new(a).join(b).join(c));
+        }
+      }
+    }
+
+    paths
+  }
+
+  /// Spellings of a folder that point to the same location.
+  fn folder_spellings(folder: &Path) -> Vec<PathBuf> {
+    vec![
+      folder.to_path_buf(),
+      PathBuf::from(format!("{}{}", folder.display(), MAIN_SEPARATOR)),
+      folder.join("."),
+      folder.join("a").join(".."),
+    ]
+  }
+
+  #[test]
+  fn permissions_match_exact_label_before_prefix() {
+    let mut permissions = PermissionSettings::default();
+    assert!(permissions.allows("main", WindowPermission::Settings));
+
+    permissions.windows.insert("editor-*".to_string(), [WindowPermission::OpenFiles].into_iter().collect());
+    permissions.windows.insert("editor-settings".to_string(), [WindowPermission::Settings].into_iter().collect());
+
+    assert!(permissions.allows("editor-1", WindowPermission::OpenFiles));
+    assert!(!permissions.allows("editor-1", WindowPermission::Settings));
+    assert!(permissions.allows("editor-settings", WindowPermission::Settings));
+    assert!(!permissions.allows("editor-settings", WindowPermission::OpenFiles));
+    assert!(!permissions.allows("main", WindowPermission::OpenFiles));
+  }
+
+  #[test]
+  fn routes_by_first_matching_pattern() {
+    let rule = |pattern: &str, route: OpenRoute| RoutingRule { pattern: pattern.to_string(), route };
+
+    let rules = vec![
+      rule("[", OpenRoute::Ignore),
+      rule("**/assets/*", OpenRoute::OpenInExisting),
+      rule("*.png", OpenRoute::EmitCustomEvent("windows://open_image".to_string())),
+      rule("*.zip", OpenRoute::Ignore),
+    ];
+
+    assert_eq!(route_of(&rules, &missing("assets/logo.png")), Some(&OpenRoute::OpenInExisting));
+    assert_eq!(route_of(&rules, &missing("logo.png")), Some(&OpenRoute::EmitCustomEvent("windows://open_image".to_string())));
+    assert_eq!(route_of(&rules, &missing("archive.zip")), Some(&OpenRoute::Ignore));
+    assert_eq!(route_of(&rules, &missing("main.rs")), None);
+  }
+
+  #[test]
+  fn resolve_empty_openable() {
+    let paths = resolve_openable(&WindowOpenable::new(None, None)).unwrap();
+
+    assert!(paths.is_empty());
+  }
+
+  #[test]
+  fn resolve_missing_folder() {
+    let folder = missing("folder");
+    let paths = resolve_openable(&WindowOpenable::new(None, Some(folder.clone()))).unwrap();
+
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].folder, Some(folder));
+    assert!(paths[0].path_type == FileType::Directory);
+    assert!(!paths[0].exists);
+  }
+
+  #[test]
+  fn resolve_existing_folder() {
+    let folder = temp_dir();
+    let paths = resolve_openable(&WindowOpenable::new(None, Some(folder.clone()))).unwrap();
+
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].folder, Some(folder));
+    assert!(paths[0].exists);
+  }
+
+  #[test]
+  fn resolve_missing_file() {
+    let file = missing("file.txt");
+    let paths = resolve_openable(&WindowOpenable::new(Some(file.clone()), None)).unwrap();
+
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].file, Some(file));
+    assert!(paths[0].path_type == FileType::File);
+    assert!(!paths[0].exists);
+  }
+
+  #[test]
+  fn resolve_file_that_is_a_folder() {
+    let folder = temp_dir();
+    let paths = resolve_openable(&WindowOpenable::new(Some(folder.clone()), None)).unwrap();
+
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].folder, Some(folder));
+    assert_eq!(paths[0].file, None);
+  }
+
+  #[test]
+  fn resolve_relative_file_in_folder() {
+    let folder = missing("folder");
+    let openable = WindowOpenable::new(Some(PathBuf::from("src/main.rs")), Some(folder.clone()));
+    let paths = resolve_openable(&openable).unwrap();
+
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0].folder, Some(folder.clone()));
+    assert_eq!(paths[1].file, Some(folder.join("src/main.rs")));
+  }
+
+  #[test]
+  fn resolve_absolute_file_in_folder() {
+    let folder = missing("folder");
+    let file = folder.join("main.rs");
+    let paths = resolve_openable(&WindowOpenable::new(Some(file.clone()), Some(folder))).unwrap();
+
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[1].file, Some(file));
+  }
+
+  #[test]
+  fn resolve_file_outside_folder() {
+    let folder = missing("folder");
+
+    let absolute = WindowOpenable::new(Some(missing("other/main.rs")), Some(folder.clone()));
+    let relative = WindowOpenable::new(Some(PathBuf::from("../other/main.rs")), Some(folder));
+
+    assert!(resolve_openable(&absolute).is_err());
+    assert!(resolve_openable(&relative).is_err());
+  }
+
+  #[test]
+  fn resolve_any_missing_file() {
+    for relative in relative_paths() {
+      let file = missing("files").join(&relative);
+      let path = resolve_file_path(&file).unwrap();
+
+      assert_eq!(path.file, Some(file), "{:?}", relative);
+      assert!(!path.exists);
+    }
+  }
+
+  #[test]
+  fn extract_files_in_folders() {
+    let folder = missing("folder");
+
+    let openables = relative_paths()
+      .into_iter()
+      .map(|relative| WindowOpenable::new(Some(relative), Some(folder.clone())))
+      .chain(Some(WindowOpenable::new(Some(PathBuf::from("../outside")), Some(folder.clone()))))
+      .collect::<Vec<WindowOpenable>>();
+
+    let paths = extract_paths(&Some(openables));
+
+    // The file outside the folder is skipped
+    assert_eq!(paths.len(), relative_paths().len() * 2);
+
+    for path in paths.iter().filter_map(|path| path.file.as_ref()) {
+      assert!(is_path_in_folder(path, &folder), "{:?}", path);
+    }
+  }
+
+  #[test]
+  fn path_in_folder_with_any_spelling() {
+    for folder in [missing("folder"), temp_dir()] {
+      for spelling in folder_spellings(&folder) {
+        assert!(is_path_in_folder(&folder, &spelling), "{:?}", spelling);
+
+        for relative in relative_paths() {
+          assert!(is_path_in_folder(&spelling.join(&relative), &folder), "{:?} {:?}", spelling, relative);
+          assert!(is_path_in_folder(&folder.join(&relative), &spelling), "{:?} {:?}", spelling, relative);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn path_in_sibling_with_shared_prefix() {
+    let parent = missing("siblings");
+
+    for name in COMPONENTS {
+      let folder = parent.join(name);
+      let sibling = parent.join(format!("{}-sibling", name));
+
+      for relative in relative_paths() {
+        assert!(!is_path_in_folder(&sibling.join(&relative), &folder), "{:?} {:?}", name, relative);
+        assert!(!is_path_in_folder(&folder, &folder.join(&relative)), "{:?} {:?}", name, relative);
+      }
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn path_in_folder_through_symlink() {
+    use std::{fs::{create_dir_all, remove_dir_all}, os::unix::fs::symlink};
+
+    let root = temp_dir().join(format!("tauri-plugin-windows-{}", uuid::Uuid::new_v4().simple()));
+    let folder = root.join("folder");
+    let link = root.join("link");
+
+    create_dir_all(&folder).unwrap();
+    symlink(&folder, &link).unwrap();
+
+    for relative in relative_paths() {
+      assert!(is_path_in_folder(&link.join(&relative), &folder), "{:?}", relative);
+      assert!(is_path_in_folder(&folder.join(&relative), &link), "{:?}", relative);
+    }
+
+    remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn expands_folder_respecting_ignores() {
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    let root = temp_dir().join(format!("tauri-plugin-windows-{}", uuid::Uuid::new_v4().simple()));
+
+    for file in ["a.md", "b.txt", "docs/c.md", "docs/drafts/d.md", "docs/keep.md", "build/e.md", "deep/1/2/f.md"] {
+      create_dir_all(root.join(file).parent().unwrap()).unwrap();
+      write(root.join(file), "").unwrap();
+    }
+
+    write(root.join(".gitignore"), "# build output\nbuild/\n").unwrap();
+    write(root.join("docs/.gitignore"), "*.md\n!keep.md\n").unwrap();
+
+    let expansion = FolderExpansion {
+      pattern: "**/*.md".to_string(),
+      max_depth: 2,
+      ..Default::default()
+    };
+
+    let relative = |files: Vec<PathBuf>| files.iter().map(|file| file.strip_prefix(&root).unwrap().to_path_buf()).collect::<Vec<PathBuf>>();
+
+    assert_eq!(relative(expand_folder(&root, &expansion).unwrap()), vec![PathBuf::from("a.md"), Path::new("docs").join("keep.md")]);
+
+    let expansion = FolderExpansion {
+      pattern: "*.md".to_string(),
+      ignore: vec!["a.*".to_string()],
+      use_gitignore: false,
+      ..Default::default()
+    };
+
+    assert!(expand_folder(&root, &expansion).unwrap().is_empty());
+
+    let expansion = FolderExpansion {
+      use_gitignore: false,
+      max_files: 3,
+      ..Default::default()
+    };
+
+    assert_eq!(expand_folder(&root, &expansion).unwrap().len(), 3);
+
+    remove_dir_all(&root).unwrap();
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn path_in_unc_folder() {
+    let folder = PathBuf::from(r"\\server\share\folder");
+
+    for spelling in folder_spellings(&folder) {
+      for relative in relative_paths() {
+        assert!(is_path_in_folder(&spelling.join(&relative), &folder), "{:?} {:?}", spelling, relative);
+      }
+    }
+
+    assert!(!is_path_in_folder(Path::new(r"\\server\other\folder"), &folder));
+  }
+
+  #[test]
+  fn decodes_png_screenshot() {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let mut png = Vec::new();
+
+    {
+      let mut encoder = png::Encoder::new(&mut png, 2, 3);
+      encoder.set_color(png::ColorType::Rgba);
+      encoder.set_depth(png::BitDepth::Eight);
+
+      encoder.write_header().unwrap().write_image_data(&[0; 2 * 3 * 4]).unwrap();
+    }
+
+    let (bytes, width, height) = decode_png(&STANDARD.encode(&png)).unwrap();
+
+    assert_eq!((bytes, width, height), (png, 2, 3));
+    assert!(decode_png("not a png").is_err());
+    assert!(decode_png(&STANDARD.encode(b"not a png")).is_err());
+  }
+
+  #[test]
+  fn init_script_sets_payload_global() {
+    let script = init_script(&serde_json::json!({ "purpose": "compare" })).unwrap();
+
+    assert_eq!(script, r#"window.__WINDOWS_INIT__ = {"purpose":"compare"};"#);
+  }
+
+  fn settings(folders: OpenInNewWindow, files: OpenInNewWindow, cli: OpenInNewWindow) -> InnerWindowsSettings {
+    InnerWindowsSettings {
+      open_folders_in_new_window: folders,
+      open_files_in_new_window: files,
+      open_context_policy: OpenContextPolicy {
+        cli,
+        ..Default::default()
+      },
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn open_options_precedence() {
+    use OpenInNewWindow::{Default as Unset, Off, On};
+
+    let force_new = OpenConfiguration { force_new_window: true, force_reuse_window: true, ..Default::default() };
+    let force_reuse = OpenConfiguration { force_reuse_window: true, prefer_new_window: true, ..Default::default() };
+    let prefer_new = OpenConfiguration { prefer_new_window: true, ..Default::default() };
+    let desktop = OpenConfiguration { context: OpenContext::Desktop, ..Default::default() };
+    let cli = OpenConfiguration { context: OpenContext::Cli, ..Default::default() };
+    let dialog = OpenConfiguration { context: OpenContext::Dialog, ..Default::default() };
+    let menu = OpenConfiguration { context: OpenContext::Menu, ..Default::default() };
+    let drop = OpenConfiguration { context: OpenContext::Drop, ..Default::default() };
+    let url = OpenConfiguration { context: OpenContext::Url, ..Default::default() };
+
+    // (configuration, folders setting, files setting, cli policy, open folder in new window, open files in new window)
+    let cases = vec![
+      // force new wins over everything
+      (&force_new, Off, Off, Off, true, true),
+      // force reuse wins over prefer new and settings
+      (&force_reuse, On, On, On, false, false),
+      // prefer new wins over settings and policies
+      (&prefer_new, Off, Off, Unset, true, true),
+      // context policy wins over settings
+      (&cli, Off, Off, On, true, true),
+      (&cli, On, On, Off, false, false),
+      // policy of another context doesn't apply
+      (&desktop, Unset, Unset, Off, false, true),
+      // settings win over context defaults
+      (&desktop, On, Off, Unset, true, false),
+      (&dialog, Unset, On, Unset, false, true),
+      // context defaults
+      (&desktop, Unset, Unset, Unset, false, true),
+      (&cli, Unset, Unset, Unset, false, true),
+      (&dialog, Unset, Unset, Unset, false, false),
+      (&menu, Unset, Unset, Unset, false, false),
+      (&drop, Unset, Unset, Unset, false, false),
+      (&url, Unset, Unset, Unset, false, true),
+    ];
+
+    for (index, (configuration, folders, files, cli_policy, open_folder_in_new_window, open_files_in_new_window)) in cases.into_iter().enumerate() {
+      let [folders, files] = get_open_decisions(configuration, &settings(folders, files, cli_policy));
+
+      assert_eq!(
+        (folders.0, files.0),
+        (open_folder_in_new_window, open_files_in_new_window),
+        "case {}",
+        index
+      );
+    }
+  }
+
+  mod open_window {
+    use std::{env::temp_dir, path::PathBuf, thread, time::Duration};
+
+    use tauri::{
+      async_runtime::block_on,
+      test::{mock_builder, mock_context, noop_assets, MockRuntime},
+      window::WindowBuilder,
+      App, Manager, WindowUrl
+    };
+
+    use crate::{Error, TauriWindows};
+
+    use super::super::{
+      check_open_permissions,
+      clear_sticky_deliveries,
+      ContextMenuAction,
+      ContextMenuEntry,
+      remove_broadcast_window,
+      sanitize_label,
+      BroadcastStatus,
+      emit_when_ready,
+      queue_open,
+      record_page_load,
+      OpenConfiguration,
+      OpenPath,
+      OpenRoute,
+      PathToOpen,
+      ReadyState,
+      RoutingRule,
+      SnapRegion,
+      TileLayout,
+      WindowOpenable,
+      WindowOptions,
+      WindowPermission,
+      WindowState,
+      WindowsAPI,
+      WindowsReadyQueue,
+      WindowsSettings,
+      WindowsStartupInfo,
+      WindowsState,
+      WindowsStateCache,
+      WindowsStickyEvents,
+      WindowsSubscriptions
+    };
+
+    fn app() -> App<MockRuntime> {
+      mock_builder()
+        .plugin(TauriWindows::default().in_memory())
+        .build(mock_context(noop_assets()))
+        .unwrap()
+    }
+
+    fn window_state(app: &App<MockRuntime>, label: &str) -> Option<WindowState> {
+      app.state::<WindowsStateCache>().0.read().unwrap().get_item(label)
+    }
+
+    #[test]
+    fn opens_empty_window() {
+      let app = app();
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+
+      assert_eq!(opened.path, OpenPath::NewWindow);
+      assert!(app.get_window(opened.window.label()).is_some());
+      assert_eq!(window_state(&app, opened.window.label()).unwrap().folder, None);
+    }
+
+    #[test]
+    fn reuses_window_of_open_folder() {
+      let app = app();
+
+      let configuration = OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(None, Some(temp_dir()))]),
+        ..Default::default()
+      };
+
+      let first = WindowsAPI::open_window(&app, configuration.clone()).unwrap();
+      let second = WindowsAPI::open_window(&app, configuration).unwrap();
+
+      assert_eq!(first.path, OpenPath::NewWindow);
+      assert_eq!(second.path, OpenPath::ExistingWindow);
+      assert_eq!(first.window.label(), second.window.label());
+    }
+
+    #[test]
+    fn activates_open_singleton_window() {
+      let app = app();
+
+      let options = || WindowOptions {
+        singleton_key: Some("settings".to_string()),
+        ..Default::default()
+      };
+
+      let first = WindowsAPI::open_empty_window(&app, OpenConfiguration::default(), options()).unwrap();
+      let second = WindowsAPI::open_empty_window(&app, OpenConfiguration::default(), options()).unwrap();
+
+      assert_eq!(first.path, OpenPath::NewWindow);
+      assert_eq!(second.path, OpenPath::ExistingWindow);
+      assert_eq!(first.window.label(), second.window.label());
+      assert_eq!(window_state(&app, first.window.label()).unwrap().singleton_key, Some("settings".to_string()));
+    }
+
+    #[test]
+    fn records_route_of_window() {
+      let app = app();
+
+      let route = |label: &str| window_state(&app, label).unwrap().configuration.app_route;
+
+      let opened = WindowsAPI::open_route(&app, "/projects", WindowOptions::default()).unwrap();
+      assert_eq!(opened.path, OpenPath::NewWindow);
+      assert_eq!(route(opened.window.label()), Some("/projects".to_string()));
+
+      let options = WindowOptions {
+        singleton_key: Some("settings".to_string()),
+        ..Default::default()
+      };
+
+      let first = WindowsAPI::open_route(&app, "settings/general", options.clone()).unwrap();
+      let second = WindowsAPI::open_route(&app, "settings/keys", options).unwrap();
+
+      assert_eq!(first.window.label(), second.window.label());
+      assert_eq!(route(second.window.label()), Some("settings/keys".to_string()));
+    }
+
+    #[test]
+    fn validates_supplied_labels() {
+      let app = app();
+
+      let options = |label: &str| WindowOptions {
+        label: Some(label.to_string()),
+        ..Default::default()
+      };
+
+      assert!(matches!(WindowsAPI::open_route(&app, "/", options("")), Err(Error::InvalidLabel(_, _))));
+      assert!(matches!(WindowsAPI::open_route(&app, "/", options("my window")), Err(Error::InvalidLabel(_, _))));
+
+      let opened = WindowsAPI::open_route(&app, "/", options("editor:main")).unwrap();
+      assert_eq!(opened.window.label(), "editor:main");
+
+      assert!(matches!(WindowsAPI::open_route(&app, "/", options("editor:main")), Err(Error::InvalidLabel(_, _))));
+      assert!(matches!(WindowsAPI::open_route(&app, "/", options("windows_1")), Err(Error::InvalidLabel(_, _))));
+
+      assert_eq!(sanitize_label("my window (2)"), "my-window--2-");
+    }
+
+    #[test]
+    fn adopts_app_windows() {
+      let app = app();
+
+      let managed = |label: &str| {
+        WindowsAPI::list_windows(&app).unwrap()
+          .into_iter()
+          .find(|info| info.label == label)
+          .map(|info| info.managed)
+      };
+
+      WindowBuilder::new(&app, "inspector", WindowUrl::default()).build().unwrap();
+      assert_eq!(managed("inspector"), Some(false));
+
+      assert!(WindowsAPI::adopt_window(&app, "inspector").unwrap());
+      assert!(!WindowsAPI::adopt_window(&app, "inspector").unwrap());
+      assert_eq!(managed("inspector"), Some(true));
+
+      assert!(matches!(WindowsAPI::adopt_window(&app, "missing"), Err(Error::WindowNotFound(_))));
+    }
+
+    #[test]
+    fn auto_adopts_app_windows() {
+      let app = app();
+
+      app.state::<WindowsSettings>().0.write().unwrap().auto_adopt = true;
+
+      WindowBuilder::new(&app, "inspector", WindowUrl::default()).build().unwrap();
+      assert!(window_state(&app, "inspector").is_some());
+
+      // Windows of the plugin keep the state they were opened with
+      let opened = WindowsAPI::open_route(&app, "/projects", WindowOptions::default()).unwrap();
+      assert_eq!(window_state(&app, opened.window.label()).unwrap().configuration.app_route, Some("/projects".to_string()));
+    }
+
+    #[test]
+    fn resolves_window_directories() {
+      let app = app();
+
+      let user_data_dir = temp_dir().join("tauri-plugin-windows-profile");
+
+      let opened = WindowsAPI::open_route(&app, "/", WindowOptions {
+        user_data_dir: Some(user_data_dir.clone()),
+        ..Default::default()
+      }).unwrap();
+
+      let configuration = window_state(&app, opened.window.label()).unwrap().configuration;
+
+      assert_eq!(configuration.user_data_dir, Some(user_data_dir.clone()));
+      assert_eq!(configuration.webview.data_directory, Some(user_data_dir));
+      assert_eq!(configuration.tmp_dir, Some(temp_dir()));
+    }
+
+    #[test]
+    fn ignores_excluded_windows() {
+      let app = app();
+
+      app.state::<WindowsSettings>().0.write().unwrap().exclude = vec!["devtools-*".to_string()];
+      app.state::<WindowsSettings>().0.write().unwrap().auto_adopt = true;
+
+      WindowBuilder::new(&app, "devtools-1", WindowUrl::default()).build().unwrap();
+
+      assert!(window_state(&app, "devtools-1").is_none());
+      assert!(!WindowsAPI::adopt_window(&app, "devtools-1").unwrap());
+      assert!(WindowsAPI::list_windows(&app).unwrap().iter().all(|info| info.label != "devtools-1"));
+
+      let options = WindowOptions {
+        label: Some("devtools-2".to_string()),
+        ..Default::default()
+      };
+
+      assert!(matches!(WindowsAPI::open_route(&app, "/", options), Err(Error::InvalidLabel(_, _))));
+    }
+
+    #[test]
+    fn lists_switcher_entries() {
+      let app = app();
+
+      let mut labels = (0..2)
+        .map(|_index| {
+          WindowsAPI::open_window(&app, OpenConfiguration {
+            force_new_window: true,
+            force_empty_window: true,
+            ..Default::default()
+          }).unwrap().window.label().to_string()
+        })
+        .collect::<Vec<String>>();
+
+      // The mock app starts with its main window
+      labels.push("main".to_string());
+      labels.sort();
+
+      let entries = WindowsAPI::get_switcher_entries(&app, false).unwrap();
+
+      assert_eq!(entries.iter().map(|entry| entry.label.clone()).collect::<Vec<String>>(), labels);
+      assert!(entries.iter().all(|entry| entry.thumbnail.is_none()));
+    }
+
+    #[test]
+    fn persists_content_protection() {
+      let app = app();
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+      let label = opened.window.label();
+
+      WindowsAPI::set_content_protected(&app, label, true).unwrap();
+      assert!(window_state(&app, label).unwrap().configuration.content_protected);
+
+      assert!(WindowsAPI::set_content_protected(&app, "missing", true).is_err());
+    }
+
+    #[test]
+    fn persists_zoom_within_bounds() {
+      let app = app();
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+      let label = opened.window.label();
+
+      assert_eq!(WindowsAPI::zoom_in(&app, label).unwrap(), 1.1);
+      assert_eq!(WindowsAPI::zoom_in(&app, label).unwrap(), 1.2);
+      assert_eq!(window_state(&app, label).unwrap().zoom, Some(1.2));
+
+      assert_eq!(WindowsAPI::set_zoom(&app, label, 10.0).unwrap(), 3.0);
+      assert_eq!(WindowsAPI::set_zoom(&app, label, 0.0).unwrap(), 0.3);
+
+      assert_eq!(WindowsAPI::zoom_reset(&app, label).unwrap(), 1.0);
+      assert_eq!(window_state(&app, label).unwrap().zoom, None);
+    }
+
+    #[test]
+    fn runs_action_of_selected_context_menu_entry() {
+      let app = app();
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(None, Some(temp_dir()))]),
+        ..Default::default()
+      }).unwrap();
+      let label = opened.window.label();
+
+      let entries = vec![ContextMenuEntry {
+        id: "close-folder".to_string(),
+        title: "Close Folder".to_string(),
+        action: Some(ContextMenuAction::CloseFolder),
+        disabled: false,
+      }];
+
+      let replaced_id = WindowsAPI::show_context_menu(&app, label, entries.clone(), None).unwrap();
+      let id = WindowsAPI::show_context_menu(&app, label, entries, None).unwrap();
+
+      // Answers to a replaced menu are ignored
+      WindowsAPI::select_context_menu_entry(&app, label, replaced_id, Some("close-folder")).unwrap();
+      assert!(window_state(&app, label).unwrap().folder.is_some());
+
+      WindowsAPI::select_context_menu_entry(&app, label, id, Some("close-folder")).unwrap();
+      assert_eq!(window_state(&app, label).unwrap().folder, None);
+    }
+
+    #[test]
+    fn returns_first_result_of_repeated_request() {
+      let app = app();
+
+      let configuration = OpenConfiguration {
+        force_new_window: true,
+        force_empty_window: true,
+        request_id: Some("menu-new-window".to_string()),
+        ..Default::default()
+      };
+
+      let first = WindowsAPI::open_window(&app, configuration.clone()).unwrap();
+      let second = WindowsAPI::open_window(&app, configuration.clone()).unwrap();
+
+      assert_eq!(first.window.label(), second.window.label());
+      assert_eq!(second.path, OpenPath::NewWindow);
+
+      let other = WindowsAPI::open_window(&app, OpenConfiguration {
+        request_id: Some("menu-new-window-2".to_string()),
+        ..configuration
+      }).unwrap();
+
+      assert_ne!(first.window.label(), other.window.label());
+    }
+
+    #[test]
+    fn saves_state_of_window_reused_at_limit() {
+      let app = app();
+
+      app.state::<WindowsSettings>().0.write().unwrap().max_windows = Some(1);
+
+      let folder = temp_dir().join(format!("tauri-plugin-windows-{}", uuid::Uuid::new_v4().simple()));
+      std::fs::create_dir_all(&folder).unwrap();
+
+      let open_folder = |folder: PathBuf| WindowsAPI::open_window(&app, OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(None, Some(folder))]),
+        force_new_window: true,
+        ..Default::default()
+      }).unwrap();
+
+      let first = open_folder(temp_dir());
+      let reused = open_folder(folder.clone());
+
+      std::fs::remove_dir(&folder).unwrap();
+
+      assert_eq!(reused.path, OpenPath::MaxWindowsReached);
+      assert_eq!(reused.window.label(), first.window.label());
+      assert_eq!(window_state(&app, reused.window.label()).unwrap().folder, Some(folder));
+    }
+
+    #[test]
+    fn opens_window_with_requested_label() {
+      let app = app();
+
+      let configuration = OpenConfiguration {
+        label: Some("editor".to_string()),
+        force_new_window: true,
+        force_empty_window: true,
+        ..Default::default()
+      };
+
+      let opened = WindowsAPI::open_window(&app, configuration.clone()).unwrap();
+      assert_eq!(opened.window.label(), "editor");
+
+      assert!(matches!(WindowsAPI::open_window(&app, configuration), Err(Error::InvalidLabel(_, _))));
+    }
+
+    #[test]
+    fn checks_each_request_of_a_batch() {
+      let app = app();
+
+      let configuration = OpenConfiguration {
+        force_new_window: true,
+        force_empty_window: true,
+        request_id: Some("batch".to_string()),
+        ..Default::default()
+      };
+
+      let first = WindowsAPI::open_window(&app, configuration.clone()).unwrap();
+
+      let results = WindowsAPI::open_windows(&app, vec![
+        configuration,
+        OpenConfiguration {
+          label: Some("not a label".to_string()),
+          force_empty_window: true,
+          ..Default::default()
+        },
+        OpenConfiguration {
+          force_new_window: true,
+          force_empty_window: true,
+          request_id: Some("batch-2".to_string()),
+          ..Default::default()
+        },
+      ]);
+
+      assert_eq!(results[0].as_ref().unwrap().window.label(), first.window.label());
+      assert!(matches!(results[1], Err(Error::InvalidLabel(_, _))));
+
+      // Recorded like a single request
+      let opened = results[2].as_ref().unwrap().window.label().to_string();
+      let again = WindowsAPI::open_window(&app, OpenConfiguration {
+        request_id: Some("batch-2".to_string()),
+        ..Default::default()
+      }).unwrap();
+
+      assert_eq!(again.window.label(), opened);
+    }
+
+    #[test]
+    fn serializes_concurrent_opens() {
+      let app = app();
+
+      let openers = (0..4)
+        .map(|_index| {
+          let app = app.handle();
+
+          thread::spawn(move || {
+            let handle = app.clone();
+
+            tauri::async_runtime::block_on(queue_open(&handle, move || {
+              WindowsAPI::open_window(&app, OpenConfiguration {
+                uris_to_open: Some(vec![WindowOpenable::new(None, Some(temp_dir()))]),
+                ..Default::default()
+              })
+              .map(|opened_window| opened_window.window.label().to_string())
+            }))
+          })
+        })
+        .collect::<Vec<_>>();
+
+      let labels = openers
+        .into_iter()
+        .map(|opener| opener.join().unwrap().unwrap().0)
+        .collect::<Vec<String>>();
+
+      assert!(labels.iter().all(|label| label == &labels[0]));
+    }
+
+    #[test]
+    fn restores_relaunched_session() {
+      let app = app();
+
+      let mut state = WindowsState {
+        was_restarted: true,
+        restart_windows: vec!["previous".to_string()],
+        ..Default::default()
+      };
+
+      state.opened_windows.insert("previous".to_string(), WindowState {
+        folder: Some(temp_dir()),
+        ..Default::default()
+      });
+
+      app.state::<WindowsStateCache>().0.write().unwrap().replace(state).unwrap();
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        initial_startup: true,
+        ..Default::default()
+      }).unwrap();
+
+      assert_eq!(opened.path, OpenPath::NewWindow);
+      assert_eq!(window_state(&app, opened.window.label()).unwrap().folder, Some(temp_dir()));
+      assert!(!app.state::<WindowsStateCache>().0.read().unwrap().state().was_restarted);
+      assert!(app.state::<WindowsStartupInfo>().0.read().unwrap().was_restarted);
+    }
+
+    #[test]
+    fn enforces_window_permissions() {
+      let app = mock_builder()
+        .plugin(TauriWindows::default().in_memory().permissions("main", &[WindowPermission::OpenFolders]))
+        .build(mock_context(noop_assets()))
+        .unwrap();
+
+      let open = |file: Option<&str>, folder: Option<&str>| OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(file.map(PathBuf::from), folder.map(PathBuf::from))]),
+        ..Default::default()
+      };
+
+      assert!(check_open_permissions(&app, "main", &open(None, Some("project"))).is_ok());
+      assert!(check_open_permissions(&app, "main", &open(Some("main.rs"), Some("project"))).is_err());
+      assert!(check_open_permissions(&app, "other", &open(None, Some("project"))).is_err());
+      assert!(check_open_permissions(&app, "other", &OpenConfiguration::default()).is_ok());
+
+      let main = app.get_window("main").unwrap();
+
+      assert_eq!(
+        crate::export_state(app.handle(), main.clone()),
+        Err(Error::PermissionDenied("main".to_string(), WindowPermission::Settings.name().to_string()).to_string())
+      );
+      assert_eq!(
+        crate::close_group(app.handle(), main, "group".to_string()),
+        Err(Error::PermissionDenied("main".to_string(), WindowPermission::Settings.name().to_string()).to_string())
+      );
+    }
+
+    #[test]
+    fn limits_commands_to_their_window() {
+      let app = mock_builder()
+        .plugin(TauriWindows::default().in_memory().permissions("main", &[WindowPermission::OpenFolders]))
+        .build(mock_context(noop_assets()))
+        .unwrap();
+
+      let main = app.get_window("main").unwrap();
+      let other = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap().window;
+      let label = || other.label().to_string();
+
+      let denied = Err(Error::PermissionDenied("main".to_string(), WindowPermission::Settings.name().to_string()).to_string());
+
+      assert_eq!(crate::zoom_in(app.handle(), main.clone(), label()).map(|_zoom| ()), denied);
+      assert_eq!(crate::set_zoom(app.handle(), main.clone(), label(), 1.5).map(|_zoom| ()), denied);
+      assert_eq!(crate::snap_window(app.handle(), main.clone(), label(), SnapRegion::LeftHalf), denied);
+      assert_eq!(crate::tile_windows(app.handle(), main.clone(), vec![label()], TileLayout::Grid), denied);
+      assert_eq!(
+        block_on(crate::screenshot_window(app.handle(), main.clone(), label(), None)).map(|_screenshot| ()),
+        denied
+      );
+      assert_eq!(
+        block_on(crate::get_switcher_entries(app.handle(), main.clone(), Some(true))).map(|_entries| ()),
+        denied
+      );
+      assert!(block_on(crate::get_switcher_entries(app.handle(), main.clone(), None)).is_ok());
+
+      // Windows act on themselves without permissions
+      assert!(crate::zoom_in(app.handle(), other.clone(), label()).is_ok());
+
+      let not_own = Err(Error::NotOwnWindow("main".to_string(), label()).to_string());
+
+      assert_eq!(crate::confirm_close(app.handle(), main.clone(), label(), true), not_own);
+      assert_eq!(crate::acknowledge_quit(app.handle(), main.clone(), label(), true), not_own);
+      assert_eq!(crate::set_dirty(app.handle(), main.clone(), label(), true), not_own);
+      assert_eq!(crate::set_pending_work(app.handle(), main.clone(), label(), true), not_own);
+      assert_eq!(crate::subscribe(app.handle(), main.clone(), label(), vec!["channel".to_string()]), not_own);
+      assert_eq!(crate::unsubscribe(app.handle(), main.clone(), label(), vec!["channel".to_string()]), not_own);
+      assert_eq!(crate::show_context_menu(app.handle(), main.clone(), label(), Vec::new(), None).map(|_id| ()), not_own);
+      assert_eq!(block_on(crate::select_context_menu_entry(app.handle(), main.clone(), label(), 0, None)), not_own);
+
+      assert!(crate::set_dirty(app.handle(), other.clone(), label(), true).is_ok());
+      assert!(window_state(&app, other.label()).unwrap().dirty);
+    }
+
+    #[test]
+    fn duplicates_window_with_user_data() {
+      let app = app();
+
+      let source = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap().window;
+
+      {
+        let windows_state_cache = app.state::<WindowsStateCache>();
+
+        let mut cache = windows_state_cache.0.write().unwrap();
+        let mut state = cache.get_item(source.label()).unwrap();
+
+        state.user_data = Some(serde_json::json!({ "tab": 2 }));
+        state.group = Some("project".to_string());
+
+        cache.set_item(source.label(), state).unwrap();
+      }
+
+      let duplicate = WindowsAPI::duplicate_window(&app, source.label()).unwrap().window;
+      let duplicate_state = window_state(&app, duplicate.label()).unwrap();
+
+      assert_ne!(duplicate.label(), source.label());
+      assert_eq!(duplicate_state.user_data, Some(serde_json::json!({ "tab": 2 })));
+      assert_eq!(duplicate_state.group.as_deref(), Some("project"));
+    }
+
+    #[test]
+    fn moves_folder_between_windows() {
+      use std::fs::{create_dir_all, remove_dir_all};
+
+      let app = app();
+
+      let root = temp_dir().join(format!("tauri-plugin-windows-{}", uuid::Uuid::new_v4().simple()));
+      let folder = root.join("folder");
+      let other_folder = root.join("other");
+
+      create_dir_all(&folder).unwrap();
+      create_dir_all(&other_folder).unwrap();
+
+      let open_folder = |folder: &PathBuf| WindowsAPI::open_window(&app, OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(None, Some(folder.clone()))]),
+        force_new_window: true,
+        ..Default::default()
+      }).unwrap().window;
+
+      let source = open_folder(&folder);
+      let destination = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap().window;
+
+      assert!(matches!(
+        WindowsAPI::move_resource(&app, source.label(), source.label(), folder.clone()),
+        Err(Error::MoveRejected(_))
+      ));
+      assert!(matches!(
+        WindowsAPI::move_resource(&app, destination.label(), source.label(), folder.clone()),
+        Err(Error::ResourceNotInWindow(_, _))
+      ));
+
+      let moved = WindowsAPI::move_resource(&app, source.label(), destination.label(), folder.clone()).unwrap();
+
+      assert_eq!(moved.window.label(), destination.label());
+      assert_eq!(window_state(&app, source.label()).unwrap().folder, None);
+      assert_eq!(window_state(&app, destination.label()).unwrap().folder, Some(folder.clone()));
+
+      // The destination keeps its folder, the other one stays where it is
+      let other = open_folder(&other_folder);
+
+      assert!(matches!(
+        WindowsAPI::move_resource(&app, other.label(), destination.label(), other_folder.clone()),
+        Err(Error::MoveRejected(_))
+      ));
+      assert_eq!(window_state(&app, other.label()).unwrap().folder, Some(other_folder));
+      assert_eq!(window_state(&app, destination.label()).unwrap().folder, Some(folder));
+
+      remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn sends_to_focused_window() {
+      let app = app();
+
+      let open = || WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        force_new_window: true,
+        ..Default::default()
+      }).unwrap().window;
+
+      let first = open();
+      let second = open();
+
+      app.state::<WindowsStateCache>().0.write().unwrap().handle_focused_window(first.label(), &true);
+
+      assert_eq!(WindowsAPI::get_focused_window(&app).unwrap().label(), first.label());
+      assert_eq!(
+        WindowsAPI::send_to_focused(&app, "refresh", serde_json::Value::Null).unwrap().as_deref(),
+        Some(first.label())
+      );
+
+      app.state::<WindowsStateCache>().0.write().unwrap().handle_focused_window(second.label(), &true);
+      WindowsAPI::subscribe(&app, second.label(), vec!["other".to_string()]).unwrap();
+
+      assert_eq!(WindowsAPI::get_focused_window(&app).unwrap().label(), second.label());
+      assert_eq!(WindowsAPI::send_to_focused(&app, "refresh", serde_json::Value::Null).unwrap(), None);
+    }
+
+    #[test]
+    fn filters_paths_before_opening() {
+      let app = mock_builder()
+        .plugin(TauriWindows::default().in_memory().register_open_filter(
+          |_configuration: &OpenConfiguration, paths_to_open: Vec<PathToOpen>| {
+            Ok(
+              paths_to_open
+                .into_iter()
+                .filter(|path_to_open| path_to_open.file.as_ref().map_or(true, |file| file.extension().map_or(true, |extension| extension != "zip")))
+                .collect()
+            )
+          }
+        ))
+        .build(mock_context(noop_assets()))
+        .unwrap();
+
+      let windows_before = app.windows().len();
+
+      let archive = OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(Some(temp_dir().join("archive.zip")), None)]),
+        force_new_window: true,
+        ..Default::default()
+      };
+
+      assert!(matches!(WindowsAPI::open_window(&app, archive), Err(Error::OpenRejected(_))));
+      assert_eq!(app.windows().len(), windows_before);
+
+      let folder = OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(None, Some(temp_dir()))]),
+        force_new_window: true,
+        ..Default::default()
+      };
+
+      assert!(WindowsAPI::open_window(&app, folder).is_ok());
+      assert_eq!(app.windows().len(), windows_before + 1);
+    }
+
+    #[test]
+    fn routes_files_by_pattern() {
+      let app = app();
+
+      app.state::<WindowsSettings>().0.write().unwrap().routing = vec![
+        RoutingRule { pattern: "*.zip".to_string(), route: OpenRoute::Ignore },
+        RoutingRule { pattern: "*.log".to_string(), route: OpenRoute::OpenInNewWindow },
+      ];
+
+      let open_file = |name: &str| OpenConfiguration {
+        uris_to_open: Some(vec![WindowOpenable::new(Some(temp_dir().join(name)), None)]),
+        force_reuse_window: true,
+        ..Default::default()
+      };
+
+      WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+
+      let windows_before = app.windows().len();
+
+      assert!(matches!(WindowsAPI::open_window(&app, open_file("archive.zip")), Err(Error::OpenRejected(_))));
+      assert_eq!(app.windows().len(), windows_before);
+
+      // Route wins over `force_reuse_window`
+      assert_eq!(WindowsAPI::open_window(&app, open_file("app.log")).unwrap().path, OpenPath::NewWindow);
+      assert_eq!(app.windows().len(), windows_before + 1);
+    }
+
+    #[test]
+    fn tracks_channel_subscriptions() {
+      let app = app();
+
+      let channels = |label: &str| app.state::<WindowsSubscriptions>().0.read().unwrap().get(label).cloned();
+      let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+
+      WindowsAPI::subscribe(&app, "main", names(&["windows://ready", "windows://state-changed"])).unwrap();
+      WindowsAPI::unsubscribe(&app, "main", names(&["windows://ready"])).unwrap();
+
+      assert_eq!(channels("main"), Some(names(&["windows://state-changed"]).into_iter().collect()));
+      assert_eq!(channels("other"), None);
+
+      WindowsAPI::unsubscribe(&app, "main", Vec::new()).unwrap();
+      assert_eq!(channels("main").map(|channels| channels.len()), Some(0));
+
+      assert!(WindowsAPI::send_to_all(&app, "app://message", "hello".into(), &names(&["main"]), false).is_ok());
+    }
+
+    #[test]
+    fn waits_for_broadcast_replies() {
+      let app = app();
+
+      let labels = (0..2)
+        .map(|_index| {
+          WindowsAPI::open_window(&app, OpenConfiguration {
+            force_new_window: true,
+            force_empty_window: true,
+            ..Default::default()
+          }).unwrap().window.label().to_string()
+        })
+        .collect::<Vec<String>>();
+
+      // First window closes while the broadcast waits
+      let handle = app.handle();
+      let closed = labels[0].clone();
+
+      let closer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        remove_broadcast_window(&handle, &closed).unwrap();
+      });
+
+      let replies = WindowsAPI::send_to_all_and_wait(&app, "app://save", "now".into(), &[], Duration::from_millis(500)).unwrap();
+
+      closer.join().unwrap();
+
+      let status = |label: &str| replies.iter().find(|reply| reply.label == label).map(|reply| reply.status.clone());
+
+      assert_eq!(status(&labels[0]), Some(BroadcastStatus::Closed));
+      assert_eq!(status(&labels[1]), Some(BroadcastStatus::TimedOut));
+
+      // Answers to finished broadcasts are ignored
+      assert!(WindowsAPI::acknowledge_broadcast(&app, u64::MAX, &labels[1], None, None).is_ok());
+    }
+
+    #[test]
+    fn holds_events_until_window_is_ready() {
+      let app = app();
+      app.state::<WindowsSettings>().0.write().unwrap().ready_handshake = true;
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+      let label = opened.window.label().to_string();
+
+      assert_eq!(window_state(&app, &label).unwrap().ready_state, ReadyState::Navigating);
+
+      emit_when_ready(&opened.window, "windows://test", "held").unwrap();
+      assert_eq!(app.state::<WindowsReadyQueue>().0.read().unwrap().get(&label).map(Vec::len), Some(1));
+
+      WindowsAPI::window_ready(&app, &label).unwrap();
+
+      let state = window_state(&app, &label).unwrap();
+      assert_eq!(state.ready_state, ReadyState::Ready);
+      assert!(state.ready_time.is_some());
+      assert!(app.state::<WindowsReadyQueue>().0.read().unwrap().get(&label).is_none());
+    }
+
+    #[test]
+    fn holds_events_while_window_navigates() {
+      let app = app();
+      app.state::<WindowsSettings>().0.write().unwrap().ready_handshake = true;
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+      let label = opened.window.label().to_string();
+
+      WindowsAPI::window_ready(&app, &label).unwrap();
+
+      let external = WindowUrl::External("https://tauri.app".parse().unwrap());
+      assert!(matches!(WindowsAPI::navigate_window(&app, &label, external), Err(Error::UrlNotAllowed(_))));
+      assert_eq!(window_state(&app, &label).unwrap().ready_state, ReadyState::Ready);
+
+      WindowsAPI::navigate_window(&app, &label, WindowUrl::App("about.html".into())).unwrap();
+      assert_eq!(window_state(&app, &label).unwrap().ready_state, ReadyState::Navigating);
+
+      emit_when_ready(&opened.window, "windows://test", "held").unwrap();
+      assert_eq!(app.state::<WindowsReadyQueue>().0.read().unwrap().get(&label).map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn replays_sticky_events_once_ready() {
+      let app = app();
+      app.state::<WindowsSettings>().0.write().unwrap().ready_handshake = true;
+
+      let delivered = |label: &str| app.state::<WindowsStickyEvents>().0.read().unwrap()
+        .get("app://theme")
+        .map_or(false, |event| event.delivered.contains(label));
+
+      WindowsAPI::send_to_all(&app, "app://theme", "dark".into(), &[], true).unwrap();
+
+      let opened = WindowsAPI::open_window(&app, OpenConfiguration {
+        force_empty_window: true,
+        ..Default::default()
+      }).unwrap();
+      let label = opened.window.label().to_string();
+
+      assert!(!delivered(&label));
+
+      WindowsAPI::window_ready(&app, &label).unwrap();
+      assert!(delivered(&label));
+
+      clear_sticky_deliveries(&app, &label).unwrap();
+      assert!(!delivered(&label));
+    }
+
+    #[test]
+    fn records_startup_metrics() {
+      let app = app();
+
+      let labels = (0..3)
+        .map(|_index| {
+          WindowsAPI::open_window(&app, OpenConfiguration {
+            force_new_window: true,
+            force_empty_window: true,
+            ..Default::default()
+          }).unwrap().window.label().to_string()
+        })
+        .collect::<Vec<String>>();
+
+      for label in &labels {
+        record_page_load(&app, label).unwrap();
+      }
+
+      WindowsAPI::window_ready(&app, &labels[0]).unwrap();
+
+      let metrics = WindowsAPI::get_startup_metrics(&app).unwrap();
+
+      assert_eq!(metrics.windows.len(), 3);
+      assert_eq!(metrics.page_load.map(|stats| stats.count), Some(3));
+      assert_eq!(metrics.ready.map(|stats| stats.count), Some(1));
+      assert!(metrics.windows.iter().all(|window| window.page_load.is_some()));
+    }
+  }
+}
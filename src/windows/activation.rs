@@ -0,0 +1,56 @@
+//! Activation.
+//!
+//! This module contains basic methods and types for the first click on inactive windows.
+//! macOS intercepts the click natively, other platforms rely on the frontend.
+
+use std::result::Result as StdResult;
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_ACTIVATED_EVENT;
+
+use super::{window::now, WindowsSettings, SPLASH_WINDOW_LABEL};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Payload of the window activated event.
+#[derive(Clone, Debug, Serialize)]
+pub struct WindowActivatedPayload {
+  pub label: String,
+  /// Activation time in milliseconds since the unix epoch.
+  pub activated_at: u64,
+}
+
+/// Check if the first click on an inactive window only focuses it.
+pub fn is_click_through_inactive_enabled<R: Runtime, M: Manager<R>>(manager: &M) -> bool {
+  match manager.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.click_through_inactive,
+    Err(_e) => false,
+  }
+}
+
+/// Tell the frontend a window was activated so it can drop the activating click.
+///
+/// Only emitted where the click can't be intercepted natively.
+/// Frontends should ignore pointer events arriving shortly after `activated_at`.
+pub fn handle_activated_window<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  if cfg!(target_os = "macos") || label == SPLASH_WINDOW_LABEL {
+    return Ok(());
+  }
+
+  if !is_click_through_inactive_enabled(manager) {
+    return Ok(());
+  }
+
+  if let Some(window) = manager.get_window(label) {
+    window.emit(WINDOW_ACTIVATED_EVENT, WindowActivatedPayload {
+      label: label.to_string(),
+      activated_at: now().as_millis() as u64,
+    })?;
+  }
+
+  Ok(())
+}
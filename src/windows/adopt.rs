@@ -0,0 +1,107 @@
+//! Adopt.
+//!
+//! This module contains basic methods for bringing windows created by the app under plugin management.
+//! Adopted windows get a window state, so focus, geometry and session restore cover them.
+//! With the `auto_adopt` setting every window the app creates is adopted as it's created.
+
+use std::{
+  collections::HashSet,
+  result::Result as StdResult,
+  sync::RwLock,
+};
+
+use tauri::{Manager, Runtime, Window};
+
+use crate::error::Error;
+
+use super::{
+  exclude::is_excluded,
+  handle_window_bounds_changed,
+  record_window_created,
+  save_window_state,
+  window::ReadyState,
+  WindowConfiguration,
+  WindowsSettings,
+  WindowsStateCache,
+  SPLASH_WINDOW_LABEL
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Managed state for windows the plugin is creating.
+///
+/// Tauri calls the `created` hook from the window builder, before the plugin saved the state of the window.
+#[derive(Default)]
+pub struct WindowsCreating(pub RwLock<HashSet<String>>);
+
+/// Check whether the plugin manages an open window.
+///
+/// Windows opened by the plugin or adopted have a window state until they close.
+pub fn is_managed<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<bool> {
+  Ok(
+    manager.state::<WindowsStateCache>().0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .get_item(label)
+    .map_or(false, |state| state.closed_time.is_none())
+  )
+}
+
+/// Bring a window created by the app under plugin management.
+///
+/// Returns whether the window was adopted, `false` if it was already managed or is excluded.
+pub fn adopt_window<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<bool> {
+  let window = manager.get_window(label)
+  .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+  if label == SPLASH_WINDOW_LABEL || is_excluded(manager, label) || is_managed(manager, label)? {
+    return Ok(false);
+  }
+
+  save_adopted_window_state(manager, &window)?;
+
+  // Page of the window already loaded, nothing would declare it ready
+  manager.state::<WindowsStateCache>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .set_ready_state(label, ReadyState::Ready)?;
+
+  Ok(true)
+}
+
+/// Adopt a window the app just created, if `auto_adopt` is set.
+///
+/// Windows created by the plugin are skipped, their state is saved once they're built.
+pub fn handle_window_created<R: Runtime>(window: &Window<R>) -> Result<()> {
+  let auto_adopt = window.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .auto_adopt;
+
+  if !auto_adopt || window.label() == SPLASH_WINDOW_LABEL {
+    return Ok(());
+  }
+
+  let creating = window.state::<WindowsCreating>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .contains(window.label());
+
+  if creating || is_managed(window, window.label())? {
+    return Ok(());
+  }
+
+  save_adopted_window_state(window, window)?;
+
+  // Page loads like in windows of the plugin
+  record_window_created(window, window.label())
+}
+
+/// Save a new window state for a window created by the app.
+fn save_adopted_window_state<R: Runtime, M: Manager<R>>(manager: &M, window: &Window<R>) -> Result<()> {
+  let configuration = WindowConfiguration {
+    full_screen: window.is_fullscreen()?,
+    maximized: window.is_maximized()?,
+    ..Default::default()
+  };
+
+  save_window_state(manager, window.label(), configuration)?;
+
+  handle_window_bounds_changed(manager, window.label())
+}
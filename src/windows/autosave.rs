@@ -0,0 +1,66 @@
+//! Auto save.
+//!
+//! This module contains basic methods and types for saving caches on an interval.
+//! Protects long running sessions against power loss when no window events fire for hours.
+
+use std::{thread, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::{log_error, WindowsAPI, WindowsSettings};
+
+/// Auto save settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > auto_save`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AutoSaveSettings {
+  /// Whether caches are saved on an interval, besides the saves on window events.
+  pub enabled: bool,
+  /// Time between saves.
+  pub interval_ms: u64,
+}
+
+impl Default for AutoSaveSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      interval_ms: 60_000,
+    }
+  }
+}
+
+fn auto_save_settings<R: Runtime>(handle: &AppHandle<R>) -> AutoSaveSettings {
+  match handle.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.auto_save.clone(),
+    Err(_e) => AutoSaveSettings::default(),
+  }
+}
+
+/// Save caches on the interval of the settings, on a background thread.
+///
+/// Only files that changed since the last save are written.
+/// Stops once auto save is disabled in the settings.
+pub fn start_auto_save<R: Runtime>(handle: &AppHandle<R>) {
+  if !auto_save_settings(handle).enabled {
+    return;
+  }
+
+  let handle = handle.clone();
+
+  thread::spawn(move || loop {
+    let settings = auto_save_settings(&handle);
+
+    if !settings.enabled {
+      return;
+    }
+
+    thread::sleep(Duration::from_millis(settings.interval_ms.max(1000)));
+
+    if let Err(e) = WindowsAPI::flush(&handle) {
+      log_error(&e);
+    }
+  });
+}
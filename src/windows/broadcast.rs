@@ -0,0 +1,184 @@
+//! Broadcast.
+//!
+//! This module contains basic methods and types for broadcasts waiting on the windows to answer.
+//! Each recipient answers with `acknowledge_broadcast`, the sender gets the answers or a timeout per window.
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Condvar,
+    Mutex
+  },
+  time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::subscribers;
+
+type Result<T> = StdResult<T, Error>;
+
+static BROADCAST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Time windows have to answer when the sender doesn't set one.
+pub const DEFAULT_BROADCAST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload of a broadcast waiting on the windows, answer it with the id.
+#[derive(Clone, Debug, Serialize)]
+pub struct BroadcastPayload {
+  pub id: u64,
+  pub payload: JsonValue,
+}
+
+/// Outcome of a broadcast for a window.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum BroadcastStatus {
+  Acknowledged,
+  /// The window answered with an error.
+  Failed,
+  /// The window didn't answer in time.
+  TimedOut,
+  /// The window was closed before answering.
+  Closed,
+}
+
+/// Answer of a window to a broadcast.
+#[derive(Clone, Debug, Serialize)]
+pub struct BroadcastReply {
+  pub label: String,
+  pub status: BroadcastStatus,
+  /// Result sent by the window.
+  pub result: Option<JsonValue>,
+  pub error: Option<String>,
+}
+
+/// Managed state for broadcasts waiting on the windows, keyed by broadcast id.
+///
+/// A mutex rather than a lock so senders can wait for answers on the condition variable.
+#[derive(Default)]
+pub struct WindowsBroadcasts {
+  pending: Mutex<HashMap<u64, BTreeMap<String, Option<BroadcastReply>>>>,
+  answered: Condvar,
+}
+
+/// Send a message to the windows subscribed to the channel and wait for each to answer.
+///
+/// Windows that don't answer within the timeout are reported as timed out, the order follows the labels.
+pub fn send_and_wait<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  channel: &str,
+  payload: JsonValue,
+  window_labels_to_ignore: &[String],
+  timeout: Duration,
+) -> Result<Vec<BroadcastReply>> {
+  let broadcasts = manager.state::<WindowsBroadcasts>();
+
+  let id = BROADCAST_ID.fetch_add(1, Ordering::Relaxed);
+  let labels = subscribers(manager, channel, window_labels_to_ignore)?;
+
+  broadcasts.pending.lock()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .insert(id, labels.iter().map(|label| (label.clone(), None)).collect());
+
+  for label in &labels {
+    manager.emit_to(label, channel, BroadcastPayload { id, payload: payload.clone() })?;
+  }
+
+  let deadline = Instant::now() + timeout;
+
+  let mut pending = broadcasts.pending.lock()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  loop {
+    let is_done = pending.get(&id).map_or(true, |replies| replies.values().all(Option::is_some));
+    let now = Instant::now();
+
+    if is_done || now >= deadline {
+      break;
+    }
+
+    pending = broadcasts.answered.wait_timeout(pending, deadline - now)
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .0;
+  }
+
+  let replies = pending.remove(&id).unwrap_or_default();
+
+  Ok(
+    replies
+      .into_iter()
+      .map(|(label, reply)| reply.unwrap_or(BroadcastReply {
+        label,
+        status: BroadcastStatus::TimedOut,
+        result: None,
+        error: None,
+      }))
+      .collect()
+  )
+}
+
+/// Record the answer of a window to a broadcast.
+///
+/// Answers to unknown or finished broadcasts, and from windows that weren't sent the broadcast, are ignored.
+pub fn acknowledge_broadcast<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  id: u64,
+  label: &str,
+  result: Option<JsonValue>,
+  error: Option<String>,
+) -> Result<()> {
+  let status = match error {
+    Some(_) => BroadcastStatus::Failed,
+    None => BroadcastStatus::Acknowledged,
+  };
+
+  set_reply(manager, Some(id), BroadcastReply {
+    label: label.to_string(),
+    status,
+    result,
+    error,
+  })
+}
+
+/// Stop waiting on a destroyed window.
+pub fn remove_broadcast_window<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  set_reply(manager, None, BroadcastReply {
+    label: label.to_string(),
+    status: BroadcastStatus::Closed,
+    result: None,
+    error: None,
+  })
+}
+
+/// Set the reply of a window to a broadcast, or to every broadcast if `id` is `None`.
+fn set_reply<R: Runtime, M: Manager<R>>(manager: &M, id: Option<u64>, reply: BroadcastReply) -> Result<()> {
+  let broadcasts = manager.state::<WindowsBroadcasts>();
+
+  let mut pending = broadcasts.pending.lock()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut answered = false;
+
+  for (_id, replies) in pending.iter_mut().filter(|(pending_id, _replies)| id.map_or(true, |id| id == **pending_id)) {
+    match replies.get_mut(&reply.label) {
+      Some(slot) if slot.is_none() => {
+        *slot = Some(reply.clone());
+        answered = true;
+      },
+      _ => {},
+    }
+  }
+
+  if answered {
+    broadcasts.answered.notify_all();
+  }
+
+  Ok(())
+}
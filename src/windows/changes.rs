@@ -0,0 +1,62 @@
+//! Changes.
+//!
+//! This module contains basic methods and types for telling frontends about cache changes.
+//! Caches record their changes, which are emitted once per event loop iteration.
+
+use std::result::Result as StdResult;
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_STATE_CHANGED_EVENT;
+#[cfg(feature = "recents")]
+use crate::event::WINDOW_RECENTS_CHANGED_EVENT;
+
+use super::{diagnostics::write_lock, emit_to_subscribers, WindowsStateCache};
+#[cfg(feature = "recents")]
+use super::WindowsRecentsCache;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Payload of the state changed event.
+#[derive(Clone, Debug, Serialize)]
+pub struct StateChangedPayload {
+  /// Labels of windows whose state was added, updated or removed.
+  pub labels: Vec<String>,
+}
+
+/// Emit changes made to the recents and window states since the last call.
+///
+/// Changes are coalesced, so frequent updates like moves emit once per loop iteration.
+pub fn emit_cache_changes<R: Runtime, M: Manager<R>>(manager: &M) -> Result<()> {
+  #[cfg(feature = "recents")]
+  {
+    let windows_recents_cache = manager.state::<WindowsRecentsCache>();
+
+    let has_recents_changes = windows_recents_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .has_changes();
+
+    if has_recents_changes {
+      let changes = write_lock(&windows_recents_cache.0)?.take_changes();
+
+      emit_to_subscribers(manager, WINDOW_RECENTS_CHANGED_EVENT, changes, &[])?;
+    }
+  }
+
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let has_state_changes = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .has_changes();
+
+  if has_state_changes {
+    let labels = write_lock(&windows_state_cache.0)?.take_changes();
+
+    emit_to_subscribers(manager, WINDOW_STATE_CHANGED_EVENT, StateChangedPayload { labels }, &[])?;
+  }
+
+  Ok(())
+}
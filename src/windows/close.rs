@@ -0,0 +1,432 @@
+//! Close.
+//!
+//! This module contains basic methods and types for the confirm close flow.
+//! Lets the frontend veto closing a window, e.g. while it has unsaved changes.
+
+use std::{
+  collections::{HashMap, HashSet},
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    RwLock
+  },
+  thread,
+  time::Duration,
+};
+#[cfg(feature = "backups")]
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_CLOSE_REQUESTED_EVENT;
+
+use super::{log_error, WindowsSettings, WindowsStateCache};
+#[cfg(feature = "backups")]
+use super::WindowsBackupCache;
+
+type Result<T> = StdResult<T, Error>;
+
+static CLOSE_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Windows that go through the confirm close flow.
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize)]
+pub enum ConfirmClose {
+  /// Close windows right away.
+  Off,
+  /// Ask every window before closing.
+  Always,
+  /// Ask windows that reported pending work through `set_pending_work`.
+  PendingWork,
+}
+
+impl Default for ConfirmClose {
+  fn default() -> Self {
+    ConfirmClose::Off
+  }
+}
+
+/// Confirm close settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > confirm_close`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ConfirmCloseSettings {
+  pub mode: ConfirmClose,
+  /// Time the frontend has to answer, the window is closed when it doesn't.
+  pub timeout_ms: u64,
+}
+
+impl Default for ConfirmCloseSettings {
+  fn default() -> Self {
+    Self {
+      mode: ConfirmClose::Off,
+      timeout_ms: 10_000,
+    }
+  }
+}
+
+/// Payload of the close requested event.
+#[derive(Clone, Debug, Serialize)]
+pub struct CloseRequestedPayload {
+  pub label: String,
+  pub pending_work: bool,
+  /// Whether the document of the window has unsaved changes, see [`set_dirty`].
+  pub dirty: bool,
+}
+
+/// Close requests cached during runtime.
+#[derive(Default)]
+pub struct InnerWindowsCloseState {
+  /// Unanswered close requests, keyed by window label.
+  pending: HashMap<String, u64>,
+  /// Windows allowed to close on the next close request.
+  allowed: HashSet<String>,
+  /// Windows that reported pending work.
+  pending_work: HashSet<String>,
+}
+
+impl InnerWindowsCloseState {
+  pub fn set_pending_work(&mut self, label: &str, pending_work: bool) {
+    if pending_work {
+      self.pending_work.insert(label.to_string());
+    } else {
+      self.pending_work.remove(label);
+    }
+  }
+
+  pub fn has_pending_work(&self, label: &str) -> bool {
+    self.pending_work.contains(label)
+  }
+
+  /// Forget a destroyed window.
+  pub fn remove(&mut self, label: &str) {
+    self.pending.remove(label);
+    self.allowed.remove(label);
+    self.pending_work.remove(label);
+  }
+}
+
+/// Managed state for the confirm close flow.
+#[derive(Default)]
+pub struct WindowsCloseState(pub RwLock<InnerWindowsCloseState>);
+
+/// Check whether the document of a window has unsaved changes.
+pub fn is_dirty<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<bool> {
+  Ok(
+    manager.state::<WindowsStateCache>().0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .get_item(label)
+    .map_or(false, |state| state.dirty)
+  )
+}
+
+/// Mark the document of a window as having unsaved changes, or as saved.
+pub fn set_dirty<R: Runtime, M: Manager<R>>(manager: &M, label: &str, dirty: bool) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut state = cache.get_item(label)
+  .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+  if state.dirty == dirty {
+    return Ok(());
+  }
+
+  state.dirty = dirty;
+
+  cache.set_item(label, state)
+}
+
+/// Check if closing a window must wait for the frontend.
+///
+/// Dirty windows are always asked, whatever the mode.
+/// Consumes the permission given by [`confirm_close`], so the next request is checked again.
+pub fn should_confirm_close<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<bool> {
+  let mode = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .confirm_close
+  .mode;
+
+  let dirty = is_dirty(manager, label)?;
+
+  let windows_close_state = manager.state::<WindowsCloseState>();
+
+  let mut state = windows_close_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  if state.allowed.remove(label) {
+    return Ok(false);
+  }
+
+  let res = dirty || match mode {
+    ConfirmClose::Off => false,
+    ConfirmClose::Always => true,
+    ConfirmClose::PendingWork => state.has_pending_work(label),
+  };
+
+  Ok(res)
+}
+
+/// Ask the frontend of a window to confirm closing.
+///
+/// The window is closed if no answer arrives within `timeout_ms`.
+pub fn request_close<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let timeout = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .confirm_close
+  .timeout_ms;
+
+  let dirty = is_dirty(manager, label)?;
+
+  let windows_close_state = manager.state::<WindowsCloseState>();
+
+  let mut state = windows_close_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  // Already waiting for an answer
+  if state.pending.contains_key(label) {
+    return Ok(());
+  }
+
+  let id = CLOSE_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+
+  state.pending.insert(label.to_string(), id);
+
+  let payload = CloseRequestedPayload {
+    label: label.to_string(),
+    pending_work: state.has_pending_work(label),
+    dirty,
+  };
+
+  drop(state);
+
+  if let Some(window) = manager.get_window(label) {
+    window.emit(WINDOW_CLOSE_REQUESTED_EVENT, payload)?;
+  }
+
+  let handle = manager.app_handle();
+  let label = label.to_string();
+
+  thread::spawn(move || {
+    thread::sleep(Duration::from_millis(timeout));
+
+    let timed_out = match handle.state::<WindowsCloseState>().0.read() {
+      Ok(state) => state.pending.get(&label) == Some(&id),
+      Err(_e) => false,
+    };
+
+    if timed_out {
+      if let Err(e) = confirm_close(&handle, &label, true) {
+        log_error(&e);
+      }
+    }
+  });
+
+  Ok(())
+}
+
+/// Answer a close request.
+///
+/// Closes the window if `allow` is set, otherwise the window stays open.
+pub fn confirm_close<R: Runtime, M: Manager<R>>(manager: &M, label: &str, allow: bool) -> Result<()> {
+  let windows_close_state = manager.state::<WindowsCloseState>();
+
+  let mut state = windows_close_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  state.pending.remove(label);
+
+  if !allow {
+    return Ok(());
+  }
+
+  state.allowed.insert(label.to_string());
+
+  drop(state);
+
+  if let Some(window) = manager.get_window(label) {
+    window.close()?;
+  }
+
+  Ok(())
+}
+
+/// Close a window left without folder, files or url, if `close_when_empty` is set.
+///
+/// Dirty windows stay open. Returns whether the window was closed.
+pub fn close_when_empty<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<bool> {
+  let enabled = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .close_when_empty;
+
+  if !enabled {
+    return Ok(false);
+  }
+
+  let state = match manager.state::<WindowsStateCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .get_item(label) {
+    Some(state) => state,
+    None => return Ok(false),
+  };
+
+  let empty = state.folder.is_none()
+    && state.configuration.files_to_open_or_create.is_empty()
+    && state.configuration.url.is_none();
+
+  if !empty || state.dirty {
+    return Ok(false);
+  }
+
+  match manager.get_window(label) {
+    Some(window) => {
+      window.close()?;
+      Ok(true)
+    },
+    None => Ok(false),
+  }
+}
+
+/// Remove the backups of a window that closed without pending work or unsaved changes.
+///
+/// Folder backups still used by another open window are handed over to it instead.
+#[cfg(feature = "backups")]
+pub fn remove_clean_window_backups<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let pending_work = manager.state::<WindowsCloseState>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .has_pending_work(label);
+
+  if pending_work || is_dirty(manager, label)? {
+    return Ok(());
+  }
+
+  let windows_on_folders = manager.state::<WindowsStateCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .state()
+  .opened_windows
+  .iter()
+  .filter(|(other, state)| other.as_str() != label && state.closed_time.is_none() && manager.get_window(other).is_some())
+  .filter_map(|(other, state)| state.folder.clone().map(|folder| (folder, other.clone())))
+  .collect::<HashMap<PathBuf, String>>();
+
+  let windows_backup_cache = manager.state::<WindowsBackupCache>();
+
+  let mut cache = windows_backup_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let shared_folders = cache.backups.folders
+    .iter()
+    .filter(|info| info.window == label)
+    .filter_map(|info| info.folder.as_ref())
+    .filter_map(|folder| windows_on_folders.get(folder).map(|other| (folder.clone(), other.clone())))
+    .collect::<Vec<(PathBuf, String)>>();
+
+  for (folder, other) in shared_folders {
+    cache.move_folder_backup(&folder, &other);
+  }
+
+  cache.remove_window_backups(label)?;
+
+  Ok(())
+}
+
+#[cfg(not(feature = "backups"))]
+pub fn remove_clean_window_backups<R: Runtime, M: Manager<R>>(_manager: &M, _label: &str) -> Result<()> {
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{thread, time::Duration};
+
+  use tauri::{
+    test::{mock_builder, mock_context, noop_assets, MockRuntime},
+    App,
+    Manager
+  };
+
+  use crate::TauriWindows;
+
+  use super::{
+    super::WindowsSettings,
+    confirm_close,
+    request_close,
+    should_confirm_close,
+    ConfirmClose,
+    ConfirmCloseSettings,
+    WindowsCloseState
+  };
+
+  fn app(timeout_ms: u64) -> App<MockRuntime> {
+    let app = mock_builder()
+      .plugin(TauriWindows::default().in_memory())
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    app.state::<WindowsSettings>().0.write().unwrap().confirm_close = ConfirmCloseSettings {
+      mode: ConfirmClose::Always,
+      timeout_ms,
+    };
+
+    app
+  }
+
+  fn is_pending(app: &App<MockRuntime>, label: &str) -> bool {
+    app.state::<WindowsCloseState>().0.read().unwrap().pending.contains_key(label)
+  }
+
+  fn is_allowed(app: &App<MockRuntime>, label: &str) -> bool {
+    app.state::<WindowsCloseState>().0.read().unwrap().allowed.contains(label)
+  }
+
+  #[test]
+  fn veto_keeps_window_open_past_timeout() {
+    let app = app(20);
+
+    assert!(should_confirm_close(&app, "main").unwrap());
+
+    request_close(&app, "main").unwrap();
+    assert!(is_pending(&app, "main"));
+
+    confirm_close(&app, "main", false).unwrap();
+    assert!(!is_pending(&app, "main"));
+
+    // The timeout of the answered request doesn't close the window
+    thread::sleep(Duration::from_millis(100));
+
+    assert!(!is_allowed(&app, "main"));
+    assert!(should_confirm_close(&app, "main").unwrap());
+  }
+
+  #[test]
+  fn allowed_close_is_asked_again_next_time() {
+    let app = app(60_000);
+
+    request_close(&app, "main").unwrap();
+    confirm_close(&app, "main", true).unwrap();
+
+    assert!(!is_pending(&app, "main"));
+    assert!(!should_confirm_close(&app, "main").unwrap());
+    assert!(should_confirm_close(&app, "main").unwrap());
+  }
+
+  #[test]
+  fn unanswered_request_closes_after_timeout() {
+    let app = app(20);
+
+    request_close(&app, "main").unwrap();
+
+    // Requesting again while waiting doesn't restart the timeout
+    request_close(&app, "main").unwrap();
+
+    thread::sleep(Duration::from_millis(100));
+
+    assert!(!is_pending(&app, "main"));
+    assert!(is_allowed(&app, "main"));
+  }
+}
@@ -0,0 +1,271 @@
+//! Context menu.
+//!
+//! This module contains basic methods and types for context menus of windows with window management actions.
+//! Tauri v1 has no native popup menus, the frontend renders the menu it is sent and answers with the selected entry.
+
+use std::{
+  collections::HashMap,
+  path::PathBuf,
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    RwLock
+  },
+};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime, WindowUrl};
+
+use crate::error::Error;
+use crate::event::{
+  WINDOW_CLOSE_FOLDER_EVENT,
+  WINDOW_CONTEXT_MENU_EVENT,
+  WINDOW_CONTEXT_MENU_SELECTED_EVENT
+};
+
+use super::{
+  open_in_webview_window,
+  route_url,
+  unwatch_folder,
+  FilesToOpen,
+  OpenedWindow,
+  WindowOptions,
+  WindowPosition,
+  WindowsAPI,
+  WindowsStateCache,
+  SPLASH_WINDOW_LABEL
+};
+
+type Result<T> = StdResult<T, Error>;
+
+static CONTEXT_MENU_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Window management action run by the plugin when its entry is selected.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ContextMenuAction {
+  /// Move a file or folder of the window to another window.
+  MoveToWindow { path: PathBuf, to_label: String },
+  /// Close the folder of the window.
+  CloseFolder,
+  /// Open the window closed last again.
+  ReopenClosedWindow,
+}
+
+/// Entry of a context menu.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ContextMenuEntry {
+  pub id: String,
+  pub title: String,
+  /// Standard action, entries without one are only delivered to the window.
+  #[serde(default)]
+  pub action: Option<ContextMenuAction>,
+  #[serde(default)]
+  pub disabled: bool,
+}
+
+/// Payload of the context menu event, the frontend renders it.
+#[derive(Clone, Debug, Serialize)]
+pub struct ContextMenuPayload {
+  /// Id to answer `select_context_menu_entry` with.
+  pub id: u64,
+  pub entries: Vec<ContextMenuEntry>,
+  /// Logical position in the window, at the cursor if not set.
+  pub position: Option<WindowPosition>,
+}
+
+/// Payload of the context menu selected event.
+#[derive(Clone, Debug, Serialize)]
+pub struct ContextMenuSelection {
+  pub entry_id: String,
+  pub action: Option<ContextMenuAction>,
+  /// Label of the window reopened by [`ContextMenuAction::ReopenClosedWindow`].
+  pub reopened_label: Option<String>,
+}
+
+/// Managed state for the context menu open in each window, keyed by label.
+#[derive(Default)]
+pub struct WindowsContextMenus(pub RwLock<HashMap<String, ContextMenuPayload>>);
+
+/// Show a context menu in a window, replacing the menu it shows.
+///
+/// Returns the id of the menu.
+pub fn show_context_menu<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  label: &str,
+  entries: Vec<ContextMenuEntry>,
+  position: Option<WindowPosition>,
+) -> Result<u64> {
+  let window = manager.get_window(label)
+  .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+  let payload = ContextMenuPayload {
+    id: CONTEXT_MENU_ID.fetch_add(1, Ordering::Relaxed),
+    entries,
+    position,
+  };
+
+  manager.state::<WindowsContextMenus>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .insert(label.to_string(), payload.clone());
+
+  window.emit(WINDOW_CONTEXT_MENU_EVENT, &payload)?;
+
+  Ok(payload.id)
+}
+
+/// Handle the answer of a window to its context menu, `entry_id` is `None` if it was dismissed.
+///
+/// Runs the action of the selected entry and emits the context menu selected event to the window.
+/// Answers to a replaced menu or with a disabled entry are ignored.
+pub fn select_context_menu_entry<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  label: &str,
+  id: u64,
+  entry_id: Option<&str>,
+) -> Result<()> {
+  let entry = {
+    let windows_context_menus = manager.state::<WindowsContextMenus>();
+
+    let mut menus = windows_context_menus.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let menu = match menus.remove(label) {
+      Some(menu) if menu.id == id => menu,
+      // Answer to a replaced menu
+      Some(menu) => {
+        menus.insert(label.to_string(), menu);
+        return Ok(());
+      },
+      None => return Ok(()),
+    };
+
+    match entry_id {
+      Some(entry_id) => menu.entries.into_iter().find(|entry| entry.id == entry_id && !entry.disabled),
+      None => None,
+    }
+  };
+
+  let entry = match entry {
+    Some(entry) => entry,
+    None => return Ok(()),
+  };
+
+  let reopened_label = match &entry.action {
+    Some(ContextMenuAction::MoveToWindow { path, to_label }) => {
+      WindowsAPI::move_resource(manager, label, to_label, path.clone())?;
+      None
+    },
+    Some(ContextMenuAction::CloseFolder) => {
+      close_folder(manager, label)?;
+      None
+    },
+    Some(ContextMenuAction::ReopenClosedWindow) => reopen_closed_window(manager)?
+      .map(|opened_window| opened_window.window.label().to_string()),
+    None => None,
+  };
+
+  manager.emit_to(label, WINDOW_CONTEXT_MENU_SELECTED_EVENT, ContextMenuSelection {
+    entry_id: entry.id,
+    action: entry.action,
+    reopened_label,
+  })?;
+
+  Ok(())
+}
+
+/// Forget the context menu of a closed window.
+pub fn clear_context_menu<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  manager.state::<WindowsContextMenus>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .remove(label);
+
+  Ok(())
+}
+
+/// Remove the folder from the state of a window and let the window drop it.
+fn close_folder<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let folder = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let mut state = cache.get_item(label)
+    .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+    let folder = state.folder.take();
+
+    state.configuration.folder = None;
+
+    cache.set_item(label, state)?;
+
+    folder
+  };
+
+  unwatch_folder(manager, label)?;
+
+  if let Some(folder) = folder {
+    manager.emit_to(label, WINDOW_CLOSE_FOLDER_EVENT, &folder)?;
+  }
+
+  Ok(())
+}
+
+/// Open the window closed last again, with its folder, files, url and route.
+///
+/// Returns `None` if no closed window is kept.
+pub fn reopen_closed_window<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Option<OpenedWindow<R>>> {
+  let closed = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    cache.state().opened_windows
+      .iter()
+      .filter(|(label, state)| {
+        state.closed_time.is_some() && label.as_str() != SPLASH_WINDOW_LABEL && manager.get_window(label).is_none()
+      })
+      .max_by_key(|(_label, state)| state.closed_time)
+      .map(|(label, state)| (label.clone(), state.clone()))
+  };
+
+  let (closed_label, closed_state) = match closed {
+    Some(closed) => closed,
+    None => return Ok(None),
+  };
+
+  let configuration = closed_state.configuration;
+
+  let url = match (&configuration.app_route, &configuration.url) {
+    (Some(route), _) => Some(route_url(route)),
+    (None, Some(url)) => Some(WindowUrl::External(url.clone())),
+    (None, None) => None,
+  };
+
+  let opened_window = open_in_webview_window(manager, WindowOptions {
+    folder: configuration.folder,
+    files_to_open: FilesToOpen {
+      files_to_open_or_create: configuration.files_to_open_or_create,
+    },
+    url,
+    app_route: configuration.app_route,
+    fullscreen: Some(configuration.full_screen),
+    maximized: Some(configuration.maximized),
+    force_new_window: true,
+    ..Default::default()
+  })?;
+
+  // Reopened once, unless the new window took over the label
+  if opened_window.window.label() != closed_label {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    cache.remove_item(&closed_label)?;
+  }
+
+  Ok(Some(opened_window))
+}
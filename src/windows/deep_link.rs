@@ -0,0 +1,65 @@
+//! Deep link.
+//!
+//! This module contains basic methods for turning app deep links into open configurations.
+//! Supports links like `myapp://open?file=/path/to/file&folder=/path/to/folder`.
+
+use std::path::PathBuf;
+
+use tauri::Url;
+
+use super::{OpenConfiguration, OpenContext, WindowOpenable};
+
+/// Deep link action for opening resources.
+const OPEN_ACTION: &str = "open";
+
+/// Get the action of a deep link.
+///
+/// Handles both `myapp://open` and `myapp:open` forms.
+fn deep_link_action(url: &Url) -> String {
+  match url.host_str() {
+    Some(host) if !host.is_empty() => host.to_string(),
+    _ => url.path().trim_matches('/').to_string(),
+  }
+}
+
+/// Create [`OpenConfiguration`] from a deep link.
+///
+/// Returns `None` if the link is not an open link or has nothing to open.
+///
+/// Query parameters:
+/// - `file`: file to open, can be repeated.
+/// - `folder`: folder to open, can be repeated.
+/// - `new_window`: force a new window.
+/// - `reuse_window`: force reusing a window.
+pub fn parse_deep_link(url: &Url) -> Option<OpenConfiguration> {
+  if deep_link_action(url) != OPEN_ACTION {
+    return None;
+  }
+
+  let mut uris_to_open: Vec<WindowOpenable> = Vec::new();
+  let mut force_new_window = false;
+  let mut force_reuse_window = false;
+
+  for (key, value) in url.query_pairs() {
+    match key.as_ref() {
+      "file" => uris_to_open.push(WindowOpenable::new(Some(PathBuf::from(value.as_ref())), None)),
+      "folder" => uris_to_open.push(WindowOpenable::new(None, Some(PathBuf::from(value.as_ref())))),
+      "new_window" => force_new_window = value != "false",
+      "reuse_window" => force_reuse_window = value != "false",
+      // Ignore parameters meant for the frontend, e.g. `line`
+      _ => {}
+    }
+  }
+
+  if uris_to_open.is_empty() {
+    return None;
+  }
+
+  Some(OpenConfiguration {
+    context: OpenContext::Url,
+    uris_to_open: Some(uris_to_open),
+    force_new_window,
+    force_reuse_window,
+    ..Default::default()
+  })
+}
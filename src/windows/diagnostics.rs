@@ -0,0 +1,210 @@
+//! Diagnostics.
+//!
+//! This module contains basic methods and types for reporting the internal health of the plugin.
+//! The report is meant to be attached to issues, it holds paths but no file contents.
+
+use std::{
+  collections::VecDeque,
+  fmt::Debug,
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex, RwLock, RwLockWriteGuard, TryLockError,
+  },
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::{Error, ErrorKind};
+use crate::event::WINDOW_ERROR_EVENT;
+
+use super::{
+  storage::StorageStatus,
+  window::now,
+  WindowsLayoutCache,
+  WindowsStateCache,
+  WindowsTrustCache,
+  SPLASH_WINDOW_LABEL
+};
+#[cfg(feature = "backups")]
+use super::WindowsBackupCache;
+#[cfg(feature = "recents")]
+use super::WindowsRecentsCache;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Number of internal errors kept for the report.
+const MAX_RECORDED_ERRORS: usize = 50;
+
+static LOCK_CONTENTIONS: AtomicUsize = AtomicUsize::new(0);
+
+static RECORDED_ERRORS: Lazy<Mutex<VecDeque<RecordedError>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Internal error logged by the plugin.
+#[derive(Clone, Debug, Serialize)]
+pub struct RecordedError {
+  /// Time in milliseconds since the unix epoch.
+  pub time: u64,
+  pub message: String,
+}
+
+/// Payload of the window error event.
+#[derive(Clone, Debug, Serialize)]
+pub struct WindowErrorPayload {
+  pub label: String,
+  pub kind: ErrorKind,
+  pub message: String,
+}
+
+/// Status of the files backing the caches.
+///
+/// Caches compiled out with their feature have no status.
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageDiagnostics {
+  pub state: StorageStatus,
+  pub recents: Option<StorageStatus>,
+  pub backups: Option<StorageStatus>,
+  pub trust: StorageStatus,
+  pub layouts: StorageStatus,
+}
+
+/// Internal health of the plugin.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostics {
+  pub version: String,
+  pub storage: StorageDiagnostics,
+  /// Managed windows that are open.
+  pub open_windows: usize,
+  /// Cached window states, including closed windows.
+  pub window_states: usize,
+  pub recent_files: usize,
+  pub recent_folders: usize,
+  pub folder_backups: usize,
+  pub empty_window_backups: usize,
+  /// Times the event loop had to wait for a cache lock.
+  pub lock_contentions: usize,
+  /// Last internal errors, oldest first.
+  pub errors: Vec<RecordedError>,
+}
+
+/// Log an internal error and keep it for the diagnostics report.
+pub fn log_error<E: Debug>(error: &E) {
+  let message = format!("{:?}", error);
+
+  log::error!("{}", message);
+
+  if let Ok(mut errors) = RECORDED_ERRORS.lock() {
+    if errors.len() == MAX_RECORDED_ERRORS {
+      errors.pop_front();
+    }
+
+    errors.push_back(RecordedError {
+      time: now().as_millis() as u64,
+      message,
+    });
+  }
+}
+
+/// Log an internal error and send it to the window it's about.
+///
+/// Lets the frontend show errors of background work, which has no caller to return them to.
+pub fn report_window_error<R: Runtime, M: Manager<R>>(manager: &M, label: &str, error: &Error) {
+  log_error(error);
+
+  let window = match manager.get_window(label) {
+    Some(window) => window,
+    None => return,
+  };
+
+  let payload = WindowErrorPayload {
+    label: label.to_string(),
+    kind: error.kind(),
+    message: error.to_string(),
+  };
+
+  if let Err(e) = window.emit(WINDOW_ERROR_EVENT, payload) {
+    log_error(&e);
+  }
+}
+
+/// Lock a cache for writing, counting the times the lock was held by someone else.
+///
+/// Used on the event loop, where waiting blocks the app.
+pub fn write_lock<T>(lock: &RwLock<T>) -> Result<RwLockWriteGuard<'_, T>> {
+  match lock.try_write() {
+    Ok(guard) => Ok(guard),
+    Err(TryLockError::WouldBlock) => {
+      LOCK_CONTENTIONS.fetch_add(1, Ordering::Relaxed);
+
+      lock.write().map_err(|e| Error::RwLock(e.to_string()))
+    },
+    Err(TryLockError::Poisoned(e)) => Err(Error::RwLock(e.to_string())),
+  }
+}
+
+/// Collect the internal health of the plugin.
+pub fn get_diagnostics<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Diagnostics> {
+  let (state, window_states) = {
+    let cache = manager.state::<WindowsStateCache>();
+    let cache = cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    (cache.storage_status(), cache.state().opened_windows.len())
+  };
+
+  #[cfg(feature = "recents")]
+  let (recents, recent_files, recent_folders) = {
+    let cache = manager.state::<WindowsRecentsCache>();
+    let cache = cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    (Some(cache.storage_status()), cache.recents().files.len(), cache.recents().folders.len())
+  };
+  #[cfg(not(feature = "recents"))]
+  let (recents, recent_files, recent_folders) = (None, 0, 0);
+
+  #[cfg(feature = "backups")]
+  let (backups, folder_backups, empty_window_backups) = {
+    let cache = manager.state::<WindowsBackupCache>();
+    let cache = cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    (Some(cache.storage_status()), cache.backups.folders.len(), cache.backups.empty_windows.len())
+  };
+  #[cfg(not(feature = "backups"))]
+  let (backups, folder_backups, empty_window_backups) = (None, 0, 0);
+
+  let trust = manager.state::<WindowsTrustCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .storage_status();
+
+  let layouts = manager.state::<WindowsLayoutCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .storage_status();
+
+  let open_windows = manager.windows()
+    .into_keys()
+    .filter(|label| label != SPLASH_WINDOW_LABEL)
+    .count();
+
+  let errors = match RECORDED_ERRORS.lock() {
+    Ok(errors) => errors.iter().cloned().collect(),
+    Err(_e) => Vec::new(),
+  };
+
+  Ok(Diagnostics {
+    version: env!("CARGO_PKG_VERSION").to_string(),
+    storage: StorageDiagnostics { state, recents, backups, trust, layouts },
+    open_windows,
+    window_states,
+    recent_files,
+    recent_folders,
+    folder_backups,
+    empty_window_backups,
+    lock_contentions: LOCK_CONTENTIONS.load(Ordering::Relaxed),
+    errors,
+  })
+}
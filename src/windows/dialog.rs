@@ -0,0 +1,92 @@
+//! Dialog.
+//!
+//! This module contains basic methods and types for picking resources with the native dialog.
+//! The dialog needs the `dialog` feature, without it picking fails with [`Error::DialogUnavailable`].
+
+use std::{path::PathBuf, result::Result as StdResult};
+
+use serde::Deserialize;
+
+use tauri::{Runtime, Window};
+
+use crate::error::Error;
+
+use super::{get_default_open_directory, OpenBehavior};
+
+type Result<T> = StdResult<T, Error>;
+
+/// File filter of the dialog.
+#[derive(Clone, Deserialize)]
+pub struct DialogFilter {
+  pub name: String,
+  /// Extensions without the leading dot, e.g. `rs`.
+  pub extensions: Vec<String>,
+}
+
+/// Options of the pick and open dialogs.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PickOptions {
+  pub title: Option<String>,
+  pub filters: Vec<DialogFilter>,
+  pub multiple: bool,
+  /// Directory the dialog starts in, defaults to [`get_default_open_directory`].
+  ///
+  /// [`get_default_open_directory`]: super::WindowsAPI::get_default_open_directory
+  pub default_directory: Option<PathBuf>,
+  pub behavior: OpenBehavior,
+}
+
+/// Show the native dialog to pick files, or folders if `directory` is set.
+///
+/// Returns `None` if the dialog was cancelled.
+pub fn pick_paths<R: Runtime>(window: &Window<R>, options: &PickOptions, directory: bool) -> Result<Option<Vec<PathBuf>>> {
+  let default_directory = options.default_directory.clone()
+    .or_else(|| get_default_open_directory(window));
+
+  show_dialog(window, options, directory, default_directory)
+}
+
+#[cfg(feature = "dialog")]
+fn show_dialog<R: Runtime>(
+  window: &Window<R>,
+  options: &PickOptions,
+  directory: bool,
+  default_directory: Option<PathBuf>,
+) -> Result<Option<Vec<PathBuf>>> {
+  use tauri::api::dialog::blocking::FileDialogBuilder;
+
+  let mut dialog = FileDialogBuilder::new().set_parent(window);
+
+  if let Some(title) = &options.title {
+    dialog = dialog.set_title(title);
+  }
+
+  for filter in &options.filters {
+    let extensions = filter.extensions.iter().map(String::as_str).collect::<Vec<&str>>();
+    dialog = dialog.add_filter(&filter.name, &extensions);
+  }
+
+  if let Some(default_directory) = default_directory {
+    dialog = dialog.set_directory(default_directory);
+  }
+
+  let paths = match (directory, options.multiple) {
+    (true, true) => dialog.pick_folders(),
+    (true, false) => dialog.pick_folder().map(|path| vec![path]),
+    (false, true) => dialog.pick_files(),
+    (false, false) => dialog.pick_file().map(|path| vec![path]),
+  };
+
+  Ok(paths.filter(|paths| !paths.is_empty()))
+}
+
+#[cfg(not(feature = "dialog"))]
+fn show_dialog<R: Runtime>(
+  _window: &Window<R>,
+  _options: &PickOptions,
+  _directory: bool,
+  _default_directory: Option<PathBuf>,
+) -> Result<Option<Vec<PathBuf>>> {
+  Err(Error::DialogUnavailable)
+}
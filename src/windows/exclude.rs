@@ -0,0 +1,24 @@
+//! Exclude.
+//!
+//! This module contains basic methods for windows the plugin must not manage.
+//! Excluded windows, e.g. devtools or auxiliary windows of the app, are ignored by the window event handlers and never cached.
+
+use tauri::{Manager, Runtime};
+
+use super::WindowsSettings;
+
+/// Check if a label matches a pattern, a pattern ending with `*` matches labels starting with the rest.
+fn matches_label(pattern: &str, label: &str) -> bool {
+  match pattern.strip_suffix('*') {
+    Some(prefix) => label.starts_with(prefix),
+    None => pattern == label,
+  }
+}
+
+/// Check if a window is excluded from management by the `exclude` setting.
+pub fn is_excluded<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> bool {
+  match manager.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.exclude.iter().any(|pattern| matches_label(pattern, label)),
+    Err(_e) => false,
+  }
+}
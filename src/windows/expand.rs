@@ -0,0 +1,225 @@
+//! Expand.
+//!
+//! This module contains basic methods and types for expanding the folders of open requests into their files.
+//! Files are matched by a glob pattern, ignored files and folders use the `.gitignore` syntax.
+
+use std::{
+  fs::{read_dir, read_to_string, symlink_metadata},
+  path::{Path, PathBuf},
+  result::Result as StdResult,
+};
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+use super::WindowOpenable;
+
+type Result<T> = StdResult<T, Error>;
+
+const GITIGNORE_FILENAME: &str = ".gitignore";
+
+/// `*` and `?` don't match `/`, `**` matches any number of folders.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+  case_sensitive: true,
+  require_literal_separator: true,
+  require_literal_leading_dot: false,
+};
+
+/// Expansion of requested folders into their files.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FolderExpansion {
+  /// Glob pattern of files to open, relative to the folder, e.g. `**/*.md`.
+  pub pattern: String,
+  /// Ignored files and folders, in `.gitignore` syntax.
+  pub ignore: Vec<String>,
+  /// Also ignore what the `.gitignore` files of the folder and its subfolders list.
+  pub use_gitignore: bool,
+  /// Subfolders to descend into, `0` only matches files directly inside the folder.
+  pub max_depth: usize,
+  /// Files to open from each folder at most.
+  pub max_files: usize,
+}
+
+impl Default for FolderExpansion {
+  fn default() -> Self {
+    FolderExpansion {
+      pattern: "**/*".to_string(),
+      ignore: Vec::new(),
+      use_gitignore: true,
+      max_depth: 8,
+      max_files: 500,
+    }
+  }
+}
+
+/// Ignore pattern of a `.gitignore` line.
+struct IgnoreRule {
+  /// Folder the pattern is relative to.
+  base: PathBuf,
+  pattern: Pattern,
+  /// Matched against the whole path from the base, otherwise against the name.
+  anchored: bool,
+  /// Re-include matching paths, `!` prefix.
+  negated: bool,
+  /// Match folders only, `/` suffix.
+  folder_only: bool,
+}
+
+impl IgnoreRule {
+  /// Parse a line, `None` for blank lines, comments and invalid patterns.
+  fn parse(base: &Path, line: &str) -> Option<Self> {
+    let line = line.trim_end();
+
+    if line.is_empty() || line.starts_with('#') {
+      return None;
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+      Some(line) => (true, line),
+      None => (false, line),
+    };
+
+    let (folder_only, line) = match line.strip_suffix('/') {
+      Some(line) => (true, line),
+      None => (false, line),
+    };
+
+    let anchored = line.contains('/');
+
+    Some(IgnoreRule {
+      base: base.to_path_buf(),
+      pattern: Pattern::new(line.trim_start_matches('/')).ok()?,
+      anchored,
+      negated,
+      folder_only,
+    })
+  }
+
+  fn matches(&self, path: &Path, is_folder: bool) -> bool {
+    if self.folder_only && !is_folder {
+      return false;
+    }
+
+    let relative = match path.strip_prefix(&self.base) {
+      Ok(relative) => relative,
+      Err(_e) => return false,
+    };
+
+    match self.anchored {
+      true => self.pattern.matches_path_with(relative, MATCH_OPTIONS),
+      false => path.file_name().map_or(false, |name| self.pattern.matches_with(&name.to_string_lossy(), MATCH_OPTIONS)),
+    }
+  }
+}
+
+/// Check if the last rule matching the path ignores it.
+fn is_ignored(rules: &[IgnoreRule], path: &Path, is_folder: bool) -> bool {
+  rules
+    .iter()
+    .rev()
+    .find(|rule| rule.matches(path, is_folder))
+    .map_or(false, |rule| !rule.negated)
+}
+
+/// Get the files of a folder matching the expansion, sorted by path.
+pub fn expand_folder(folder: &Path, expansion: &FolderExpansion) -> Result<Vec<PathBuf>> {
+  if !folder.is_dir() {
+    return Err(Error::FolderNotFound(folder.display().to_string()));
+  }
+
+  let pattern = Pattern::new(&expansion.pattern)
+    .map_err(|e| Error::InvalidPattern(expansion.pattern.clone(), e.to_string()))?;
+
+  let mut rules = expansion.ignore
+    .iter()
+    .filter_map(|line| IgnoreRule::parse(folder, line))
+    .collect::<Vec<IgnoreRule>>();
+
+  let mut files = Vec::new();
+
+  walk(folder, folder, 0, &pattern, expansion, &mut rules, &mut files);
+
+  Ok(files)
+}
+
+fn walk(
+  root: &Path,
+  folder: &Path,
+  depth: usize,
+  pattern: &Pattern,
+  expansion: &FolderExpansion,
+  rules: &mut Vec<IgnoreRule>,
+  files: &mut Vec<PathBuf>,
+) {
+  let inherited_rules = rules.len();
+
+  if expansion.use_gitignore {
+    if let Ok(contents) = read_to_string(folder.join(GITIGNORE_FILENAME)) {
+      rules.extend(contents.lines().filter_map(|line| IgnoreRule::parse(folder, line)));
+    }
+  }
+
+  let mut entries = match read_dir(folder) {
+    Ok(dir) => dir.flatten().map(|entry| entry.path()).collect::<Vec<PathBuf>>(),
+    Err(_e) => Vec::new(),
+  };
+
+  entries.sort();
+
+  for path in entries {
+    if files.len() >= expansion.max_files {
+      break;
+    }
+
+    // Symlinked folders aren't followed
+    let is_folder = match symlink_metadata(&path) {
+      Ok(metadata) => metadata.is_dir(),
+      Err(_e) => continue,
+    };
+
+    let is_git_folder = expansion.use_gitignore && path.file_name().map_or(false, |name| name == ".git");
+
+    if is_git_folder || is_ignored(rules, &path, is_folder) {
+      continue;
+    }
+
+    if is_folder {
+      if depth < expansion.max_depth {
+        walk(root, &path, depth + 1, pattern, expansion, rules, files);
+      }
+    } else if path.strip_prefix(root).map_or(false, |relative| pattern.matches_path_with(relative, MATCH_OPTIONS)) {
+      files.push(path);
+    }
+  }
+
+  // Rules of a `.gitignore` only apply to its folder
+  rules.truncate(inherited_rules);
+}
+
+/// Replace requested folders with their files, folders requested along with a file are kept.
+pub fn expand_openables(uris_to_open: &Option<Vec<WindowOpenable>>, expansion: &FolderExpansion) -> Result<Option<Vec<WindowOpenable>>> {
+  let uris = match uris_to_open {
+    Some(uris) => uris,
+    None => return Ok(None),
+  };
+
+  let mut expanded = Vec::new();
+
+  for openable in uris {
+    match (&openable.file, &openable.folder) {
+      (None, Some(folder)) => {
+        expanded.extend(
+          expand_folder(folder, expansion)?
+            .into_iter()
+            .map(|file| WindowOpenable::new(Some(file), None))
+        );
+      },
+      _ => expanded.push(openable.clone()),
+    }
+  }
+
+  Ok(Some(expanded))
+}
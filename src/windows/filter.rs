@@ -0,0 +1,61 @@
+//! Filter.
+//!
+//! This module contains basic methods and types for filtering the paths of open requests.
+//! Apps register a filter on the plugin builder, it runs before any window is created.
+
+use std::result::Result as StdResult;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{OpenConfiguration, PathToOpen};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Filter invoked with the paths of every open request.
+///
+/// Returns the paths to open, rewritten or narrowed down, or a reason rejecting the request.
+/// E.g. `.zip` files are left out and passed to an import flow of the app.
+/// Register it with [`TauriWindows::register_open_filter`].
+///
+/// [`TauriWindows::register_open_filter`]: crate::TauriWindows::register_open_filter
+pub trait WindowsOpenFilter: Send + Sync {
+  fn filter(&self, configuration: &OpenConfiguration, paths_to_open: Vec<PathToOpen>) -> StdResult<Vec<PathToOpen>, String>;
+}
+
+impl<F> WindowsOpenFilter for F
+where
+  F: Fn(&OpenConfiguration, Vec<PathToOpen>) -> StdResult<Vec<PathToOpen>, String> + Send + Sync
+{
+  fn filter(&self, configuration: &OpenConfiguration, paths_to_open: Vec<PathToOpen>) -> StdResult<Vec<PathToOpen>, String> {
+    self(configuration, paths_to_open)
+  }
+}
+
+/// Managed state for the open filter.
+#[derive(Default)]
+pub struct WindowsOpenFilterHook(pub Option<Box<dyn WindowsOpenFilter>>);
+
+/// Pass the paths of an open request through the registered filter, if any.
+///
+/// Requests with nothing to open aren't filtered, filtering every path out rejects the request.
+pub fn filter_paths_to_open<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  configuration: &OpenConfiguration,
+  paths_to_open: Vec<PathToOpen>,
+) -> Result<Vec<PathToOpen>> {
+  let hook = manager.state::<WindowsOpenFilterHook>();
+
+  let filter = match &hook.0 {
+    Some(filter) if !paths_to_open.is_empty() => filter,
+    _ => return Ok(paths_to_open),
+  };
+
+  let paths_to_open = filter.filter(configuration, paths_to_open).map_err(Error::OpenRejected)?;
+
+  match paths_to_open.is_empty() {
+    true => Err(Error::OpenRejected("every path was filtered out".to_string())),
+    false => Ok(paths_to_open),
+  }
+}
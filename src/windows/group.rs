@@ -0,0 +1,154 @@
+//! Group.
+//!
+//! This module contains basic methods and types for window groups.
+//! Windows tagged with the same group belong to one logical project and can be acted on together.
+
+use std::{cmp::Reverse, path::PathBuf, result::Result as StdResult};
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime, Window};
+
+use crate::error::Error;
+
+use super::{
+  exclude::is_excluded,
+  get_focus_history,
+  window::WindowMode,
+  WindowsStateCache,
+  SPLASH_WINDOW_LABEL
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Serializable info about a managed window.
+#[derive(Clone, Serialize)]
+pub struct WindowInfo {
+  pub label: String,
+  /// Stable id of the window.
+  pub id: u32,
+  /// Index of the monitor the window is on.
+  pub display: u32,
+  pub folder: Option<PathBuf>,
+  pub group: Option<String>,
+  pub mode: WindowMode,
+  /// Whether the document of the window has unsaved changes.
+  pub dirty: bool,
+  /// Whether the plugin manages the window, windows created by the app are not until adopted.
+  pub managed: bool,
+}
+
+/// List windows that are open, managed by the plugin or not, except excluded ones.
+pub fn list_windows<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Vec<WindowInfo>> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut windows = manager.windows()
+    .into_keys()
+    .filter(|label| label != SPLASH_WINDOW_LABEL && !is_excluded(manager, label))
+    .map(|label| {
+      let state = cache.get_item(&label);
+      let managed = state.as_ref().map_or(false, |state| state.closed_time.is_none());
+      let state = state.unwrap_or_default();
+
+      WindowInfo {
+        label,
+        id: state.id,
+        display: state.display,
+        folder: state.folder,
+        group: state.group,
+        mode: state.mode,
+        dirty: state.dirty,
+        managed,
+      }
+    })
+    .collect::<Vec<WindowInfo>>();
+
+  windows.sort_by(|a, b| a.label.cmp(&b.label));
+
+  Ok(windows)
+}
+
+/// Assign a window to a group, or remove it from its group if `group` is `None`.
+pub fn set_window_group<R: Runtime, M: Manager<R>>(manager: &M, label: &str, group: Option<String>) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut state = cache.get_item(label)
+  .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+  state.group = group;
+
+  cache.set_item(label, state)
+}
+
+/// Get the open windows of a group, least recently focused first.
+fn get_group_windows<R: Runtime, M: Manager<R>>(manager: &M, group: &str) -> Result<Vec<Window<R>>> {
+  let labels = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    cache.state().opened_windows
+      .iter()
+      .filter(|(_label, state)| state.group.as_deref() == Some(group))
+      .map(|(label, _state)| label.clone())
+      .collect::<Vec<String>>()
+  };
+
+  let focus_history = get_focus_history(manager);
+
+  let mut windows = labels
+    .iter()
+    .filter_map(|label| manager.get_window(label))
+    .collect::<Vec<Window<R>>>();
+
+  if windows.is_empty() {
+    return Err(Error::GroupNotFound(group.to_string()));
+  }
+
+  windows.sort_by_key(|window| {
+    let position = focus_history.iter().position(|label| label == window.label());
+    Reverse(position.unwrap_or(usize::MAX))
+  });
+
+  Ok(windows)
+}
+
+/// Bring all windows of a group to front, keeping their stacking.
+pub fn focus_group<R: Runtime, M: Manager<R>>(manager: &M, group: &str) -> Result<()> {
+  for window in get_group_windows(manager, group)? {
+    if window.is_minimized()? {
+      window.unminimize()?;
+    }
+
+    window.set_focus()?;
+  }
+
+  Ok(())
+}
+
+/// Close all windows of a group.
+///
+/// Windows go through the usual close confirmation.
+pub fn close_group<R: Runtime, M: Manager<R>>(manager: &M, group: &str) -> Result<()> {
+  for window in get_group_windows(manager, group)? {
+    window.close()?;
+  }
+
+  Ok(())
+}
+
+/// Minimize all windows of a group.
+pub fn minimize_group<R: Runtime, M: Manager<R>>(manager: &M, group: &str) -> Result<()> {
+  for window in get_group_windows(manager, group)? {
+    window.minimize()?;
+  }
+
+  Ok(())
+}
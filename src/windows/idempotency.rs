@@ -0,0 +1,73 @@
+//! Idempotency.
+//!
+//! This module contains basic methods and types for open requests submitted more than once.
+//! Successful requests are remembered by their `request_id` for a short time and repeats get the first result.
+
+use std::{
+  collections::HashMap,
+  result::Result as StdResult,
+  sync::RwLock,
+  time::{Duration, Instant},
+};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{OpenConfiguration, OpenPath, OpenedWindow};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Time a request id is remembered after its request opened.
+pub const REQUEST_ID_TTL: Duration = Duration::from_secs(30);
+
+/// Result of an open request, without the window.
+pub struct OpenedRequest {
+  pub label: String,
+  pub path: OpenPath,
+  pub configuration: Option<OpenConfiguration>,
+  pub trace: Option<Vec<String>>,
+  opened_at: Instant,
+}
+
+/// Managed state for recent open requests, keyed by request id.
+#[derive(Default)]
+pub struct WindowsOpenRequests(pub RwLock<HashMap<String, OpenedRequest>>);
+
+/// Get the first result of a request opened recently with the same id.
+///
+/// Expired requests are dropped, a request whose window was closed since opens again.
+pub fn find_opened_request<R: Runtime, M: Manager<R>>(manager: &M, request_id: &str) -> Result<Option<OpenedWindow<R>>> {
+  let windows_open_requests = manager.state::<WindowsOpenRequests>();
+
+  let mut requests = windows_open_requests.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  requests.retain(|_request_id, request| request.opened_at.elapsed() < REQUEST_ID_TTL);
+
+  Ok(
+    requests.get(request_id).and_then(|request| {
+      manager.get_window(&request.label).map(|window| OpenedWindow {
+        window,
+        path: request.path,
+        configuration: request.configuration.clone(),
+        trace: request.trace.clone(),
+      })
+    })
+  )
+}
+
+/// Remember the result of a request for repeats of its id.
+pub fn record_opened_request<R: Runtime, M: Manager<R>>(manager: &M, request_id: &str, opened_window: &OpenedWindow<R>) -> Result<()> {
+  manager.state::<WindowsOpenRequests>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .insert(request_id.to_string(), OpenedRequest {
+    label: opened_window.window.label().to_string(),
+    path: opened_window.path,
+    configuration: opened_window.configuration.clone(),
+    trace: opened_window.trace.clone(),
+    opened_at: Instant::now(),
+  });
+
+  Ok(())
+}
@@ -0,0 +1,165 @@
+//! Index.
+//!
+//! This module contains basic methods and types for looking up open windows by folder.
+//! The state cache keeps the index and rebuilds it after changes, lookups don't scan every window state.
+
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  path::{Path, PathBuf},
+  result::Result as StdResult,
+  sync::Arc,
+};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{
+  window::{normalize_path, WindowsState},
+  WindowsStateCache
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Windows by normalized folder, for window states that aren't closed.
+#[derive(Clone, Default)]
+pub struct FolderIndex {
+  /// Labels of windows by folder, sorted so the folders containing a path are found by its ancestors.
+  folders: BTreeMap<PathBuf, Vec<String>>,
+  /// Folder of each window, `None` for empty windows.
+  windows: HashMap<String, Option<PathBuf>>,
+  /// Normalized form of indexed folders, reused by the next rebuild to skip the filesystem.
+  normalized: HashMap<PathBuf, PathBuf>,
+}
+
+impl FolderIndex {
+  /// Index window states, reusing normalized folders of the previous index.
+  pub fn new(state: &WindowsState, previous: Option<&FolderIndex>) -> Self {
+    let mut index = FolderIndex::default();
+
+    for (label, window_state) in &state.opened_windows {
+      if window_state.closed_time.is_some() {
+        continue;
+      }
+
+      let folder = window_state.folder.as_ref().map(|folder| {
+        let normalized = previous
+          .and_then(|previous| previous.normalized.get(folder).cloned())
+          .unwrap_or_else(|| normalize_path(folder));
+
+        index.normalized.insert(folder.clone(), normalized.clone());
+
+        normalized
+      });
+
+      index.insert_normalized(label, folder);
+    }
+
+    index
+  }
+
+  /// Add a window, or update the folder of an indexed one.
+  pub fn insert(&mut self, label: &str, folder: Option<&Path>) {
+    self.insert_normalized(label, folder.map(normalize_path));
+  }
+
+  fn insert_normalized(&mut self, label: &str, folder: Option<PathBuf>) {
+    if let Some(Some(previous)) = self.windows.remove(label) {
+      if let Some(labels) = self.folders.get_mut(&previous) {
+        labels.retain(|indexed| indexed != label);
+
+        if labels.is_empty() {
+          self.folders.remove(&previous);
+        }
+      }
+    }
+
+    if let Some(folder) = &folder {
+      self.folders.entry(folder.clone()).or_default().push(label.to_string());
+    }
+
+    self.windows.insert(label.to_string(), folder);
+  }
+
+  /// Get the folder of an indexed window, `None` if the window isn't indexed.
+  pub fn folder_of(&self, label: &str) -> Option<Option<&Path>> {
+    self.windows.get(label).map(Option::as_deref)
+  }
+
+  /// Get labels of windows with the folder open.
+  pub fn windows_on_folder(&self, folder: &Path) -> Vec<&String> {
+    self.folders.get(&normalize_path(folder)).into_iter().flatten().collect()
+  }
+
+  /// Get labels of windows with a folder containing the file.
+  ///
+  /// Every ancestor of the file is looked up, linear in the depth of the file
+  /// rather than in the number of windows.
+  pub fn windows_on_file(&self, file: &Path) -> Vec<&String> {
+    normalize_path(file)
+      .ancestors()
+      .filter_map(|folder| self.folders.get(folder))
+      .flatten()
+      .collect()
+  }
+}
+
+/// Folder lookups of an open request.
+///
+/// Holds the index of the state cache and the windows open when the request started,
+/// read under a single lock of the state cache.
+#[derive(Default)]
+pub struct FolderLookup {
+  index: Arc<FolderIndex>,
+  open_windows: HashSet<String>,
+  /// Window labels by focus order, most recently focused first.
+  focus_history: Vec<String>,
+}
+
+impl FolderLookup {
+  pub fn read<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Self> {
+    let open_windows = manager.windows().into_keys().collect::<HashSet<String>>();
+
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    Ok(FolderLookup {
+      index: cache.folder_index(),
+      open_windows,
+      focus_history: cache.focus_history().to_vec(),
+    })
+  }
+
+  /// Add a window opened during the request.
+  pub fn insert(&mut self, label: &str, folder: Option<&Path>) {
+    Arc::make_mut(&mut self.index).insert(label, folder);
+    self.open_windows.insert(label.to_string());
+  }
+
+  /// Check if a window has a folder open.
+  pub fn is_single_folder(&self, label: &str) -> Result<bool> {
+    self.index.folder_of(label)
+      .map(|folder| folder.is_some())
+      .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))
+  }
+
+  /// Get the open window the folder is open in.
+  pub fn window_on_folder(&self, folder: &Path) -> Option<&str> {
+    self.most_recently_focused(self.index.windows_on_folder(folder))
+  }
+
+  /// Get the open window with the file inside its folder.
+  pub fn window_on_file(&self, file: &Path) -> Option<&str> {
+    self.most_recently_focused(self.index.windows_on_file(file))
+  }
+
+  fn most_recently_focused<'a>(&self, labels: Vec<&'a String>) -> Option<&'a str> {
+    labels
+      .into_iter()
+      .filter(|label| self.open_windows.contains(*label))
+      .min_by_key(|label| self.focus_history.iter().position(|focused| focused == *label).unwrap_or(usize::MAX))
+      .map(String::as_str)
+  }
+}
@@ -0,0 +1,232 @@
+//! Label.
+//!
+//! This module contains basic methods and types for generating and validating window labels.
+//! Provides the label strategies that can be set on the plugin builder.
+//! Labels may only hold ascii alphanumerics and `-`, `/`, `:`, `_`, as required by tauri.
+
+use std::{
+  path::Path,
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    RwLock
+  },
+};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{exclude::is_excluded, WindowsSettings, WindowsStateCache};
+
+type Result<T> = StdResult<T, Error>;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+/// Prefix attached to plugin generated labels, unless the `label_prefix` setting is set.
+const DEFAULT_LABEL_PREFIX: &str = "windows_";
+
+/// Longest label accepted.
+const MAX_LABEL_LENGTH: usize = 128;
+
+/// Maximum tries before falling back to a uuid label.
+const MAX_LABEL_ATTEMPTS: usize = 100;
+
+/// Strategy used to generate labels for new windows.
+pub enum LabelStrategy {
+  /// Attach the label prefix to a process-local counter.
+  Counter,
+  /// Attach the label prefix to a random uuid.
+  Uuid,
+  /// Use a slug of the folder name, falls back to counter for windows without folder.
+  FolderSlug,
+  /// Use a user provided function, receives the folder opened in the window if any.
+  Custom(Box<dyn Fn(Option<&Path>) -> String + Send + Sync>),
+}
+
+impl Default for LabelStrategy {
+  fn default() -> Self {
+    LabelStrategy::Counter
+  }
+}
+
+/// Managed state for window label generation.
+#[derive(Default)]
+pub struct WindowLabels(pub RwLock<LabelStrategy>);
+
+impl WindowLabels {
+  pub fn new(strategy: LabelStrategy) -> Self {
+    Self(RwLock::new(strategy))
+  }
+}
+
+/// Check if a character is allowed in labels.
+fn is_label_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_')
+}
+
+/// Replace characters not allowed in labels with `-` and cut the label to the maximum length.
+///
+/// An empty result is replaced by a counter label.
+pub fn sanitize_label(label: &str) -> String {
+  let sanitized = label
+    .chars()
+    .map(|c| if is_label_char(c) { c } else { '-' })
+    .take(MAX_LABEL_LENGTH)
+    .collect::<String>();
+
+  match sanitized.is_empty() {
+    true => counter_label(DEFAULT_LABEL_PREFIX),
+    false => sanitized,
+  }
+}
+
+/// Check the charset and length of a label.
+pub fn validate_label(label: &str) -> Result<()> {
+  let invalid = |reason: &str| Err(Error::InvalidLabel(label.to_string(), reason.to_string()));
+
+  if label.is_empty() {
+    return invalid("label is empty");
+  }
+
+  if label.len() > MAX_LABEL_LENGTH {
+    return invalid(&format!("label is longer than {} characters", MAX_LABEL_LENGTH));
+  }
+
+  if let Some(c) = label.chars().find(|c| !is_label_char(*c)) {
+    return invalid(&format!("character `{}` is not allowed, use ascii alphanumerics, `-`, `/`, `:` or `_`", c));
+  }
+
+  Ok(())
+}
+
+/// Check a label supplied for a new window.
+///
+/// The label must not be used by a live window, or by the cached state of a window still to be restored.
+/// States of closed windows are taken over, so a window opened again with its label gets its old state.
+/// The label prefix is reserved for generated labels, and excluded labels are left to the app.
+pub fn validate_new_label<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  validate_label(label)?;
+
+  if is_excluded(manager, label) {
+    return Err(Error::InvalidLabel(label.to_string(), "the label is excluded from management".to_string()));
+  }
+
+  let prefix = get_label_prefix(manager);
+
+  if !prefix.is_empty() && label.starts_with(&prefix) {
+    return Err(Error::InvalidLabel(label.to_string(), format!("the `{}` prefix is reserved for generated labels", prefix)));
+  }
+
+  if manager.get_window(label).is_some() {
+    return Err(Error::InvalidLabel(label.to_string(), "a window with the label is open".to_string()));
+  }
+
+  let pending_restore = manager.state::<WindowsStateCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .get_item(label)
+  .map_or(false, |state| state.closed_time.is_none());
+
+  if pending_restore {
+    return Err(Error::InvalidLabel(label.to_string(), "the label belongs to a window to be restored".to_string()));
+  }
+
+  Ok(())
+}
+
+/// Get the prefix of generated labels.
+pub fn get_label_prefix<R: Runtime, M: Manager<R>>(manager: &M) -> String {
+  let label_prefix = match manager.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.label_prefix.clone(),
+    Err(_e) => None,
+  };
+
+  label_prefix.unwrap_or_else(|| DEFAULT_LABEL_PREFIX.to_string())
+}
+
+/// Create a counter label.
+fn counter_label(prefix: &str) -> String {
+  format!("{}{}", prefix, COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Create a uuid label.
+fn uuid_label(prefix: &str) -> String {
+  format!("{}{}", prefix, uuid::Uuid::new_v4().simple())
+}
+
+/// Create a label safe slug from the folder name.
+fn folder_slug(folder: &Path) -> Option<String> {
+  let name = folder.file_name()?.to_string_lossy().to_lowercase();
+
+  let slug = name
+    .split(|c: char| !c.is_ascii_alphanumeric())
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<&str>>()
+    .join("-");
+
+  if slug.is_empty() {
+    None
+  } else {
+    Some(slug)
+  }
+}
+
+/// Check if a label is used by a live window or a cached window state, or is excluded.
+fn is_label_taken<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> bool {
+  if manager.get_window(label).is_some() || is_excluded(manager, label) {
+    return true;
+  }
+
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let res = match windows_state_cache.0.read() {
+    Ok(cache) => cache.get_item(label).is_some(),
+    Err(_e) => false,
+  };
+
+  res
+}
+
+/// Create a unique window label.
+///
+/// Uses the managed [`LabelStrategy`], retrying until the label doesn't collide
+/// with live windows or cached window states.
+pub fn new_window_label<R: Runtime, M: Manager<R>>(manager: &M, folder: Option<&Path>) -> String {
+  let window_labels = manager.state::<WindowLabels>();
+
+  let prefix = get_label_prefix(manager);
+
+  let strategy = match window_labels.0.read() {
+    Ok(strategy) => strategy,
+    Err(_e) => return uuid_label(&prefix),
+  };
+
+  let slug = match (&*strategy, folder) {
+    (LabelStrategy::FolderSlug, Some(folder)) => folder_slug(folder),
+    _ => None,
+  };
+
+  for attempt in 0..MAX_LABEL_ATTEMPTS {
+    let label = match &*strategy {
+      LabelStrategy::Counter => counter_label(&prefix),
+      LabelStrategy::Uuid => uuid_label(&prefix),
+      LabelStrategy::FolderSlug => match &slug {
+        Some(slug) if attempt == 0 => slug.clone(),
+        Some(slug) => format!("{}-{}", slug, attempt + 1),
+        None => counter_label(&prefix),
+      },
+      // Apps may return anything, it's made safe instead of failing the open
+      LabelStrategy::Custom(generate) => match attempt {
+        0 => sanitize_label(&generate(folder)),
+        _ => sanitize_label(&format!("{}-{}", generate(folder), attempt + 1)),
+      },
+    };
+
+    if !is_label_taken(manager, &label) {
+      return label;
+    }
+  }
+
+  uuid_label(&prefix)
+}
+
@@ -0,0 +1,231 @@
+//! Layout.
+//!
+//! This module contains basic methods and types for window layout presets.
+//! Layouts store monitor, bounds and stacking of all windows under a name.
+
+use std::{
+  collections::HashMap,
+  path::Path,
+  result::Result as StdResult,
+  sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{
+  snap::set_window_bounds,
+  storage::{StorageCipher, StorageData, StorageFile, StorageStatus},
+  window::{get_window_monitor, get_working_area, WindowBounds, WindowBoundsTrait, WindowMode},
+  WindowsStateCache,
+  SPLASH_WINDOW_LABEL
+};
+
+type Result<T> = StdResult<T, Error>;
+
+pub(crate) const LAYOUTS_FILENAME: &str = ".windows_layouts_session";
+
+/// Arrangement of a window in a layout.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct WindowLayoutEntry {
+  pub label: String,
+  /// Name of the monitor the window was on.
+  pub monitor: Option<String>,
+  /// Work area of that monitor, used to follow the monitor if it moved.
+  pub monitor_bounds: Option<WindowBounds>,
+  pub bounds: WindowBounds,
+  pub mode: WindowMode,
+}
+
+/// Named arrangement of windows.
+///
+/// Entries are ordered bottom to top.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct WindowLayout {
+  pub windows: Vec<WindowLayoutEntry>,
+}
+
+/// Saved layouts by name.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct WindowLayouts {
+  pub layouts: HashMap<String, WindowLayout>,
+}
+
+impl StorageData for WindowLayouts {}
+
+pub struct InnerWindowsLayoutCache {
+  storage: StorageFile<WindowLayouts>,
+}
+
+impl InnerWindowsLayoutCache {
+  pub fn new(file: &Path) -> Self {
+    InnerWindowsLayoutCache {
+      storage: StorageFile::new(file)
+    }
+  }
+
+  /// Cache kept in memory only, nothing is read from or written to disk.
+  pub fn in_memory() -> Self {
+    InnerWindowsLayoutCache {
+      storage: StorageFile::in_memory()
+    }
+  }
+
+  /// Encrypt the file backing the cache, see [`StorageFile::set_cipher`].
+  pub fn set_cipher(&mut self, cipher: Arc<dyn StorageCipher>) {
+    self.storage.set_cipher(cipher);
+  }
+
+  pub fn get_layout(&self, name: &str) -> Option<WindowLayout> {
+    self.storage.get().layouts.get(name).cloned()
+  }
+
+  pub fn set_layout(&mut self, name: &str, layout: WindowLayout) -> Result<()> {
+    self.storage.get_mut().layouts.insert(name.to_string(), layout);
+    self.storage.save()
+  }
+
+  pub fn storage_status(&self) -> StorageStatus {
+    self.storage.status()
+  }
+
+  pub fn save(&mut self) -> Result<()> {
+    self.storage.save()
+  }
+}
+
+impl Default for InnerWindowsLayoutCache {
+  fn default() -> Self {
+    InnerWindowsLayoutCache::in_memory()
+  }
+}
+
+#[derive(Default)]
+pub struct WindowsLayoutCache(pub RwLock<InnerWindowsLayoutCache>);
+
+impl WindowsLayoutCache {
+  pub fn in_memory() -> Self {
+    Self(RwLock::new(InnerWindowsLayoutCache::in_memory()))
+  }
+
+  /// Cache backed by its file in the directory, encrypted if a cipher is set.
+  pub fn open(dir: &Path, cipher: Option<Arc<dyn StorageCipher>>) -> Self {
+    let mut cache = InnerWindowsLayoutCache::new(&dir.join(LAYOUTS_FILENAME));
+
+    if let Some(cipher) = cipher {
+      cache.set_cipher(cipher);
+    }
+
+    Self(RwLock::new(cache))
+  }
+}
+
+/// Save the arrangement of all windows as a named layout.
+///
+/// Stacking follows focus order, the most recently focused window is on top.
+pub fn save_layout<R: Runtime, M: Manager<R>>(manager: &M, name: &str) -> Result<()> {
+  let focus_times = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    cache.state().opened_windows
+      .keys()
+      .map(|label| (label.clone(), cache.last_focus_time(label).unwrap_or_default()))
+      .collect::<HashMap<_, _>>()
+  };
+
+  let mut windows = manager.windows()
+    .into_iter()
+    .filter(|(label, _window)| label != SPLASH_WINDOW_LABEL)
+    .collect::<Vec<_>>();
+
+  windows.sort_by_key(|(label, _window)| focus_times.get(label).cloned().unwrap_or_default());
+
+  let mut entries = Vec::new();
+
+  for (label, window) in windows {
+    let monitor = get_window_monitor(&window)?;
+
+    let mode = if window.is_fullscreen()? {
+      WindowMode::Fullscreen
+    } else if window.is_maximized()? {
+      WindowMode::Maximized
+    } else if window.is_minimized()? {
+      WindowMode::Minimized
+    } else {
+      WindowMode::Normal
+    };
+
+    entries.push(WindowLayoutEntry {
+      label,
+      monitor: monitor.as_ref().and_then(|monitor| monitor.name().cloned()),
+      monitor_bounds: monitor.as_ref().map(get_working_area),
+      bounds: window.get_bounds(),
+      mode,
+    });
+  }
+
+  let windows_layout_cache = manager.state::<WindowsLayoutCache>();
+
+  let mut cache = windows_layout_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  cache.set_layout(name, WindowLayout { windows: entries })
+}
+
+/// Re-apply a named layout to the windows that are still open.
+pub fn apply_layout<R: Runtime, M: Manager<R>>(manager: &M, name: &str) -> Result<()> {
+  let layout = manager.state::<WindowsLayoutCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .get_layout(name)
+  .ok_or_else(|| Error::LayoutNotFound(name.to_string()))?;
+
+  for entry in layout.windows {
+    let window = match manager.get_window(&entry.label) {
+      Some(window) => window,
+      None => continue,
+    };
+
+    let monitors = window.available_monitors()?;
+
+    // Follow the monitor if it moved since the layout was saved
+    let monitor_area = monitors
+      .iter()
+      .find(|monitor| monitor.name().is_some() && monitor.name() == entry.monitor.as_ref())
+      .map(get_working_area);
+
+    let bounds = match (monitor_area, entry.monitor_bounds) {
+      (Some(area), Some(saved_area)) => WindowBounds {
+        x: entry.bounds.x - saved_area.x + area.x,
+        y: entry.bounds.y - saved_area.y + area.y,
+        ..entry.bounds
+      },
+      _ => entry.bounds,
+    };
+
+    if window.is_minimized()? {
+      window.unminimize()?;
+    }
+
+    set_window_bounds(&window, &bounds)?;
+
+    match entry.mode {
+      WindowMode::Fullscreen => window.set_fullscreen(true)?,
+      WindowMode::Maximized => window.maximize()?,
+      WindowMode::Minimized => window.minimize()?,
+      WindowMode::Normal => {},
+    };
+
+    // Focusing bottom to top restores the stacking
+    if entry.mode != WindowMode::Minimized {
+      window.set_focus()?;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,145 @@
+//! Magnetism.
+//!
+//! This module contains basic methods and types for snapping moved windows to nearby edges.
+//! Windows close to an edge of their monitor work area, or of another managed window, are moved flush with it.
+
+use std::result::Result as StdResult;
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, PhysicalPosition, Runtime};
+
+use crate::error::Error;
+
+use super::{
+  window::{get_window_monitor, get_working_area, WindowBounds, WindowBoundsTrait},
+  WindowsSettings,
+  WindowsStateCache,
+  SPLASH_WINDOW_LABEL
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Magnetism settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > magnetism`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MagnetismSettings {
+  /// Snap moved windows to monitor edges and the edges of other managed windows.
+  pub enabled: bool,
+  /// Distance in physical pixels within which edges snap.
+  pub threshold: u32,
+}
+
+impl Default for MagnetismSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      threshold: 12,
+    }
+  }
+}
+
+/// Get the smallest offset moving one of the edges onto one of the targets, `0` if none is within the threshold.
+fn snap_offset(edges: &[i32], targets: &[i32], threshold: u32) -> i32 {
+  edges
+    .iter()
+    .flat_map(|edge| targets.iter().map(move |target| target - edge))
+    .filter(|offset| offset.unsigned_abs() <= threshold)
+    .min_by_key(|offset| offset.unsigned_abs())
+    .unwrap_or(0)
+}
+
+/// Check whether two ranges overlap or touch.
+fn spans_overlap(start: i32, length: u32, other_start: i32, other_length: u32) -> bool {
+  start <= other_start + other_length as i32 && other_start <= start + length as i32
+}
+
+/// Get the position a window snaps to, given the work area of its monitor and the bounds of other windows.
+///
+/// Edges of other windows only attract when the windows are side by side, or above one another.
+pub fn get_magnetic_position(
+  bounds: &WindowBounds,
+  area: Option<&WindowBounds>,
+  others: &[WindowBounds],
+  threshold: u32,
+) -> PhysicalPosition<i32> {
+  let mut x_targets = Vec::new();
+  let mut y_targets = Vec::new();
+
+  if let Some(area) = area {
+    x_targets.extend([area.x, area.x + area.width as i32]);
+    y_targets.extend([area.y, area.y + area.height as i32]);
+  }
+
+  for other in others {
+    if spans_overlap(bounds.y, bounds.height, other.y, other.height) {
+      x_targets.extend([other.x, other.x + other.width as i32]);
+    }
+
+    if spans_overlap(bounds.x, bounds.width, other.x, other.width) {
+      y_targets.extend([other.y, other.y + other.height as i32]);
+    }
+  }
+
+  let x_edges = [bounds.x, bounds.x + bounds.width as i32];
+  let y_edges = [bounds.y, bounds.y + bounds.height as i32];
+
+  PhysicalPosition::new(
+    bounds.x + snap_offset(&x_edges, &x_targets, threshold),
+    bounds.y + snap_offset(&y_edges, &y_targets, threshold),
+  )
+}
+
+/// Snap a window that moved to nearby edges, if magnetism is enabled.
+///
+/// Moving the window fires another move, which finds the window already flush and stops.
+pub fn handle_window_moved<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let settings = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .magnetism
+  .clone();
+
+  if !settings.enabled || label == SPLASH_WINDOW_LABEL {
+    return Ok(());
+  }
+
+  let window = match manager.get_window(label) {
+    Some(window) => window,
+    None => return Ok(()),
+  };
+
+  if window.is_minimized()? || window.is_maximized()? || window.is_fullscreen()? {
+    return Ok(());
+  }
+
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  // Only windows managed by the plugin attract
+  if cache.get_item(label).is_none() {
+    return Ok(());
+  }
+
+  let others = manager.windows()
+    .into_iter()
+    .filter(|(other_label, _other)| other_label != label && cache.get_item(other_label).is_some())
+    .filter(|(_other_label, other)| other.is_visible().unwrap_or(false) && !other.is_minimized().unwrap_or(true))
+    .map(|(_other_label, other)| other.get_bounds())
+    .collect::<Vec<WindowBounds>>();
+
+  drop(cache);
+
+  let bounds = window.get_bounds();
+  let area = get_window_monitor(&window)?.map(|monitor| get_working_area(&monitor));
+  let position = get_magnetic_position(&bounds, area.as_ref(), &others, settings.threshold);
+
+  if position.x != bounds.x || position.y != bounds.y {
+    window.set_position(position)?;
+  }
+
+  Ok(())
+}
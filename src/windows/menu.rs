@@ -3,23 +3,152 @@
 //! This module contains basic implemention of menu, submenu, and menu item types.
 //! Provides builder methods for menus, submenus, and menu items.
 
+use std::result::Result as StdResult;
+
 use tauri::{
   CustomMenuItem,
+  Manager,
   Menu as TauriMenu,
   MenuEntry,
   MenuItem,
-  Submenu
+  Runtime,
+  Submenu,
+  Window
 };
 
+use crate::error::Error;
 use crate::event::{
+  WINDOW_FOCUS_WINDOW_EVENT,
+  WINDOW_HELP_EVENT,
+  WINDOW_TOGGLE_FULLSCREEN_EVENT,
   WINDOW_NEW_WINDOW_EVENT,
   WINDOW_OPEN_FILE_EVENT,
   WINDOW_OPEN_FOLDER_EVENT,
   WINDOW_CLOSE_WINDOW_EVENT,
   WINDOW_CLOSE_FILE_EVENT,
-  WINDOW_CLOSE_FOLDER_EVENT
+  WINDOW_CLOSE_FOLDER_EVENT,
+  WINDOW_ZOOM_IN_EVENT,
+  WINDOW_ZOOM_OUT_EVENT,
+  WINDOW_ZOOM_RESET_EVENT
 };
 
+use super::group::list_windows;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Windows listed in the Window submenu, later windows are left out.
+pub const WINDOW_LIST_SIZE: usize = 9;
+
+/// Titles of the generated menus, English by default.
+///
+/// Native items, e.g. Cut or Quit, are titled by the platform.
+#[derive(Clone)]
+pub struct MenuLabels {
+  pub file: String,
+  pub edit: String,
+  pub view: String,
+  pub window: String,
+  pub help: String,
+  pub new_window: String,
+  pub open_file: String,
+  pub open_folder: String,
+  pub close_file: String,
+  pub close_folder: String,
+  pub close_window: String,
+  pub zoom_in: String,
+  pub zoom_out: String,
+  pub zoom_reset: String,
+  pub toggle_fullscreen: String,
+  pub learn_more: String,
+}
+
+impl Default for MenuLabels {
+  fn default() -> Self {
+    Self {
+      file: "File".into(),
+      edit: "Edit".into(),
+      view: "View".into(),
+      window: "Window".into(),
+      help: "Help".into(),
+      new_window: "New Window".into(),
+      open_file: "Open File".into(),
+      open_folder: "Open Folder".into(),
+      close_file: "Close File".into(),
+      close_folder: "Close Folder".into(),
+      close_window: "Close Window".into(),
+      zoom_in: "Zoom In".into(),
+      zoom_out: "Zoom Out".into(),
+      zoom_reset: "Reset Zoom".into(),
+      toggle_fullscreen: "Toggle Full Screen".into(),
+      learn_more: "Learn More".into(),
+    }
+  }
+}
+
+impl MenuLabels {
+  /// Create labels by passing each English title through a translator.
+  ///
+  /// # Examples
+  /// ```
+  /// # use tauri_plugin_windows::windows::{Menu, MenuLabels};
+  /// let labels = MenuLabels::translate(|title| match title {
+  ///   "File" => "Fichier".to_string(),
+  ///   title => title.to_string(),
+  /// });
+  ///
+  /// Menu::as_submenu_with_labels(labels.file.clone(), &labels);
+  /// ```
+  pub fn translate<F: Fn(&str) -> String>(translator: F) -> Self {
+    let labels = MenuLabels::default();
+
+    Self {
+      file: translator(&labels.file),
+      edit: translator(&labels.edit),
+      view: translator(&labels.view),
+      window: translator(&labels.window),
+      help: translator(&labels.help),
+      new_window: translator(&labels.new_window),
+      open_file: translator(&labels.open_file),
+      open_folder: translator(&labels.open_folder),
+      close_file: translator(&labels.close_file),
+      close_folder: translator(&labels.close_folder),
+      close_window: translator(&labels.close_window),
+      zoom_in: translator(&labels.zoom_in),
+      zoom_out: translator(&labels.zoom_out),
+      zoom_reset: translator(&labels.zoom_reset),
+      toggle_fullscreen: translator(&labels.toggle_fullscreen),
+      learn_more: translator(&labels.learn_more),
+    }
+  }
+}
+
+/// Submenus of the default application menu, all included by default.
+#[derive(Clone)]
+pub struct DefaultMenuOptions {
+  /// Name shown in the About item of the app submenu on macOS.
+  pub app_name: Option<String>,
+  pub labels: MenuLabels,
+  pub file: bool,
+  pub edit: bool,
+  pub view: bool,
+  pub window: bool,
+  pub help: bool,
+}
+
+impl Default for DefaultMenuOptions {
+  fn default() -> Self {
+    Self {
+      app_name: None,
+      labels: MenuLabels::default(),
+      file: true,
+      edit: true,
+      view: true,
+      window: true,
+      help: true,
+    }
+  }
+}
+
 /// Window menu builder.
 pub struct Menu {}
 
@@ -54,21 +183,141 @@ impl Menu {
     CustomMenuItem::new(WINDOW_CLOSE_FILE_EVENT, title).accelerator("CTRL+F4")
   }
 
+  /// Creates a new custom menu item for zoom in.
+  pub fn zoom_in<T: Into<String>>(title: T) -> CustomMenuItem {
+    CustomMenuItem::new(WINDOW_ZOOM_IN_EVENT, title).accelerator("CmdOrCtrl+Plus")
+  }
+
+  /// Creates a new custom menu item for zoom out.
+  pub fn zoom_out<T: Into<String>>(title: T) -> CustomMenuItem {
+    CustomMenuItem::new(WINDOW_ZOOM_OUT_EVENT, title).accelerator("CmdOrCtrl+-")
+  }
+
+  /// Creates a new custom menu item for zoom reset.
+  pub fn zoom_reset<T: Into<String>>(title: T) -> CustomMenuItem {
+    CustomMenuItem::new(WINDOW_ZOOM_RESET_EVENT, title).accelerator("CmdOrCtrl+0")
+  }
+
+  /// Creates a new custom menu item for toggle fullscreen.
+  pub fn toggle_fullscreen<T: Into<String>>(title: T) -> CustomMenuItem {
+    CustomMenuItem::new(WINDOW_TOGGLE_FULLSCREEN_EVENT, title).accelerator("F11")
+  }
+
+  /// Creates a new custom menu item for help.
+  pub fn help<T: Into<String>>(title: T) -> CustomMenuItem {
+    CustomMenuItem::new(WINDOW_HELP_EVENT, title).accelerator("F1")
+  }
+
+  /// Creates the custom menu item of a slot of the window list.
+  ///
+  /// Slots start empty and disabled, they are filled with open windows by the plugin.
+  pub fn window_list_item(slot: usize) -> CustomMenuItem {
+    CustomMenuItem::new(window_list_item_id(slot), "").disabled()
+  }
+
+  /// Creates a submenu with zoom in, zoom out, and zoom reset, user provided title.
+  ///
+  /// The plugin zooms the window of the menu when one of the items is clicked.
+  pub fn zoom_submenu<S: Into<String>>(title: S) -> Submenu {
+    Menu::zoom_submenu_with_labels(title, &MenuLabels::default())
+  }
+
+  /// Creates a submenu with zoom in, zoom out, and zoom reset, titled by the labels.
+  pub fn zoom_submenu_with_labels<S: Into<String>>(title: S, labels: &MenuLabels) -> Submenu {
+    Submenu::new(title, TauriMenu::with_items([
+      Menu::zoom_in(&labels.zoom_in).into(),
+      Menu::zoom_out(&labels.zoom_out).into(),
+      Menu::zoom_reset(&labels.zoom_reset).into(),
+    ]))
+  }
+
   /// Creates a menu filled with default menu items and submenus.
-  /// 
-  /// ## Platform-specific:
   ///
-  /// - **Windows**:
-  ///   - File
-  ///     - New Window
-  ///     - Open File
-  ///     - Open Folder
-  ///     - Close File
-  ///     - Close File
-  ///     - Close Folder
-  ///     - Close Window
+  /// Same as [`Menu::application`] with the default options.
   pub fn default() -> TauriMenu {
-    TauriMenu::with_items([Menu::as_submenu("File").into()])
+    Menu::application(DefaultMenuOptions::default())
+  }
+
+  /// Creates a complete application menu, submenus can be left out through the options.
+  ///
+  /// The plugin handles the zoom, fullscreen and window list items for the window of the menu.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS**: an app submenu with About, Services, Hide and Quit comes first, Quit isn't in File.
+  /// - **Windows / Linux**: native Undo, Redo and Zoom items are unsupported and left out by Tauri.
+  ///
+  /// - App (macOS)
+  /// - File
+  ///   - New Window, Open File, Open Folder, Close File, Close Folder, Close Window
+  ///   - Quit
+  /// - Edit
+  ///   - Undo, Redo, Cut, Copy, Paste, Select All
+  /// - View
+  ///   - Zoom In, Zoom Out, Reset Zoom
+  ///   - Toggle Full Screen
+  /// - Window
+  ///   - Minimize, Zoom
+  ///   - Open windows
+  /// - Help
+  ///   - Learn More
+  pub fn application(options: DefaultMenuOptions) -> TauriMenu {
+    let labels = &options.labels;
+
+    let mut menu = TauriMenu::new();
+
+    #[cfg(target_os = "macos")]
+    {
+      menu = menu.add_submenu(app_submenu(options.app_name.as_deref()));
+    }
+
+    if options.file {
+      menu = menu.add_submenu(file_submenu(labels));
+    }
+
+    if options.edit {
+      menu = menu.add_submenu(Submenu::new(&labels.edit, TauriMenu::with_items([
+        MenuItem::Undo.into(),
+        MenuItem::Redo.into(),
+        MenuItem::Separator.into(),
+        MenuItem::Cut.into(),
+        MenuItem::Copy.into(),
+        MenuItem::Paste.into(),
+        MenuItem::SelectAll.into(),
+      ])));
+    }
+
+    if options.view {
+      menu = menu.add_submenu(Submenu::new(&labels.view, TauriMenu::with_items([
+        Menu::zoom_in(&labels.zoom_in).into(),
+        Menu::zoom_out(&labels.zoom_out).into(),
+        Menu::zoom_reset(&labels.zoom_reset).into(),
+        MenuItem::Separator.into(),
+        Menu::toggle_fullscreen(&labels.toggle_fullscreen).into(),
+      ])));
+    }
+
+    if options.window {
+      let mut window_menu = TauriMenu::with_items([
+        MenuItem::Minimize.into(),
+        MenuItem::Zoom.into(),
+        MenuItem::Separator.into(),
+      ]);
+
+      for slot in 0..WINDOW_LIST_SIZE {
+        window_menu = window_menu.add_item(Menu::window_list_item(slot));
+      }
+
+      menu = menu.add_submenu(Submenu::new(&labels.window, window_menu));
+    }
+
+    if options.help {
+      menu = menu.add_submenu(Submenu::new(&labels.help, TauriMenu::with_items([
+        Menu::help(&labels.learn_more).into(),
+      ])));
+    }
+
+    menu
   }
 
   /// Creates a menu filled with default menu items and submenu with user provided title.
@@ -107,16 +356,12 @@ impl Menu {
   ///   - Close Folder
   ///   - Close Window
   pub fn as_menu() -> TauriMenu {
-    TauriMenu::with_items([
-      Menu::new_window("New Window").into(),
-      Menu::open_file("Open File").into(),
-      Menu::open_folder("Open Folder").into(),
-      Menu::close_file("Close File").into(),
-      Menu::close_folder("Close Folder").into(),
-      Menu::close_window("Close Window").into(),
-      MenuItem::Separator.into(),
-      MenuItem::Quit.into(),
-    ])
+    Menu::as_menu_with_labels(&MenuLabels::default())
+  }
+
+  /// Creates menu with default menu items titled by the labels.
+  pub fn as_menu_with_labels(labels: &MenuLabels) -> TauriMenu {
+    TauriMenu::with_items(Menu::menu_items_with_labels(labels))
   }
 
   /// Creates a submenu filled with default menu items, user provided title.
@@ -142,17 +387,154 @@ impl Menu {
     Submenu::new(title, Menu::as_menu())
   }
 
+  /// Creates a submenu filled with default menu items titled by the labels, user provided title.
+  pub fn as_submenu_with_labels<S: Into<String>>(title: S, labels: &MenuLabels) -> Submenu {
+    Submenu::new(title, Menu::as_menu_with_labels(labels))
+  }
+
   /// Creates vector of default menu items.
   pub fn menu_items() -> Vec<MenuEntry> {
+    Menu::menu_items_with_labels(&MenuLabels::default())
+  }
+
+  /// Creates vector of default menu items titled by the labels.
+  pub fn menu_items_with_labels(labels: &MenuLabels) -> Vec<MenuEntry> {
     vec![
-      Menu::new_window("New Window").into(),
-      Menu::open_file("Open File").into(),
-      Menu::open_folder("Open Folder").into(),
-      Menu::close_file("Close File").into(),
-      Menu::close_folder("Close Folder").into(),
-      Menu::close_window("Close Window").into(),
+      Menu::new_window(&labels.new_window).into(),
+      Menu::open_file(&labels.open_file).into(),
+      Menu::open_folder(&labels.open_folder).into(),
+      Menu::close_file(&labels.close_file).into(),
+      Menu::close_folder(&labels.close_folder).into(),
+      Menu::close_window(&labels.close_window).into(),
       MenuItem::Separator.into(),
       MenuItem::Quit.into(),
     ]
   }
 }
+
+/// App submenu of macOS, its title is replaced by the app name.
+#[cfg(target_os = "macos")]
+fn app_submenu(app_name: Option<&str>) -> Submenu {
+  let mut menu = TauriMenu::new();
+
+  if let Some(app_name) = app_name {
+    menu = menu
+      .add_native_item(MenuItem::About(app_name.to_string(), Default::default()))
+      .add_native_item(MenuItem::Separator);
+  }
+
+  menu = menu
+    .add_native_item(MenuItem::Services)
+    .add_native_item(MenuItem::Separator)
+    .add_native_item(MenuItem::Hide)
+    .add_native_item(MenuItem::HideOthers)
+    .add_native_item(MenuItem::ShowAll)
+    .add_native_item(MenuItem::Separator)
+    .add_native_item(MenuItem::Quit);
+
+  Submenu::new(app_name.unwrap_or_default(), menu)
+}
+
+/// File submenu of the application menu.
+fn file_submenu(labels: &MenuLabels) -> Submenu {
+  let menu = TauriMenu::with_items([
+    Menu::new_window(&labels.new_window).into(),
+    Menu::open_file(&labels.open_file).into(),
+    Menu::open_folder(&labels.open_folder).into(),
+    Menu::close_file(&labels.close_file).into(),
+    Menu::close_folder(&labels.close_folder).into(),
+    Menu::close_window(&labels.close_window).into(),
+  ]);
+
+  // Quit is in the app submenu on macOS
+  #[cfg(not(target_os = "macos"))]
+  let menu = menu
+    .add_native_item(MenuItem::Separator)
+    .add_native_item(MenuItem::Quit);
+
+  Submenu::new(&labels.file, menu)
+}
+
+/// Menu item id of a slot of the window list.
+fn window_list_item_id(slot: usize) -> String {
+  format!("{}-{}", WINDOW_FOCUS_WINDOW_EVENT, slot)
+}
+
+/// Fill the window list of every window menu with the open windows.
+///
+/// `closing` is left out, the window may still be listed while it is destroyed.
+pub fn refresh_window_list<R: Runtime, M: Manager<R>>(manager: &M, closing: Option<&str>) -> Result<()> {
+  let entries = list_windows(manager)?
+    .into_iter()
+    .filter(|info| Some(info.label.as_str()) != closing)
+    .map(|info| {
+      let title = manager.get_window(&info.label)
+        .and_then(|window| window.title().ok())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| info.label.clone());
+
+      (info.label, title)
+    })
+    .collect::<Vec<(String, String)>>();
+
+  for window in manager.windows().into_values() {
+    let menu_handle = window.menu_handle();
+
+    for slot in 0..WINDOW_LIST_SIZE {
+      // Windows without the window list are skipped
+      let item = match menu_handle.try_get_item(&window_list_item_id(slot)) {
+        Some(item) => item,
+        None => break,
+      };
+
+      match entries.get(slot) {
+        Some((label, title)) => {
+          item.set_title(title)?;
+          item.set_enabled(true)?;
+          item.set_selected(label == window.label())?;
+        },
+        None => {
+          item.set_title("")?;
+          item.set_enabled(false)?;
+          item.set_selected(false)?;
+        },
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Handle the fullscreen and window list items for the window of the menu.
+///
+/// Returns whether the menu item was one of them.
+pub fn handle_window_menu_event<R: Runtime>(window: &Window<R>, menu_item_id: &str) -> Result<bool> {
+  if menu_item_id == WINDOW_TOGGLE_FULLSCREEN_EVENT {
+    window.set_fullscreen(!window.is_fullscreen()?)?;
+
+    return Ok(true);
+  }
+
+  let slot = match menu_item_id
+    .strip_prefix(WINDOW_FOCUS_WINDOW_EVENT)
+    .and_then(|slot| slot.strip_prefix('-'))
+    .and_then(|slot| slot.parse::<usize>().ok())
+  {
+    Some(slot) => slot,
+    None => return Ok(false),
+  };
+
+  let target = list_windows(window)?
+    .get(slot)
+    .and_then(|info| window.get_window(&info.label));
+
+  if let Some(target) = target {
+    if target.is_minimized()? {
+      target.unminimize()?;
+    }
+
+    target.set_focus()?;
+  }
+
+  Ok(true)
+}
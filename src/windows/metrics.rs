@@ -0,0 +1,136 @@
+//! Metrics.
+//!
+//! This module contains basic methods and types for the startup latency of windows.
+//! Window states record when a window was created, its page first loaded and its frontend declared readiness.
+
+use std::{result::Result as StdResult, time::Duration};
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{
+  window::{now, ReadyState},
+  WindowsStateCache
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Startup of a window, in milliseconds since it was created.
+#[derive(Clone, Debug, Serialize)]
+pub struct WindowStartupMetrics {
+  pub label: String,
+  /// Creation time in milliseconds since the unix epoch.
+  pub created_at: u64,
+  /// Time to the first page load, `None` while loading.
+  pub page_load: Option<u64>,
+  /// Time to the ready handshake, `None` until the frontend invokes `window_ready`.
+  pub ready: Option<u64>,
+}
+
+/// Distribution of a startup phase over windows, in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LatencyStats {
+  pub count: usize,
+  pub min: u64,
+  pub median: u64,
+  pub max: u64,
+}
+
+impl LatencyStats {
+  /// Stats of the samples, `None` without samples.
+  fn of(mut samples: Vec<u64>) -> Option<Self> {
+    samples.sort_unstable();
+
+    Some(LatencyStats {
+      count: samples.len(),
+      min: *samples.first()?,
+      median: samples[samples.len() / 2],
+      max: *samples.last()?,
+    })
+  }
+}
+
+/// Startup metrics of the windows with cached state.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StartupMetrics {
+  /// Windows by creation time, oldest first.
+  pub windows: Vec<WindowStartupMetrics>,
+  pub page_load: Option<LatencyStats>,
+  pub ready: Option<LatencyStats>,
+}
+
+/// Reset the startup of a new window.
+///
+/// Times of the last session are cleared and the frontend has to declare readiness again.
+pub fn record_window_created<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut state = cache.get_item(label)
+  .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+  state.ready_state = ReadyState::Navigating;
+  state.created_time = Some(now());
+  state.page_load_time = None;
+  state.ready_time = None;
+
+  cache.set_item(label, state)
+}
+
+/// Record the first page load of a window.
+///
+/// Windows without cached state, e.g. created by the app, are skipped.
+pub fn record_page_load<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  match cache.get_item(label) {
+    Some(mut state) if state.page_load_time.is_none() => {
+      state.page_load_time = Some(now());
+      cache.set_item(label, state)
+    },
+    _ => Ok(()),
+  }
+}
+
+/// Get startup metrics of windows created by the plugin.
+pub fn get_startup_metrics<R: Runtime, M: Manager<R>>(manager: &M) -> Result<StartupMetrics> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let since = |created_time: Duration, time: Option<Duration>| {
+    time.map(|time| time.saturating_sub(created_time).as_millis() as u64)
+  };
+
+  let mut windows = cache.state().opened_windows
+    .keys()
+    .filter_map(|label| cache.get_item(label).map(|state| (label, state)))
+    .filter_map(|(label, state)| {
+      let created_time = state.created_time?;
+
+      Some(WindowStartupMetrics {
+        label: label.clone(),
+        created_at: created_time.as_millis() as u64,
+        page_load: since(created_time, state.page_load_time),
+        ready: since(created_time, state.ready_time),
+      })
+    })
+    .collect::<Vec<WindowStartupMetrics>>();
+
+  windows.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.label.cmp(&b.label)));
+
+  Ok(StartupMetrics {
+    page_load: LatencyStats::of(windows.iter().filter_map(|window| window.page_load).collect()),
+    ready: LatencyStats::of(windows.iter().filter_map(|window| window.ready).collect()),
+    windows,
+  })
+}
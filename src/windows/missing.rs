@@ -0,0 +1,121 @@
+//! Missing.
+//!
+//! This module contains basic methods and types for files of windows deleted or moved outside the app.
+//! The windows tracking them are told with the file missing event and their recents are marked missing.
+
+use std::{
+  collections::BTreeMap,
+  path::PathBuf,
+  result::Result as StdResult,
+};
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_FILE_MISSING_EVENT;
+
+use super::WindowsStateCache;
+#[cfg(feature = "recents")]
+use super::WindowsRecentsCache;
+
+type Result<T> = StdResult<T, Error>;
+
+/// File of a window that is gone.
+#[derive(Clone, Debug, Serialize)]
+pub struct MissingFile {
+  pub file: PathBuf,
+  /// New path if the file was seen moving inside a watched folder.
+  pub moved_to: Option<PathBuf>,
+}
+
+/// Payload of the file missing event.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FileMissingPayload {
+  pub files: Vec<MissingFile>,
+}
+
+/// Tell windows their files are gone and mark the recents missing.
+///
+/// `removed` holds removed paths and where they moved, a removed folder covers the files inside it.
+/// Returns the number of missing files of open windows.
+pub fn report_missing_files<R: Runtime, M: Manager<R>>(manager: &M, removed: &[(PathBuf, Option<PathBuf>)]) -> Result<usize> {
+  if removed.is_empty() {
+    return Ok(0);
+  }
+
+  let mut missing_by_window: BTreeMap<String, Vec<MissingFile>> = BTreeMap::new();
+
+  {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    for label in manager.windows().keys() {
+      let state = match cache.get_item(label) {
+        Some(state) => state,
+        None => continue,
+      };
+
+      for file in &state.configuration.files_to_open_or_create {
+        let missing = removed.iter().find_map(|(path, moved_to)| {
+          let relative = file.strip_prefix(path).ok()?;
+
+          Some(MissingFile {
+            file: file.clone(),
+            moved_to: moved_to.as_ref().map(|to| to.join(relative)),
+          })
+        });
+
+        if let Some(missing) = missing {
+          missing_by_window.entry(label.clone()).or_default().push(missing);
+        }
+      }
+    }
+  }
+
+  #[cfg(feature = "recents")]
+  {
+    let paths = removed.iter().map(|(path, _moved_to)| path.clone()).collect::<Vec<PathBuf>>();
+
+    manager.state::<WindowsRecentsCache>().0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .mark_missing(&paths)?;
+  }
+
+  let mut count = 0;
+
+  for (label, files) in missing_by_window {
+    count += files.len();
+
+    if let Some(window) = manager.get_window(&label) {
+      window.emit(WINDOW_FILE_MISSING_EVENT, FileMissingPayload { files })?;
+    }
+  }
+
+  Ok(count)
+}
+
+/// Check the files of open windows against the filesystem.
+///
+/// Returns the number of missing files.
+pub fn validate_open_files<R: Runtime, M: Manager<R>>(manager: &M) -> Result<usize> {
+  let missing = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    manager.windows()
+      .keys()
+      .filter_map(|label| cache.get_item(label))
+      .flat_map(|state| state.configuration.files_to_open_or_create)
+      .filter(|file| !file.exists())
+      .map(|file| (file, None))
+      .collect::<Vec<(PathBuf, Option<PathBuf>)>>()
+  };
+
+  report_missing_files(manager, &missing)
+}
@@ -0,0 +1,100 @@
+//! Monitor.
+//!
+//! This module contains basic methods and types for describing monitors to the frontend.
+//! Work areas are computed with the same helpers used to place windows, so placement UIs match the plugin.
+
+use std::result::Result as StdResult;
+
+use serde::Serialize;
+
+use tauri::{Manager, Monitor, Runtime, Window};
+
+use crate::error::Error;
+
+use super::window::{get_window_monitor, get_working_area, WindowBounds};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Info about a monitor.
+#[derive(Clone, Debug, Serialize)]
+pub struct MonitorInfo {
+  /// Index of the monitor among the available monitors, as stored in window states.
+  pub index: u32,
+  pub name: Option<String>,
+  /// Full bounds in physical pixels.
+  pub bounds: WindowBounds,
+  /// Area available to windows in physical pixels.
+  pub work_area: WindowBounds,
+  pub scale_factor: f64,
+  pub primary: bool,
+}
+
+/// Get the info of a monitor.
+fn monitor_info(index: usize, monitor: &Monitor, primary: Option<&Monitor>) -> MonitorInfo {
+  let position = monitor.position();
+  let size = monitor.size();
+
+  MonitorInfo {
+    index: index as u32,
+    name: monitor.name().cloned(),
+    bounds: WindowBounds {
+      x: position.x,
+      y: position.y,
+      height: size.height,
+      width: size.width,
+    },
+    work_area: get_working_area(monitor),
+    scale_factor: monitor.scale_factor(),
+    primary: primary.map_or(false, |primary| is_same_monitor(monitor, primary)),
+  }
+}
+
+/// Check whether two handles refer to the same monitor.
+fn is_same_monitor(a: &Monitor, b: &Monitor) -> bool {
+  a.name() == b.name() && a.position() == b.position()
+}
+
+/// Get any window to query monitors with, monitors are the same for all windows.
+fn any_window<R: Runtime, M: Manager<R>>(manager: &M) -> Option<Window<R>> {
+  manager.windows().into_values().next()
+}
+
+/// Get the info of all available monitors.
+///
+/// Empty if no window is open, the runtime only exposes monitors through windows.
+pub fn get_monitors<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Vec<MonitorInfo>> {
+  let window = match any_window(manager) {
+    Some(window) => window,
+    None => return Ok(Vec::new()),
+  };
+
+  let primary = window.primary_monitor()?;
+
+  Ok(
+    window.available_monitors()?
+      .iter()
+      .enumerate()
+      .map(|(index, monitor)| monitor_info(index, monitor, primary.as_ref()))
+      .collect()
+  )
+}
+
+/// Get the info of the monitor a window is on.
+pub fn get_window_monitor_info<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<Option<MonitorInfo>> {
+  let window = manager.get_window(label)
+  .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+  let monitor = match get_window_monitor(&window)? {
+    Some(monitor) => monitor,
+    None => return Ok(None),
+  };
+
+  let index = window.available_monitors()?
+    .iter()
+    .position(|available| is_same_monitor(available, &monitor))
+    .unwrap_or(0);
+
+  let primary = window.primary_monitor()?;
+
+  Ok(Some(monitor_info(index, &monitor, primary.as_ref())))
+}
@@ -0,0 +1,52 @@
+//! Navigation.
+//!
+//! This module contains basic methods and types for windows navigating away from their page.
+//! State and backups are written before the page is left, so in-app navigation doesn't lose hot exit data.
+
+use std::result::Result as StdResult;
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime, Url};
+
+use crate::error::Error;
+use crate::event::WINDOW_BEFORE_NAVIGATE_EVENT;
+
+use super::{handle_window_bounds_changed, WindowsAPI, WindowsStateCache};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Payload of the before navigate event.
+#[derive(Clone, Debug, Serialize)]
+pub struct BeforeNavigatePayload {
+  /// Url the window navigates to.
+  pub url: String,
+}
+
+/// Capture the state of a window about to navigate.
+///
+/// The first navigation of a window is skipped, there is no page to leave yet.
+/// The page gets the before navigate event to flush its own state, it isn't waited on.
+pub fn handle_before_navigate<R: Runtime, M: Manager<R>>(manager: &M, label: &str, url: &Url) -> Result<()> {
+  let has_page = manager.state::<WindowsStateCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .get_item(label)
+  .map_or(false, |state| state.page_load_time.is_some());
+
+  if !has_page {
+    return Ok(());
+  }
+
+  let window = match manager.get_window(label) {
+    Some(window) => window,
+    None => return Ok(()),
+  };
+
+  window.emit(WINDOW_BEFORE_NAVIGATE_EVENT, BeforeNavigatePayload {
+    url: url.to_string(),
+  })?;
+
+  handle_window_bounds_changed(manager, label)?;
+
+  WindowsAPI::flush(manager)
+}
@@ -0,0 +1,114 @@
+//! Outcome.
+//!
+//! This module contains basic methods and types for open requests that partially succeed.
+//! A failed request is retried per openable and url, so callers learn exactly which paths failed.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::ErrorKind;
+
+use super::{log_error, OpenConfiguration, OpenedWindowInfo, WindowOpenable, WindowsAPI};
+
+/// Windows opened and paths that failed by an open request.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OpenOutcome {
+  /// Windows used to open the resources, each once.
+  pub opened: Vec<OpenedWindowInfo>,
+  /// Paths or urls that failed to open.
+  ///
+  /// An empty path stands for the request, when it failed without anything to split.
+  pub failed: Vec<(PathBuf, ErrorKind)>,
+}
+
+impl OpenOutcome {
+  /// Whether everything requested was opened.
+  pub fn is_complete(&self) -> bool {
+    self.failed.is_empty()
+  }
+
+  fn push_opened(&mut self, info: OpenedWindowInfo) {
+    if !self.opened.iter().any(|opened| opened.label == info.label) {
+      self.opened.push(info);
+    }
+  }
+}
+
+/// Get the path an openable stands for in an outcome.
+fn openable_path(openable: &WindowOpenable) -> PathBuf {
+  match (&openable.folder, &openable.file) {
+    (Some(folder), Some(file)) if file.is_relative() => folder.join(file),
+    (_, Some(file)) => file.clone(),
+    (Some(folder), None) => folder.clone(),
+    (None, None) => PathBuf::new(),
+  }
+}
+
+/// Split a request into one request per openable and url.
+fn split_configuration(configuration: &OpenConfiguration) -> Vec<(PathBuf, OpenConfiguration)> {
+  // Parts are new requests, the id names the whole one
+  let part = OpenConfiguration {
+    uris_to_open: None,
+    urls_to_open: Vec::new(),
+    request_id: None,
+    ..configuration.clone()
+  };
+
+  let openables = configuration.uris_to_open
+    .iter()
+    .flatten()
+    .map(|openable| (openable_path(openable), OpenConfiguration {
+      uris_to_open: Some(vec![openable.clone()]),
+      ..part.clone()
+    }));
+
+  let urls = configuration.urls_to_open
+    .iter()
+    .map(|url| (PathBuf::from(url.as_str()), OpenConfiguration {
+      urls_to_open: vec![url.clone()],
+      ..part.clone()
+    }));
+
+  openables.chain(urls).collect()
+}
+
+/// Open a request, reporting the paths that failed instead of failing as a whole.
+///
+/// Paths opened before the failure are found open again by the retry, their windows are focused.
+pub fn open_with_outcome<R: Runtime, M: Manager<R>>(manager: &M, configuration: OpenConfiguration) -> OpenOutcome {
+  let mut outcome = OpenOutcome::default();
+
+  let e = match WindowsAPI::open_window(manager, configuration.clone()) {
+    Ok(opened_window) => {
+      outcome.push_opened(opened_window.info());
+      return outcome;
+    },
+    Err(e) => e,
+  };
+
+  let parts = split_configuration(&configuration);
+
+  if parts.len() <= 1 {
+    log_error(&e);
+
+    let path = parts.into_iter().next().map(|(path, _part)| path).unwrap_or_default();
+    outcome.failed.push((path, e.kind()));
+
+    return outcome;
+  }
+
+  for (path, part) in parts {
+    match WindowsAPI::open_window(manager, part) {
+      Ok(opened_window) => outcome.push_opened(opened_window.info()),
+      Err(e) => {
+        log_error(&e);
+        outcome.failed.push((path, e.kind()));
+      },
+    }
+  }
+
+  outcome
+}
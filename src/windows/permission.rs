@@ -0,0 +1,128 @@
+//! Permission.
+//!
+//! This module contains basic methods and types for restricting the commands windows may invoke.
+//! Without configured permissions every window may invoke every command.
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  result::Result as StdResult,
+};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{OpenConfiguration, WindowsSettings};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Commands a window may invoke.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum WindowPermission {
+  /// Open files, alone or inside a folder.
+  OpenFiles,
+  OpenFolders,
+  /// Send messages to other windows.
+  Broadcast,
+  /// Change settings, trusted folders, layouts and stored state.
+  Settings,
+}
+
+impl WindowPermission {
+  pub(crate) fn name(&self) -> &'static str {
+    match self {
+      WindowPermission::OpenFiles => "OpenFiles",
+      WindowPermission::OpenFolders => "OpenFolders",
+      WindowPermission::Broadcast => "Broadcast",
+      WindowPermission::Settings => "Settings",
+    }
+  }
+}
+
+/// Permissions of windows.
+///
+/// Read from `tauri.conf.json > plugins > windows > permissions`, extended with [`TauriWindows::permissions`].
+///
+/// [`TauriWindows::permissions`]: crate::TauriWindows::permissions
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PermissionSettings {
+  /// Permissions by window label, a label ending with `*` matches labels starting with the rest.
+  ///
+  /// Permissions are enforced once any window is listed, windows that aren't listed have none.
+  pub windows: BTreeMap<String, BTreeSet<WindowPermission>>,
+}
+
+impl PermissionSettings {
+  /// Whether permissions are enforced.
+  pub fn is_enforced(&self) -> bool {
+    !self.windows.is_empty()
+  }
+
+  /// Get the permissions of a window, the exact label wins over the longest matching prefix.
+  pub fn permissions_of(&self, label: &str) -> Option<&BTreeSet<WindowPermission>> {
+    self.windows.get(label).or_else(|| {
+      self.windows
+        .iter()
+        .filter_map(|(pattern, permissions)| pattern.strip_suffix('*').map(|prefix| (prefix, permissions)))
+        .filter(|(prefix, _permissions)| label.starts_with(prefix))
+        .max_by_key(|(prefix, _permissions)| prefix.len())
+        .map(|(_prefix, permissions)| permissions)
+    })
+  }
+
+  /// Check if a window has the permission.
+  pub fn allows(&self, label: &str, permission: WindowPermission) -> bool {
+    !self.is_enforced() || self.permissions_of(label).map_or(false, |permissions| permissions.contains(&permission))
+  }
+}
+
+/// Check the window invoking a command has the permission.
+pub fn check_permission<R: Runtime, M: Manager<R>>(manager: &M, label: &str, permission: WindowPermission) -> Result<()> {
+  let allowed = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .permissions
+  .allows(label, permission);
+
+  match allowed {
+    true => Ok(()),
+    false => Err(Error::PermissionDenied(label.to_string(), permission.name().to_string())),
+  }
+}
+
+/// Check the window invoking a command may act on the window with the label.
+///
+/// A window may always act on itself, acting on other windows needs the permission.
+pub fn check_window_permission<R: Runtime, M: Manager<R>>(manager: &M, caller: &str, label: &str, permission: WindowPermission) -> Result<()> {
+  match caller == label {
+    true => Ok(()),
+    false => check_permission(manager, caller, permission),
+  }
+}
+
+/// Check the window invoking a command acts on itself, e.g. when answering a close request.
+pub fn check_own_window(caller: &str, label: &str) -> Result<()> {
+  match caller == label {
+    true => Ok(()),
+    false => Err(Error::NotOwnWindow(caller.to_string(), label.to_string())),
+  }
+}
+
+/// Check the window invoking an open request may open its files and folders.
+///
+/// Expanded folders open their files.
+pub fn check_open_permissions<R: Runtime, M: Manager<R>>(manager: &M, label: &str, configuration: &OpenConfiguration) -> Result<()> {
+  for openable in configuration.uris_to_open.iter().flatten() {
+    if openable.file.is_some() || (openable.folder.is_some() && configuration.expand_folders.is_some()) {
+      check_permission(manager, label, WindowPermission::OpenFiles)?;
+    }
+
+    if openable.folder.is_some() && configuration.expand_folders.is_none() {
+      check_permission(manager, label, WindowPermission::OpenFolders)?;
+    }
+  }
+
+  Ok(())
+}
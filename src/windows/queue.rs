@@ -0,0 +1,184 @@
+//! Queue.
+//!
+//! This module contains basic methods and types for serializing open requests.
+//! Requests wait their turn in a FIFO queue and run one at a time on a single worker task.
+
+use std::{
+  panic::{catch_unwind, AssertUnwindSafe},
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc
+  },
+};
+
+use tauri::{
+  async_runtime::{channel, spawn, spawn_blocking, Sender},
+  Manager,
+  Runtime
+};
+
+use crate::error::Error;
+
+type Result<T> = StdResult<T, Error>;
+
+type OpenJob = Box<dyn FnOnce() + Send>;
+
+/// Requests waiting before senders wait for room in the queue.
+const OPEN_QUEUE_CAPACITY: usize = 64;
+
+/// Managed state for the open queue and its worker.
+pub struct WindowsOpenQueue {
+  sender: Sender<OpenJob>,
+  /// Requests queued or running.
+  queued: Arc<AtomicUsize>,
+}
+
+impl WindowsOpenQueue {
+  /// Start the worker running queued requests in order.
+  pub fn start() -> Self {
+    let (sender, mut receiver) = channel::<OpenJob>(OPEN_QUEUE_CAPACITY);
+    let queued = Arc::new(AtomicUsize::new(0));
+    let worker_queued = queued.clone();
+
+    spawn(async move {
+      while let Some(job) = receiver.recv().await {
+        // Opening blocks on window creation and the filesystem
+        let _ = spawn_blocking(job).await;
+
+        worker_queued.fetch_sub(1, Ordering::SeqCst);
+      }
+    });
+
+    Self { sender, queued }
+  }
+}
+
+/// Run an open request once the requests queued before it are done.
+///
+/// Returns its result and the number of requests that were ahead of it.
+pub async fn queue_open<R, M, T, F>(manager: &M, open: F) -> Result<(T, usize)>
+where
+  R: Runtime,
+  M: Manager<R>,
+  T: Send + 'static,
+  F: FnOnce() -> Result<T> + Send + 'static,
+{
+  let (sender, queued) = {
+    let queue = manager.state::<WindowsOpenQueue>();
+
+    (queue.sender.clone(), queue.queued.clone())
+  };
+
+  let (reply_sender, mut reply_receiver) = channel(1);
+
+  let job: OpenJob = Box::new(move || {
+    // A panic only fails its own request
+    let res = catch_unwind(AssertUnwindSafe(open)).unwrap_or(Err(Error::OpenPanicked));
+
+    // Room for the single reply, the requester may be gone
+    let _ = reply_sender.try_send(res);
+  });
+
+  let position = queued.fetch_add(1, Ordering::SeqCst);
+
+  if sender.send(job).await.is_err() {
+    queued.fetch_sub(1, Ordering::SeqCst);
+    return Err(Error::OpenQueueClosed);
+  }
+
+  match reply_receiver.recv().await {
+    Some(res) => res.map(|opened| (opened, position)),
+    None => Err(Error::OpenQueueClosed),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::{atomic::Ordering, mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+  };
+
+  use tauri::{
+    async_runtime::{block_on, spawn},
+    test::{mock_app, MockRuntime},
+    App,
+    Manager
+  };
+
+  use crate::error::Error;
+
+  use super::{queue_open, WindowsOpenQueue};
+
+  fn app() -> App<MockRuntime> {
+    let app = mock_app();
+    app.manage(WindowsOpenQueue::start());
+    app
+  }
+
+  /// Wait until the number of requests queued or running is reached.
+  fn wait_for_queued(app: &App<MockRuntime>, queued: usize) {
+    while app.state::<WindowsOpenQueue>().queued.load(Ordering::SeqCst) != queued {
+      thread::sleep(Duration::from_millis(1));
+    }
+
+    // The request is counted right before it's sent
+    thread::sleep(Duration::from_millis(20));
+  }
+
+  #[test]
+  fn runs_requests_in_order() {
+    let app = app();
+
+    let (release, blocked) = mpsc::channel::<()>();
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    let mut blocked = Some(blocked);
+
+    let requests = (0..3)
+      .map(|index| {
+        let handle = app.handle();
+        let order = order.clone();
+        let blocked = blocked.take();
+
+        let request = spawn(async move {
+          queue_open(&handle, move || {
+            // The first request holds the worker until the others are queued
+            if let Some(blocked) = blocked {
+              blocked.recv().unwrap();
+            }
+
+            order.lock().unwrap().push(index);
+            Ok(index)
+          }).await
+        });
+
+        wait_for_queued(&app, index + 1);
+
+        request
+      })
+      .collect::<Vec<_>>();
+
+    release.send(()).unwrap();
+
+    let results = requests
+      .into_iter()
+      .map(|request| block_on(request).unwrap().unwrap())
+      .collect::<Vec<(usize, usize)>>();
+
+    assert_eq!(results, vec![(0, 0), (1, 1), (2, 2)]);
+    assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+  }
+
+  #[test]
+  fn panicking_request_fails_alone() {
+    let app = app();
+
+    let panicked = block_on(queue_open(&app, || -> Result<(), Error> { panic!("open failed") }));
+    assert!(matches!(panicked, Err(Error::OpenPanicked)));
+
+    assert_eq!(block_on(queue_open(&app, || Ok(1))).unwrap().0, 1);
+  }
+}
@@ -0,0 +1,296 @@
+//! Quit.
+//!
+//! This module contains basic methods and types for coordinating app exit across windows.
+//! Windows acknowledge or veto the exit, caches are flushed before the process exits.
+
+use std::{
+  collections::HashSet,
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    RwLock
+  },
+  thread,
+  time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::{WINDOW_BEFORE_QUIT_EVENT, WINDOW_QUIT_CANCELLED_EVENT};
+
+use super::{clear_running_sentinel, emit_to_subscribers, log_error, WindowsAPI, WindowsSettings, SPLASH_WINDOW_LABEL};
+
+type Result<T> = StdResult<T, Error>;
+
+static QUIT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Graceful exit settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > graceful_exit`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GracefulExitSettings {
+  /// Whether windows are asked before the app exits.
+  pub enabled: bool,
+  /// Time windows have to answer, the app exits when they don't.
+  pub timeout_ms: u64,
+}
+
+impl Default for GracefulExitSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      timeout_ms: 5000,
+    }
+  }
+}
+
+/// Payload of the before quit event.
+#[derive(Clone, Debug, Serialize)]
+pub struct BeforeQuitPayload {
+  pub id: u64,
+}
+
+/// Exit sequence cached during runtime.
+#[derive(Default)]
+pub struct InnerWindowsQuitState {
+  /// Id of the running exit sequence.
+  id: Option<u64>,
+  /// Windows that did not answer yet.
+  pending: HashSet<String>,
+}
+
+/// Managed state for the exit sequence.
+#[derive(Default)]
+pub struct WindowsQuitState(pub RwLock<InnerWindowsQuitState>);
+
+/// Check if exit must wait for the windows.
+pub fn is_graceful_exit_enabled<R: Runtime, M: Manager<R>>(manager: &M) -> bool {
+  match manager.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.graceful_exit.enabled,
+    Err(_e) => false,
+  }
+}
+
+/// Ask all windows to get ready for exit.
+///
+/// Exits right away when there are no windows, otherwise once every window
+/// acknowledged or the timeout passed.
+pub fn request_quit<R: Runtime, M: Manager<R>>(manager: &M) -> Result<()> {
+  let timeout = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .graceful_exit
+  .timeout_ms;
+
+  let windows_quit_state = manager.state::<WindowsQuitState>();
+
+  let mut state = windows_quit_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  // Already waiting for the windows
+  if state.id.is_some() {
+    return Ok(());
+  }
+
+  let windows = manager.windows();
+  let id = QUIT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+
+  state.id = Some(id);
+  state.pending = windows
+    .keys()
+    .filter(|label| label.as_str() != SPLASH_WINDOW_LABEL)
+    .cloned()
+    .collect();
+
+  let is_done = state.pending.is_empty();
+
+  drop(state);
+
+  let handle = manager.app_handle();
+
+  if is_done {
+    finish_quit(&handle);
+    return Ok(());
+  }
+
+  for label in windows.keys().filter(|label| label.as_str() != SPLASH_WINDOW_LABEL) {
+    if let Some(window) = windows.get(label) {
+      window.emit(WINDOW_BEFORE_QUIT_EVENT, BeforeQuitPayload { id })?;
+    }
+  }
+
+  thread::spawn(move || {
+    thread::sleep(Duration::from_millis(timeout));
+
+    let timed_out = match handle.state::<WindowsQuitState>().0.read() {
+      Ok(state) => state.id == Some(id),
+      Err(_e) => false,
+    };
+
+    if timed_out {
+      finish_quit(&handle);
+    }
+  });
+
+  Ok(())
+}
+
+/// Answer a `windows://before-quit` event.
+///
+/// A veto cancels the exit for all windows.
+pub fn acknowledge_quit<R: Runtime, M: Manager<R>>(manager: &M, label: &str, allow: bool) -> Result<()> {
+  let windows_quit_state = manager.state::<WindowsQuitState>();
+
+  let mut state = windows_quit_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  // Nothing to answer
+  if state.id.is_none() {
+    return Ok(());
+  }
+
+  if !allow {
+    let id = state.id.take();
+    state.pending.clear();
+
+    drop(state);
+
+    emit_to_subscribers(manager, WINDOW_QUIT_CANCELLED_EVENT, BeforeQuitPayload { id: id.unwrap_or_default() }, &[])?;
+
+    return Ok(());
+  }
+
+  state.pending.remove(label);
+
+  let is_done = state.pending.is_empty();
+
+  drop(state);
+
+  if is_done {
+    finish_quit(&manager.app_handle());
+  }
+
+  Ok(())
+}
+
+/// Forget a destroyed window so it doesn't hold up the exit.
+pub fn remove_quit_window<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let windows_quit_state = manager.state::<WindowsQuitState>();
+
+  let mut state = windows_quit_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  if state.id.is_none() || !state.pending.remove(label) {
+    return Ok(());
+  }
+
+  let is_done = state.pending.is_empty();
+
+  drop(state);
+
+  if is_done {
+    finish_quit(&manager.app_handle());
+  }
+
+  Ok(())
+}
+
+/// Flush all caches and exit.
+fn finish_quit<R: Runtime>(handle: &AppHandle<R>) {
+  if let Err(e) = WindowsAPI::flush(handle) {
+    log_error(&e);
+  }
+
+  clear_running_sentinel(handle);
+
+  handle.exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+  use tauri::{
+    test::{mock_builder, mock_context, noop_assets, MockRuntime},
+    window::WindowBuilder,
+    App,
+    Manager,
+    WindowUrl
+  };
+
+  use crate::TauriWindows;
+
+  use super::{acknowledge_quit, request_quit, GracefulExitSettings, WindowsQuitState, WindowsSettings};
+
+  // Finishing the sequence exits the process, so these only cover the paths that keep the app running.
+  // The timeout is long enough to never pass during a test.
+  fn app() -> App<MockRuntime> {
+    let app = mock_builder()
+      .plugin(TauriWindows::default().in_memory())
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    app.state::<WindowsSettings>().0.write().unwrap().graceful_exit = GracefulExitSettings {
+      enabled: true,
+      timeout_ms: 60_000,
+    };
+
+    WindowBuilder::new(&app, "editor", WindowUrl::default()).build().unwrap();
+
+    app
+  }
+
+  fn pending(app: &App<MockRuntime>) -> (Option<u64>, Vec<String>) {
+    let state = app.state::<WindowsQuitState>();
+    let state = state.0.read().unwrap();
+
+    let mut pending = state.pending.iter().cloned().collect::<Vec<String>>();
+    pending.sort();
+
+    (state.id, pending)
+  }
+
+  #[test]
+  fn waits_for_every_window_to_acknowledge() {
+    let app = app();
+
+    request_quit(&app).unwrap();
+
+    let (id, labels) = pending(&app);
+    assert!(id.is_some());
+    assert_eq!(labels, vec!["editor".to_string(), "main".to_string()]);
+
+    acknowledge_quit(&app, "main", true).unwrap();
+    assert_eq!(pending(&app), (id, vec!["editor".to_string()]));
+
+    // Requesting again while waiting keeps the running sequence
+    request_quit(&app).unwrap();
+    assert_eq!(pending(&app), (id, vec!["editor".to_string()]));
+
+    acknowledge_quit(&app, "editor", false).unwrap();
+  }
+
+  #[test]
+  fn veto_cancels_quit_for_all_windows() {
+    let app = app();
+
+    request_quit(&app).unwrap();
+    let (first_id, _labels) = pending(&app);
+
+    acknowledge_quit(&app, "editor", false).unwrap();
+    assert_eq!(pending(&app), (None, Vec::new()));
+
+    // Late answers to the cancelled sequence are ignored
+    acknowledge_quit(&app, "main", true).unwrap();
+    assert_eq!(pending(&app), (None, Vec::new()));
+
+    request_quit(&app).unwrap();
+
+    let (second_id, labels) = pending(&app);
+    assert!(second_id.is_some() && second_id != first_id);
+    assert_eq!(labels.len(), 2);
+
+    acknowledge_quit(&app, "main", false).unwrap();
+  }
+}
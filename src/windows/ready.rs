@@ -0,0 +1,101 @@
+//! Ready.
+//!
+//! This module contains basic methods and types for the ready handshake of window frontends.
+//! With the `ready_handshake` setting events to a window are held until its frontend invokes `window_ready`.
+
+use std::{collections::HashMap, result::Result as StdResult, sync::RwLock};
+
+use serde::Serialize;
+use serde_json::{to_value, Value as JsonValue};
+
+use tauri::{Manager, Runtime, Window};
+
+use crate::error::Error;
+use crate::event::WINDOW_READY_EVENT;
+
+use super::{
+  emit_to_subscribers,
+  replay_sticky_events,
+  window::{now, ReadyState, WindowTrait},
+  WindowsSettings,
+  WindowsStateCache
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Payload of the ready event.
+#[derive(Clone, Serialize)]
+pub struct WindowReadyPayload {
+  pub label: String,
+}
+
+/// Managed state for events held until windows are ready, in emit order.
+#[derive(Default)]
+pub struct WindowsReadyQueue(pub RwLock<HashMap<String, Vec<(String, JsonValue)>>>);
+
+/// Send an event to a window, or hold it until the window is ready.
+pub fn emit_when_ready<R: Runtime, S: Serialize + Clone>(window: &Window<R>, event: &str, payload: S) -> Result<()> {
+  let handshake = window.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .ready_handshake;
+
+  if !handshake || window.is_ready() {
+    window.emit(event, payload)?;
+    return Ok(());
+  }
+
+  window.state::<WindowsReadyQueue>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .entry(window.label().to_string())
+  .or_default()
+  .push((event.to_string(), to_value(payload)?));
+
+  Ok(())
+}
+
+/// Mark a window ready, send the events held for it and let listeners know.
+pub fn handle_window_ready<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let mut state = cache.get_item(label)
+    .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+    // Reloads declare readiness again, the first handshake is kept
+    if state.ready_time.is_none() {
+      state.ready_time = Some(now());
+      cache.set_item(label, state)?;
+    }
+
+    cache.set_ready_state(label, ReadyState::Ready)?;
+  }
+
+  let queued = manager.state::<WindowsReadyQueue>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .remove(label)
+  .unwrap_or_default();
+
+  if let Some(window) = manager.get_window(label) {
+    for (event, payload) in queued {
+      window.emit(&event, payload)?;
+    }
+  }
+
+  replay_sticky_events(manager, label)?;
+
+  emit_to_subscribers(manager, WINDOW_READY_EVENT, WindowReadyPayload {
+    label: label.to_string(),
+  }, &[])
+}
+
+/// Drop events held for a destroyed window.
+pub fn clear_ready_queue<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  manager.state::<WindowsReadyQueue>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .remove(label);
+
+  Ok(())
+}
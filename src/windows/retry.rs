@@ -0,0 +1,114 @@
+//! Retry.
+//!
+//! This module contains basic methods and types for retrying window creation.
+//! Webview creation can fail transiently, e.g. while WebView2 is busy, so it's tried again with backoff.
+
+use std::{result::Result as StdResult, thread, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Error as TauriError, Manager, Runtime, Window};
+
+use crate::error::Error;
+
+use super::{adopt::WindowsCreating, log_error, WindowsSettings};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Window creation retry settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > create_retry`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CreateRetrySettings {
+  /// Times window creation is tried, defaults to `1`, which doesn't retry.
+  pub attempts: u32,
+  /// Wait before the first retry, doubled on each retry.
+  pub backoff_ms: u64,
+  /// Longest wait between retries.
+  pub max_backoff_ms: u64,
+}
+
+impl Default for CreateRetrySettings {
+  fn default() -> Self {
+    Self {
+      attempts: 1,
+      backoff_ms: 100,
+      max_backoff_ms: 2000,
+    }
+  }
+}
+
+impl CreateRetrySettings {
+  /// Get the wait before a retry, `retry` starts at `0`.
+  pub fn backoff(&self, retry: u32) -> Duration {
+    let backoff_ms = self.backoff_ms.saturating_mul(2u64.saturating_pow(retry));
+
+    Duration::from_millis(backoff_ms.min(self.max_backoff_ms))
+  }
+}
+
+/// Check whether creating the window again could succeed.
+///
+/// Only runtime and webview creation failures are retried, other errors fail the same way again.
+fn is_transient(error: &TauriError) -> bool {
+  matches!(error, TauriError::Runtime(_) | TauriError::CreateWindow)
+}
+
+/// Create a window, retrying transient failures with backoff per the settings.
+///
+/// `build` is called once per attempt. Fails with the error of the last attempt.
+/// The label is marked as being created meanwhile, so the window isn't adopted.
+pub fn create_with_retry<R: Runtime, M: Manager<R>, F: FnMut() -> StdResult<Window<R>, TauriError>>(
+  manager: &M,
+  label: &str,
+  build: F,
+) -> Result<Window<R>> {
+  let windows_creating = manager.state::<WindowsCreating>();
+
+  windows_creating.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .insert(label.to_string());
+
+  let res = build_with_retry(manager, label, build);
+
+  windows_creating.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .remove(label);
+
+  res
+}
+
+fn build_with_retry<R: Runtime, M: Manager<R>, F: FnMut() -> StdResult<Window<R>, TauriError>>(
+  manager: &M,
+  label: &str,
+  mut build: F,
+) -> Result<Window<R>> {
+  let settings = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .create_retry
+  .clone();
+
+  let attempts = settings.attempts.max(1);
+  let mut attempt = 1;
+
+  loop {
+    let e = match build() {
+      Ok(window) => return Ok(window),
+      Err(e) => e,
+    };
+
+    if attempt >= attempts || !is_transient(&e) {
+      return Err(Error::WindowCreation {
+        label: label.to_string(),
+        attempts: attempt,
+        source: e,
+      });
+    }
+
+    log_error(&e);
+
+    thread::sleep(settings.backoff(attempt - 1));
+    attempt += 1;
+  }
+}
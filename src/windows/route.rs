@@ -0,0 +1,113 @@
+//! Route.
+//!
+//! This module contains basic methods and types for windows showing a route of a single page app.
+//! Routes are loaded as the hash of `index.html`, recorded in the window state and restored with the session.
+
+use std::result::Result as StdResult;
+
+use serde_json::to_string;
+
+use tauri::{Manager, Runtime, Window, WindowUrl};
+
+use crate::error::Error;
+
+use super::{
+  activate_window,
+  open_in_webview_window,
+  open_singleton_window,
+  OpenPath,
+  OpenedWindow,
+  WindowOptions,
+  WindowState,
+  WindowsStateCache,
+  WindowTrait
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Get the app url of a route, e.g. `index.html#/settings/general` for `settings/general`.
+pub fn route_url(route: &str) -> WindowUrl {
+  WindowUrl::App(format!("index.html#/{}", route.trim_start_matches('/')).into())
+}
+
+/// Open a window on a route.
+///
+/// Windows with the singleton key of the options, or else on the folder of the options, are reused and navigated.
+pub fn open_route<R: Runtime, M: Manager<R>>(manager: &M, route: &str, options: WindowOptions) -> Result<OpenedWindow<R>> {
+  let options = WindowOptions {
+    url: Some(route_url(route)),
+    app_route: Some(route.to_string()),
+    ..options
+  };
+
+  if let Some(key) = options.singleton_key.clone() {
+    return open_singleton_window(manager, &key, WindowOptions {
+      update_singleton: true,
+      ..options
+    });
+  }
+
+  let window_on_folder = match &options.folder {
+    Some(folder) => find_window(manager, |state| state.folder.as_ref() == Some(folder))?,
+    None => None,
+  };
+
+  match window_on_folder {
+    Some(window) => {
+      navigate_to_route(manager, &window, route)?;
+      activate_window(&window, options.activation)?;
+
+      Ok(OpenedWindow::new(window, OpenPath::ExistingWindow))
+    },
+    None => open_in_webview_window(manager, WindowOptions {
+      force_new_window: true,
+      ..options
+    }),
+  }
+}
+
+/// Load a route in an open window and record it.
+pub fn navigate_to_route<R: Runtime, M: Manager<R>>(manager: &M, window: &Window<R>, route: &str) -> Result<()> {
+  window.load(&route_url(route))?;
+
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut state = cache.get_item(window.label())
+  .ok_or_else(|| Error::WindowStateWithLabelNotFound(window.label().to_string()))?;
+
+  state.configuration.app_route = Some(route.to_string());
+
+  cache.set_item(window.label(), state)
+}
+
+/// Load another url in a window, without updating its state.
+///
+/// Use [`WindowTrait::load`] for managed windows.
+pub fn navigate<R: Runtime>(window: &Window<R>, url: &WindowUrl) -> Result<()> {
+  let location = match url {
+    WindowUrl::External(url) => url.to_string(),
+    WindowUrl::App(path) => path.to_string_lossy().to_string(),
+    _ => return Ok(()),
+  };
+
+  window.eval(&format!("window.location.replace({})", to_string(&location)?))?;
+
+  Ok(())
+}
+
+/// Get the first open window whose state matches.
+fn find_window<R: Runtime, M: Manager<R>, F: Fn(&WindowState) -> bool>(manager: &M, matches: F) -> Result<Option<Window<R>>> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  Ok(
+    manager.windows()
+      .into_values()
+      .find(|window| cache.get_item(window.label()).map_or(false, |state| matches(&state)))
+  )
+}
@@ -0,0 +1,98 @@
+//! Routing.
+//!
+//! This module contains basic methods and types for routing opened files by glob pattern.
+//! Rules are read from the `routing` setting, the first rule matching a file decides its treatment.
+
+use std::{collections::BTreeMap, path::{Path, PathBuf}, result::Result as StdResult};
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{emit_to_subscribers, PathToOpen, WindowsSettings};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Treatment of files matching a routing rule.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum OpenRoute {
+  /// Open in a new window, regardless of the request and settings.
+  OpenInNewWindow,
+  /// Open in the last active window, or a new one if no window is open.
+  OpenInExisting,
+  /// Leave out of the request and emit the event with the files instead, e.g. `windows://open_image`.
+  EmitCustomEvent(String),
+  /// Leave out of the request.
+  Ignore,
+}
+
+/// Route of files matching a glob pattern.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoutingRule {
+  /// Glob pattern matched against the whole path, e.g. `*.png` or `**/assets/*`.
+  pub pattern: String,
+  pub route: OpenRoute,
+}
+
+/// Payload of events emitted by [`OpenRoute::EmitCustomEvent`].
+#[derive(Clone, Serialize)]
+pub struct RoutedFilesPayload {
+  pub files: Vec<PathBuf>,
+}
+
+/// Get the route of a file, `None` if no rule matches.
+///
+/// Rules with invalid patterns never match.
+pub fn route_of<'a>(rules: &'a [RoutingRule], file: &Path) -> Option<&'a OpenRoute> {
+  rules
+    .iter()
+    .find(|rule| Pattern::new(&rule.pattern).map_or(false, |pattern| pattern.matches_path(file)))
+    .map(|rule| &rule.route)
+}
+
+/// Apply the routing rules to the files of a request.
+///
+/// Files routed to an event are emitted, once per event, and left out along with ignored files.
+/// Files routed to a window keep their route for opening.
+pub fn route_paths<R: Runtime, M: Manager<R>>(manager: &M, paths_to_open: &[PathToOpen]) -> Result<Vec<PathToOpen>> {
+  let rules = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .routing
+  .clone();
+
+  if rules.is_empty() {
+    return Ok(paths_to_open.to_vec());
+  }
+
+  let mut events: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+  let mut routed_paths = Vec::new();
+
+  for path_to_open in paths_to_open {
+    let route = match &path_to_open.file {
+      Some(file) => route_of(&rules, file),
+      None => None,
+    };
+
+    match route {
+      Some(OpenRoute::Ignore) => {},
+      Some(OpenRoute::EmitCustomEvent(event)) => {
+        events.entry(event.clone()).or_default().extend(path_to_open.file.clone());
+      },
+      route => {
+        routed_paths.push(PathToOpen {
+          route: route.cloned(),
+          ..path_to_open.clone()
+        });
+      },
+    }
+  }
+
+  for (event, files) in events {
+    emit_to_subscribers(manager, &event, RoutedFilesPayload { files }, &[])?;
+  }
+
+  Ok(routed_paths)
+}
@@ -0,0 +1,125 @@
+//! Screenshot.
+//!
+//! This module contains basic methods and types for capturing the contents of windows to PNG.
+//! Webviews can't be captured natively, the frontend renders itself when asked with the screenshot request event.
+
+use std::{
+  env::temp_dir,
+  fs::{create_dir_all, write},
+  path::PathBuf,
+  result::Result as StdResult,
+  time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_SCREENSHOT_REQUEST_EVENT;
+
+use super::{send_and_wait, BroadcastReply, BroadcastStatus};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Time the frontend has to render itself.
+pub const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SCREENSHOTS_FOLDER: &str = "tauri-plugin-windows-screenshots";
+
+/// PNG capture of a window.
+#[derive(Clone, Debug, Serialize)]
+pub struct Screenshot {
+  pub label: String,
+  pub width: u32,
+  pub height: u32,
+  /// Base64 PNG, unless written to a file.
+  pub data: Option<String>,
+  /// Temporary PNG file, if asked for one.
+  pub path: Option<PathBuf>,
+}
+
+/// Decode a base64 PNG, returns its bytes and size.
+pub fn decode_png(data: &str) -> StdResult<(Vec<u8>, u32, u32), String> {
+  let bytes = STANDARD.decode(data).map_err(|e| e.to_string())?;
+
+  let (width, height) = {
+    let reader = png::Decoder::new(bytes.as_slice())
+      .read_info()
+      .map_err(|e| e.to_string())?;
+
+    (reader.info().width, reader.info().height)
+  };
+
+  Ok((bytes, width, height))
+}
+
+/// Capture the contents of a window.
+///
+/// The frontend answers the screenshot request with `acknowledge_broadcast` and a base64 PNG, a data url is accepted.
+/// With `to_file` the PNG is written to the temp folder instead of returned.
+pub fn screenshot_window<R: Runtime, M: Manager<R>>(manager: &M, label: &str, to_file: bool) -> Result<Screenshot> {
+  let failed = |reason: &str| Error::Screenshot(label.to_string(), reason.to_string());
+
+  if manager.get_window(label).is_none() {
+    return Err(Error::WindowNotFound(label.to_string()));
+  }
+
+  let other_labels = manager.windows()
+    .into_keys()
+    .filter(|other_label| other_label != label)
+    .collect::<Vec<String>>();
+
+  let reply = send_and_wait(manager, WINDOW_SCREENSHOT_REQUEST_EVENT, JsonValue::Null, &other_labels, SCREENSHOT_TIMEOUT)?
+    .into_iter()
+    .next()
+    .ok_or_else(|| failed("the window isn't subscribed to screenshot requests"))?;
+
+  screenshot_from_reply(reply, to_file)
+}
+
+/// Get the screenshot a window answered the screenshot request with.
+pub fn screenshot_from_reply(reply: BroadcastReply, to_file: bool) -> Result<Screenshot> {
+  let label = reply.label.as_str();
+
+  let failed = |reason: &str| Error::Screenshot(label.to_string(), reason.to_string());
+
+  let data = match (reply.status, reply.result, reply.error) {
+    (BroadcastStatus::Acknowledged, Some(JsonValue::String(data)), _) => data,
+    (BroadcastStatus::Acknowledged, _, _) => return Err(failed("the window answered without a PNG")),
+    (_, _, Some(error)) => return Err(failed(&error)),
+    (status, _, None) => return Err(failed(&format!("{:?}", status))),
+  };
+
+  let data = data.trim_start_matches("data:image/png;base64,");
+
+  let (bytes, width, height) = decode_png(data).map_err(|e| failed(&e))?;
+
+  if !to_file {
+    return Ok(Screenshot {
+      label: label.to_string(),
+      width,
+      height,
+      data: Some(data.to_string()),
+      path: None,
+    });
+  }
+
+  let folder = temp_dir().join(SCREENSHOTS_FOLDER);
+
+  create_dir_all(&folder)?;
+
+  let path = folder.join(format!("{}-{}.png", label, uuid::Uuid::new_v4().simple()));
+
+  write(&path, bytes)?;
+
+  Ok(Screenshot {
+    label: label.to_string(),
+    width,
+    height,
+    data: None,
+    path: Some(path),
+  })
+}
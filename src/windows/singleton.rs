@@ -0,0 +1,108 @@
+//! Singleton.
+//!
+//! This module contains basic methods and types for windows open at most once per key, e.g. settings or about.
+//! Opening a window with the key of an open window activates that window instead of creating another.
+
+use std::result::Result as StdResult;
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use tauri::{Manager, Runtime, Window};
+
+use crate::error::Error;
+use crate::event::WINDOW_SINGLETON_EVENT;
+
+use super::{
+  activate_window,
+  emit_when_ready,
+  navigate_to_route,
+  open_in_webview_window,
+  OpenPath,
+  OpenedWindow,
+  WindowOptions,
+  WindowsStateCache,
+  WindowTrait
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Payload of the singleton event.
+#[derive(Clone, Debug, Serialize)]
+pub struct SingletonPayload {
+  pub key: String,
+  pub payload: Option<JsonValue>,
+  /// Whether the window was already open.
+  pub reopened: bool,
+}
+
+/// Get the open window with a singleton key.
+pub fn find_singleton_window<R: Runtime, M: Manager<R>>(manager: &M, key: &str) -> Result<Option<Window<R>>> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  Ok(
+    manager.windows()
+      .into_values()
+      .find(|window| {
+        cache.get_item(window.label())
+          .map_or(false, |state| state.singleton_key.as_deref() == Some(key))
+      })
+  )
+}
+
+/// Open the window of a singleton key, or activate it if it's open.
+///
+/// With `update_singleton` an open window is navigated to `url`, or to the route of the options.
+/// The window gets `singleton_payload` with the singleton event either way.
+pub fn open_singleton_window<R: Runtime, M: Manager<R>>(manager: &M, key: &str, options: WindowOptions) -> Result<OpenedWindow<R>> {
+  let payload = options.singleton_payload.clone();
+
+  let (opened_window, reopened) = match find_singleton_window(manager, key)? {
+    Some(window) => {
+      if options.update_singleton {
+        match (&options.app_route, &options.url) {
+          (Some(route), _) => navigate_to_route(manager, &window, route)?,
+          (None, Some(url)) => window.load(url)?,
+          (None, None) => {},
+        }
+      }
+
+      activate_window(&window, options.activation)?;
+
+      (OpenedWindow::new(window, OpenPath::ExistingWindow), true)
+    },
+    None => {
+      let opened_window = open_in_webview_window(manager, WindowOptions {
+        force_new_window: true,
+        ..options
+      })?;
+
+      let label = opened_window.window.label();
+
+      let windows_state_cache = manager.state::<WindowsStateCache>();
+
+      let mut cache = windows_state_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      let mut state = cache.get_item(label)
+      .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+      state.singleton_key = Some(key.to_string());
+
+      cache.set_item(label, state)?;
+
+      (opened_window, false)
+    },
+  };
+
+  emit_when_ready(&opened_window.window, WINDOW_SINGLETON_EVENT, SingletonPayload {
+    key: key.to_string(),
+    payload,
+    reopened,
+  })?;
+
+  Ok(opened_window)
+}
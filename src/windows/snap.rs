@@ -0,0 +1,177 @@
+//! Snap.
+//!
+//! This module contains basic methods and types for snapping and tiling windows.
+//! Regions are computed against the work area of the window monitor.
+
+use std::result::Result as StdResult;
+
+use serde::Deserialize;
+
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+use crate::error::Error;
+
+use super::window::{get_window_monitor, get_working_area, WindowBounds, WindowBoundsTrait};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Region of the work area to snap a window to.
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+pub enum SnapRegion {
+  LeftHalf,
+  RightHalf,
+  TopHalf,
+  BottomHalf,
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  BottomRight,
+  /// Rectangle in fractions of the work area, e.g. `x: 0.5, width: 0.5` for the right half.
+  Fraction {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+  },
+}
+
+/// Arrangement of tiled windows.
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+pub enum TileLayout {
+  /// Closest to square grid.
+  Grid,
+  /// Side by side.
+  Columns,
+  /// Stacked.
+  Rows,
+  /// Grid with a fixed number of columns.
+  Custom { columns: u32 },
+}
+
+impl SnapRegion {
+  /// Get the region as fractions of the work area.
+  fn fractions(&self) -> (f64, f64, f64, f64) {
+    match *self {
+      SnapRegion::LeftHalf => (0.0, 0.0, 0.5, 1.0),
+      SnapRegion::RightHalf => (0.5, 0.0, 0.5, 1.0),
+      SnapRegion::TopHalf => (0.0, 0.0, 1.0, 0.5),
+      SnapRegion::BottomHalf => (0.0, 0.5, 1.0, 0.5),
+      SnapRegion::TopLeft => (0.0, 0.0, 0.5, 0.5),
+      SnapRegion::TopRight => (0.5, 0.0, 0.5, 0.5),
+      SnapRegion::BottomLeft => (0.0, 0.5, 0.5, 0.5),
+      SnapRegion::BottomRight => (0.5, 0.5, 0.5, 0.5),
+      SnapRegion::Fraction { x, y, width, height } => (
+        x.max(0.0).min(1.0),
+        y.max(0.0).min(1.0),
+        width.max(0.0).min(1.0 - x.max(0.0).min(1.0)),
+        height.max(0.0).min(1.0 - y.max(0.0).min(1.0)),
+      ),
+    }
+  }
+
+  /// Get the bounds of the region in a work area.
+  pub fn bounds(&self, area: &WindowBounds) -> WindowBounds {
+    let (x, y, width, height) = self.fractions();
+
+    WindowBounds {
+      x: area.x + (area.width as f64 * x).round() as i32,
+      y: area.y + (area.height as f64 * y).round() as i32,
+      height: (area.height as f64 * height).round() as u32,
+      width: (area.width as f64 * width).round() as u32,
+    }
+  }
+}
+
+impl TileLayout {
+  /// Get the number of columns and rows for a number of windows.
+  fn grid(&self, count: u32) -> (u32, u32) {
+    let columns = match *self {
+      TileLayout::Grid => (count as f64).sqrt().ceil() as u32,
+      TileLayout::Columns => count,
+      TileLayout::Rows => 1,
+      TileLayout::Custom { columns } => columns,
+    }
+    .max(1);
+
+    (columns, (count + columns - 1) / columns)
+  }
+
+  /// Get the region of each window in the layout.
+  pub fn regions(&self, count: u32) -> Vec<SnapRegion> {
+    let (columns, rows) = self.grid(count);
+
+    (0..count)
+      .map(|index| SnapRegion::Fraction {
+        x: (index % columns) as f64 / columns as f64,
+        y: (index / columns) as f64 / rows as f64,
+        width: 1.0 / columns as f64,
+        height: 1.0 / rows as f64,
+      })
+      .collect()
+  }
+}
+
+/// Move and resize a window so its outer bounds match.
+pub fn set_window_bounds<R: Runtime>(window: &Window<R>, bounds: &WindowBounds) -> Result<()> {
+  if window.is_fullscreen()? {
+    window.set_fullscreen(false)?;
+  }
+
+  if window.is_maximized()? {
+    window.unmaximize()?;
+  }
+
+  // Sizes are set on the inner size, leave room for the decorations
+  let outer_size = window.outer_size()?;
+  let inner_size = window.inner_size()?;
+  let decorations_width = outer_size.width.saturating_sub(inner_size.width);
+  let decorations_height = outer_size.height.saturating_sub(inner_size.height);
+
+  window.set_position(PhysicalPosition::new(bounds.x, bounds.y))?;
+  window.set_size(PhysicalSize::new(
+    bounds.width.saturating_sub(decorations_width),
+    bounds.height.saturating_sub(decorations_height),
+  ))?;
+
+  Ok(())
+}
+
+/// Get the work area of the monitor a window is on.
+fn get_window_work_area<R: Runtime>(window: &Window<R>) -> Result<WindowBounds> {
+  match get_window_monitor(window)? {
+    Some(monitor) => Ok(get_working_area(&monitor)),
+    None => Ok(window.get_bounds()),
+  }
+}
+
+/// Snap a window to a region of its monitor.
+pub fn snap_window<R: Runtime, M: Manager<R>>(manager: &M, label: &str, region: SnapRegion) -> Result<()> {
+  let window = manager.get_window(label)
+  .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+  let area = get_window_work_area(&window)?;
+
+  set_window_bounds(&window, &region.bounds(&area))
+}
+
+/// Arrange windows on the monitor of the first window.
+pub fn tile_windows<R: Runtime, M: Manager<R>>(manager: &M, labels: &[String], layout: TileLayout) -> Result<()> {
+  let windows = labels
+    .iter()
+    .map(|label| {
+      manager.get_window(label)
+      .ok_or_else(|| Error::WindowNotFound(label.to_string()))
+    })
+    .collect::<Result<Vec<Window<R>>>>()?;
+
+  let area = match windows.first() {
+    Some(window) => get_window_work_area(window)?,
+    None => return Ok(()),
+  };
+
+  for (window, region) in windows.iter().zip(layout.regions(windows.len() as u32)) {
+    set_window_bounds(window, &region.bounds(&area))?;
+  }
+
+  Ok(())
+}
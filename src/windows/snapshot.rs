@@ -0,0 +1,152 @@
+//! Snapshot.
+//!
+//! This module contains basic methods and types for exporting and importing the plugin state.
+//! A snapshot is a single json document, used to sync settings between machines or attach to bug reports.
+
+use std::{path::{Component, Path}, result::Result as StdResult};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{
+  window::{WindowsBackup, WindowsState},
+  InnerWindowsSettings,
+  RecentlyOpened,
+  WindowsSettings,
+  WindowsStateCache
+};
+#[cfg(feature = "backups")]
+use super::WindowsBackupCache;
+#[cfg(feature = "recents")]
+use super::WindowsRecentsCache;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Version of the snapshot format.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Portable snapshot of window states, backup metadata, recents and settings.
+#[derive(Deserialize, Serialize)]
+pub struct StateSnapshot {
+  /// Version of the snapshot format, snapshots of other versions are rejected.
+  pub version: u32,
+  pub windows: WindowsState,
+  #[serde(default)]
+  pub backups: WindowsBackup,
+  #[serde(default)]
+  pub recents: RecentlyOpened,
+  pub settings: InnerWindowsSettings,
+}
+
+impl StateSnapshot {
+  /// Check the snapshot can be imported.
+  fn validate(&self) -> Result<()> {
+    if self.version != SNAPSHOT_VERSION {
+      return Err(Error::InvalidSnapshot(format!("unsupported version {}", self.version)));
+    }
+
+    // Empty window backups are folder names inside the backups directory
+    let outside_backups_dir = self.backups.empty_windows
+      .iter()
+      .filter_map(|info| info.backup_folder.as_ref())
+      .find(|backup_folder| !is_backup_folder_name(backup_folder));
+
+    if let Some(backup_folder) = outside_backups_dir {
+      return Err(Error::PathNotAllowed(backup_folder.clone()));
+    }
+
+    Ok(())
+  }
+}
+
+/// Check a backup folder is a plain relative path.
+fn is_backup_folder_name(backup_folder: &Path) -> bool {
+  backup_folder.components().all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Serialize the plugin state to a json snapshot.
+///
+/// Backups and recents compiled out with their feature are exported empty.
+pub fn export_state<R: Runtime, M: Manager<R>>(manager: &M) -> Result<String> {
+  let windows = manager.state::<WindowsStateCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .folded_state();
+
+  #[cfg(feature = "backups")]
+  let backups = manager.state::<WindowsBackupCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .backups
+  .clone();
+  #[cfg(not(feature = "backups"))]
+  let backups = WindowsBackup::default();
+
+  #[cfg(feature = "recents")]
+  let recents = manager.state::<WindowsRecentsCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .recents()
+  .clone();
+  #[cfg(not(feature = "recents"))]
+  let recents = RecentlyOpened::default();
+
+  let settings = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .clone();
+
+  let snapshot = StateSnapshot {
+    version: SNAPSHOT_VERSION,
+    windows,
+    backups,
+    recents,
+    settings,
+  };
+
+  Ok(to_string_pretty(&snapshot)?)
+}
+
+/// Replace the plugin state with a json snapshot.
+///
+/// The snapshot is validated first, nothing is replaced if it is invalid.
+/// All caches are locked while replacing so no one sees a partial import.
+/// Backups and recents compiled out with their feature are ignored.
+pub fn import_state<R: Runtime, M: Manager<R>>(manager: &M, json: &str) -> Result<()> {
+  let snapshot = from_str::<StateSnapshot>(json)
+    .map_err(|e| Error::InvalidSnapshot(e.to_string()))?;
+
+  snapshot.validate()?;
+
+  let state_cache = manager.state::<WindowsStateCache>();
+  #[cfg(feature = "backups")]
+  let backup_cache = manager.state::<WindowsBackupCache>();
+  #[cfg(feature = "recents")]
+  let recents_cache = manager.state::<WindowsRecentsCache>();
+  let settings = manager.state::<WindowsSettings>();
+
+  let mut state_cache = state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  #[cfg(feature = "backups")]
+  let mut backup_cache = backup_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  #[cfg(feature = "recents")]
+  let mut recents_cache = recents_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut settings = settings.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  // Imported settings never grant permissions
+  let permissions = settings.permissions.clone();
+
+  *settings = snapshot.settings;
+  settings.permissions = permissions;
+  #[cfg(feature = "backups")]
+  backup_cache.replace(snapshot.backups);
+  #[cfg(feature = "recents")]
+  recents_cache.replace(snapshot.recents)?;
+  state_cache.replace(snapshot.windows)
+}
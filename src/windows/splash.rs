@@ -0,0 +1,105 @@
+//! Splash.
+//!
+//! This module contains basic methods and types for the splash window shown during session restore.
+//! Provides the splash settings read from plugin config and the managed splash state.
+
+use std::{result::Result as StdResult, sync::RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{window::WindowBuilder, Manager, Runtime, Window, WindowUrl};
+
+use crate::error::Error;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Label used for the splash window.
+pub const SPLASH_WINDOW_LABEL: &str = "windows_splash";
+
+/// Splash window settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > splash`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SplashSettings {
+  /// Whether the splash window is shown on initial startup.
+  pub enabled: bool,
+  /// Url loaded in the splash window.
+  pub url: WindowUrl,
+  pub width: f64,
+  pub height: f64,
+  pub title: String,
+}
+
+impl Default for SplashSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      url: WindowUrl::App("splash.html".into()),
+      width: 400.0,
+      height: 300.0,
+      title: String::from(""),
+    }
+  }
+}
+
+/// Splash state cached during runtime.
+#[derive(Default)]
+pub struct InnerSplashState {
+  /// Label of the splash window while it is visible.
+  pub label: Option<String>,
+}
+
+/// Managed state for the splash window.
+#[derive(Default)]
+pub struct SplashState(pub RwLock<InnerSplashState>);
+
+/// Create the splash window and remember it in [`SplashState`].
+pub fn show_splash<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  settings: &SplashSettings,
+) -> Result<Window<R>> {
+  let handle = manager.app_handle();
+
+  let splash = WindowBuilder::new(&handle, SPLASH_WINDOW_LABEL, settings.url.clone())
+    .inner_size(settings.width, settings.height)
+    .center()
+    .decorations(false)
+    .resizable(false)
+    .skip_taskbar(true)
+    .always_on_top(true)
+    .title(settings.title.clone())
+    .build()?;
+
+  let splash_state = manager.state::<SplashState>();
+
+  let mut state = splash_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  state.label = Some(splash.label().to_string());
+
+  Ok(splash)
+}
+
+/// Close the splash window if it is still visible.
+///
+/// Ignores calls coming from the splash window itself.
+pub fn close_splash<R: Runtime, M: Manager<R>>(manager: &M, ready_label: &str) -> Result<()> {
+  let splash_state = manager.state::<SplashState>();
+
+  let mut state = splash_state.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  match &state.label {
+    Some(label) if label != ready_label => {
+      if let Some(splash) = manager.get_window(label) {
+        splash.close()?;
+      }
+
+      state.label = None;
+
+      Ok(())
+    },
+    _ => Ok(())
+  }
+}
@@ -0,0 +1,139 @@
+//! Stack.
+//!
+//! This module contains basic methods and types for the front-to-back order of windows.
+//! Raising follows focus events, minimized windows are lowered to the back.
+
+use std::{path::PathBuf, result::Result as StdResult, sync::RwLock};
+
+use tauri::{Manager, Runtime, Window};
+
+use crate::error::Error;
+
+use super::{get_focus_history, WindowsSettings, WindowsStateCache, SPLASH_WINDOW_LABEL};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Window of the last session stack.
+#[derive(Clone)]
+pub struct WindowStackEntry {
+  pub label: String,
+  /// Folder of the window, matches restored windows that got a new label.
+  pub folder: Option<PathBuf>,
+}
+
+impl WindowStackEntry {
+  /// Check if the entry is the window, or the folder open in it.
+  fn matches(&self, label: &str, folder: &Option<PathBuf>) -> bool {
+    self.label == label || (self.folder.is_some() && self.folder == *folder)
+  }
+}
+
+/// Stack of the last session.
+#[derive(Clone, Default)]
+pub struct SessionStack {
+  /// Windows front to back.
+  pub windows: Vec<WindowStackEntry>,
+  /// Window focused last.
+  pub focused: Option<WindowStackEntry>,
+}
+
+/// Managed state for the stack of the last session.
+#[derive(Default)]
+pub struct WindowsStackState(pub RwLock<SessionStack>);
+
+/// Get labels of open windows, front to back.
+pub fn get_window_stack<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Vec<String>> {
+  let focus_history = get_focus_history(manager);
+
+  let mut windows = manager.windows()
+    .into_iter()
+    .filter(|(label, _window)| label != SPLASH_WINDOW_LABEL)
+    .map(|(label, window)| {
+      let minimized = window.is_minimized()?;
+      let position = focus_history.iter().position(|focused| *focused == label);
+
+      Ok((minimized, position.unwrap_or(usize::MAX), label))
+    })
+    .collect::<Result<Vec<(bool, usize, String)>>>()?;
+
+  windows.sort();
+
+  Ok(windows.into_iter().map(|(_minimized, _position, label)| label).collect())
+}
+
+/// Remember the stack of the last session before windows get restored.
+pub fn save_session_stack<R: Runtime, M: Manager<R>>(manager: &M) -> Result<()> {
+  let stack = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let entry = |label: &String| {
+      cache.get_item(label).map(|state| WindowStackEntry {
+        label: label.clone(),
+        folder: state.folder,
+      })
+    };
+
+    SessionStack {
+      windows: cache.focus_history().iter().filter_map(&entry).collect(),
+      focused: cache.state().focused_window.as_ref().and_then(&entry),
+    }
+  };
+
+  *manager.state::<WindowsStackState>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))? = stack;
+
+  Ok(())
+}
+
+/// Restack open windows in the order of the last session.
+///
+/// Windows are matched by label, or by folder if they got a new label.
+/// Windows that weren't open in the last session stay at the back.
+/// With the `restore_focus` setting the window focused last time ends up focused.
+pub fn restore_stacking_order<R: Runtime, M: Manager<R>>(manager: &M) -> Result<()> {
+  let SessionStack { windows: stack, focused } = manager.state::<WindowsStackState>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .clone();
+
+  if stack.is_empty() {
+    return Ok(());
+  }
+
+  let restore_focus = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .restore_focus;
+
+  let mut windows = {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let cache = windows_state_cache.0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    manager.windows()
+      .into_iter()
+      .filter(|(label, _window)| label != SPLASH_WINDOW_LABEL)
+      .map(|(label, window)| {
+        let folder = cache.get_item(&label).and_then(|state| state.folder);
+
+        let position = stack.iter().position(|entry| entry.matches(&label, &folder));
+        let is_focused = restore_focus && focused.as_ref().map_or(false, |entry| entry.matches(&label, &folder));
+
+        (is_focused, position.unwrap_or(usize::MAX), window)
+      })
+      .collect::<Vec<(bool, usize, Window<R>)>>()
+  };
+
+  // Focusing back to front leaves the first window on top, the focused window goes last
+  windows.sort_by(|(a_focused, a, _), (b_focused, b, _)| a_focused.cmp(b_focused).then(b.cmp(a)));
+
+  for (_is_focused, _position, window) in windows {
+    if !window.is_minimized()? {
+      window.set_focus()?;
+    }
+  }
+
+  Ok(())
+}
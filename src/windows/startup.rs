@@ -0,0 +1,76 @@
+//! Startup.
+//!
+//! This module contains basic methods and types for detecting how the previous session ended.
+//! A running sentinel is written on startup and removed on clean exit.
+
+use std::{
+  fs::{read_to_string, remove_file, File},
+  io::Write,
+  path::{Path, PathBuf},
+  process,
+  sync::RwLock,
+};
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use super::log_error;
+
+const RUNNING_FILENAME: &str = ".windows_running_session";
+
+/// Info about how the previous session ended.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StartupInfo {
+  /// Whether the previous session ended without a clean exit.
+  pub crashed: bool,
+  /// Whether the previous session was relaunched through `relaunch_preserving_session`.
+  pub was_restarted: bool,
+}
+
+/// Managed state for startup info and the path of the running sentinel.
+///
+/// The path is empty if no sentinel is written.
+#[derive(Default)]
+pub struct WindowsStartupInfo(pub RwLock<StartupInfo>, PathBuf);
+
+impl WindowsStartupInfo {
+  /// Check the sentinel left by the previous session in the directory and write a new one.
+  pub fn detect(dir: &Path) -> Self {
+    let path = dir.join(RUNNING_FILENAME);
+    let crashed = path.exists();
+
+    write_running_sentinel(&path);
+
+    Self(RwLock::new(StartupInfo {
+      crashed,
+      ..Default::default()
+    }), path)
+  }
+}
+
+fn write_running_sentinel(path: &Path) {
+  let res = File::create(path)
+    .and_then(|mut file| file.write_all(process::id().to_string().as_bytes()));
+
+  if let Err(e) = res {
+    log_error(&e);
+  }
+}
+
+/// Remove the running sentinel, marking the session as cleanly exited.
+///
+/// Only the sentinel written by this process is removed, in memory sessions never write one.
+pub fn clear_running_sentinel<R: Runtime, M: Manager<R>>(manager: &M) {
+  let path = &manager.state::<WindowsStartupInfo>().1;
+
+  if !path.as_os_str().is_empty() {
+    let is_own = read_to_string(path).map_or(false, |contents| contents == process::id().to_string());
+
+    if is_own {
+      if let Err(e) = remove_file(path) {
+        log_error(&e);
+      }
+    }
+  }
+}
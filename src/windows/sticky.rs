@@ -0,0 +1,108 @@
+//! Sticky.
+//!
+//! This module contains basic methods and types for events replayed to windows created after they were sent.
+//! The latest payload per channel is kept, windows get it once they invoke `window_ready`.
+
+use std::{
+  collections::{BTreeMap, HashSet},
+  result::Result as StdResult,
+  sync::RwLock,
+};
+
+use serde::Serialize;
+use serde_json::{to_value, Value as JsonValue};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+use super::{
+  subscribers,
+  window::WindowTrait,
+  WindowsSettings,
+  WindowsSubscriptions
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Latest payload of a sticky channel.
+pub struct StickyEvent {
+  pub payload: JsonValue,
+  /// Windows that got the payload, or were ignored when it was sent.
+  pub delivered: HashSet<String>,
+}
+
+/// Managed state for the latest payload of sticky channels.
+#[derive(Default)]
+pub struct WindowsStickyEvents(pub RwLock<BTreeMap<String, StickyEvent>>);
+
+/// Emit an event to the windows listening to its channel and keep it for windows created later.
+///
+/// With the `ready_handshake` setting windows that aren't ready get it with the replay instead.
+pub fn emit_sticky<R: Runtime, M: Manager<R>, S: Serialize>(
+  manager: &M,
+  channel: &str,
+  payload: S,
+  ignored_labels: &[String],
+) -> Result<()> {
+  let payload = to_value(payload)?;
+
+  let handshake = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .ready_handshake;
+
+  let mut delivered = ignored_labels.iter().cloned().collect::<HashSet<String>>();
+
+  for label in subscribers(manager, channel, ignored_labels)? {
+    let is_ready = manager.get_window(&label).map_or(false, |window| window.is_ready());
+
+    if !handshake || is_ready {
+      manager.emit_to(&label, channel, payload.clone())?;
+      delivered.insert(label);
+    }
+  }
+
+  manager.state::<WindowsStickyEvents>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .insert(channel.to_string(), StickyEvent { payload, delivered });
+
+  Ok(())
+}
+
+/// Send a ready window the sticky events it didn't get yet.
+pub fn replay_sticky_events<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  if manager.get_window(label).is_none() {
+    return Ok(());
+  }
+
+  let subscriptions = manager.state::<WindowsSubscriptions>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .get(label)
+  .cloned();
+
+  let windows_sticky_events = manager.state::<WindowsStickyEvents>();
+
+  let mut sticky_events = windows_sticky_events.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  for (channel, event) in sticky_events.iter_mut() {
+    let is_subscribed = subscriptions.as_ref().map_or(true, |channels| channels.contains(channel));
+
+    if is_subscribed && event.delivered.insert(label.to_string()) {
+      manager.emit_to(label, channel, event.payload.clone())?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Forget deliveries to a destroyed window, a new window may reuse its label.
+pub fn clear_sticky_deliveries<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  for event in manager.state::<WindowsStickyEvents>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .values_mut() {
+    event.delivered.remove(label);
+  }
+
+  Ok(())
+}
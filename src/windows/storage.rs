@@ -0,0 +1,331 @@
+//! Storage.
+//!
+//! This module contains basic implementation of the json files backing the caches.
+//! Files are read lazily on first access so plugin initialization doesn't wait on disk.
+//! Files without a path are kept in memory only, e.g. in tests.
+//! With a [`StorageCipher`] files are encrypted on write and decrypted on read.
+//! Files live in the app data directory, files of earlier versions are moved there from the shared data directory.
+
+use std::{
+  fs::{create_dir_all, metadata, read, rename, File},
+  io::{ErrorKind, Write},
+  path::{Path, PathBuf},
+  result::Result as StdResult,
+  sync::Arc,
+};
+
+use once_cell::sync::OnceCell;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{from_str, to_string};
+
+use tauri::api::path::data_dir;
+
+use crate::error::Error;
+
+use super::{
+  log_error,
+  layout::LAYOUTS_FILENAME,
+  trust::TRUST_FILENAME,
+  window::{now, BACKUP_DIRNAME, BACKUP_FILENAME, RECENTS_FILENAME, STATE_FILENAME},
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Prefix of encrypted files, plain json never starts with it.
+const ENCRYPTED_HEADER: &[u8] = b"tauri-plugin-windows:encrypted:v1\n";
+
+/// Files and directories of earlier versions in the shared data directory.
+const LEGACY_FILENAMES: &[&str] = &[
+  STATE_FILENAME,
+  BACKUP_FILENAME,
+  BACKUP_DIRNAME,
+  RECENTS_FILENAME,
+  TRUST_FILENAME,
+  LAYOUTS_FILENAME,
+];
+
+/// Create the app data directory and move files of earlier versions into it from the shared data directory.
+///
+/// The shared directory was written by every app using the plugin.
+/// Files already in the app directory are kept, so files are moved only once.
+/// Returns the shared directory if anything was moved.
+pub fn migrate_legacy_files(dir: &Path) -> Option<PathBuf> {
+  if let Err(e) = create_dir_all(dir) {
+    log_error(&e);
+    return None;
+  }
+
+  let legacy_dir = data_dir().filter(|legacy_dir| legacy_dir != dir)?;
+
+  let mut migrated = false;
+
+  for name in LEGACY_FILENAMES {
+    let legacy_path = legacy_dir.join(name);
+    let path = dir.join(name);
+
+    if !legacy_path.exists() || path.exists() {
+      continue;
+    }
+
+    match rename(&legacy_path, &path) {
+      Ok(()) => migrated = true,
+      Err(source) => log_error(&Error::MigrationFailed { from: legacy_path, to: path, source }),
+    }
+  }
+
+  migrated.then(|| legacy_dir)
+}
+
+/// Encryption of the files backing the caches.
+///
+/// The plugin doesn't ship a cipher, apps bring their own, e.g. an AEAD keyed from the OS keychain.
+/// Register it with [`TauriWindows::encryption`].
+///
+/// [`TauriWindows::encryption`]: crate::TauriWindows::encryption
+pub trait StorageCipher: Send + Sync {
+  fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>>;
+  /// Fails with [`Error::Encryption`] if the key doesn't match.
+  fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Read a file written by [`write_contents`].
+///
+/// Plain json files are read as is, so files written before encryption was enabled still load.
+/// A missing file reads as empty.
+pub fn read_contents(path: &Path, cipher: Option<&dyn StorageCipher>) -> Result<String> {
+  let bytes = match read(path) {
+    Ok(bytes) => bytes,
+    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(String::new()),
+    Err(e) => return Err(e.into()),
+  };
+
+  let bytes = match (bytes.strip_prefix(ENCRYPTED_HEADER), cipher) {
+    (Some(ciphertext), Some(cipher)) => cipher.decrypt(ciphertext)?,
+    (Some(_ciphertext), None) => return Err(Error::Encryption(format!("`{}` is encrypted, but no cipher is set", path.display()))),
+    (None, _) => bytes,
+  };
+
+  String::from_utf8(bytes).map_err(|e| Error::Encryption(e.to_string()))
+}
+
+/// Write contents, encrypted if a cipher is set.
+pub fn write_contents(path: &Path, contents: &str, cipher: Option<&dyn StorageCipher>) -> Result<()> {
+  let bytes = match cipher {
+    Some(cipher) => [ENCRYPTED_HEADER, &cipher.encrypt(contents.as_bytes())?].concat(),
+    None => contents.as_bytes().to_vec(),
+  };
+
+  File::create(path)?.write_all(&bytes)?;
+
+  Ok(())
+}
+
+/// Status of a file backing a cache.
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageStatus {
+  pub path: PathBuf,
+  /// Size on disk in bytes, `None` if the file doesn't exist.
+  pub size: Option<u64>,
+  /// Whether the file was already read.
+  pub loaded: bool,
+  /// Last successful write in milliseconds since the unix epoch.
+  pub last_saved_at: Option<u64>,
+  /// Error of the last failed write, cleared by the next successful one.
+  pub last_save_error: Option<String>,
+  /// Whether the file is written encrypted.
+  pub encrypted: bool,
+}
+
+impl StorageStatus {
+  /// Status of a file that is never written through [`StorageFile`].
+  pub fn of(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+      size: metadata(path).ok().filter(|metadata| metadata.is_file()).map(|metadata| metadata.len()),
+      loaded: false,
+      last_saved_at: None,
+      last_save_error: None,
+      encrypted: false,
+    }
+  }
+}
+
+/// Data that can be kept in a [`StorageFile`].
+pub trait StorageData: Default + DeserializeOwned + Serialize {
+  /// Cap the in memory working set.
+  ///
+  /// Called after the data is read from disk.
+  fn cap(&mut self) {}
+}
+
+struct LoadedStorage<T> {
+  data: T,
+  last_saved_contents: String,
+  /// Error reading an encrypted file, the file is never overwritten so it can be read with the right key.
+  read_error: Option<String>,
+}
+
+/// Json file read on first access.
+///
+/// An empty path keeps the data in memory, nothing is read or written.
+pub struct StorageFile<T: StorageData> {
+  path: PathBuf,
+  cipher: Option<Arc<dyn StorageCipher>>,
+  loaded: OnceCell<LoadedStorage<T>>,
+  last_saved_at: Option<u64>,
+  last_save_error: Option<String>,
+}
+
+impl<T: StorageData> StorageFile<T> {
+  pub fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+      cipher: None,
+      loaded: OnceCell::new(),
+      last_saved_at: None,
+      last_save_error: None,
+    }
+  }
+
+  /// Storage kept in memory only.
+  pub fn in_memory() -> Self {
+    Self::new(Path::new(""))
+  }
+
+  /// Encrypt the file from the next write on.
+  ///
+  /// Must be set before the first access, a plain file is rewritten encrypted on the next save.
+  pub fn set_cipher(&mut self, cipher: Arc<dyn StorageCipher>) {
+    self.cipher = Some(cipher);
+  }
+
+  /// Whether the data is kept in memory only.
+  pub fn is_in_memory(&self) -> bool {
+    self.path.as_os_str().is_empty()
+  }
+
+  /// Status of the file, for diagnostics.
+  pub fn status(&self) -> StorageStatus {
+    StorageStatus {
+      loaded: self.is_loaded(),
+      last_saved_at: self.last_saved_at,
+      last_save_error: self.last_save_error.clone(),
+      encrypted: self.cipher.is_some(),
+      ..StorageStatus::of(&self.path)
+    }
+  }
+
+  /// Path of the file on disk.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Whether the file was already read.
+  pub fn is_loaded(&self) -> bool {
+    self.loaded.get().is_some()
+  }
+
+  fn load(&self) -> &LoadedStorage<T> {
+    self.loaded.get_or_init(|| {
+      let mut read_error = None;
+
+      let contents = match self.is_in_memory() {
+        true => String::new(),
+        false => match read_contents(&self.path, self.cipher.as_deref()) {
+          Ok(contents) => contents,
+          Err(e @ Error::Encryption(_)) => {
+            read_error = Some(e.to_string());
+            String::new()
+          },
+          Err(_e) => String::new(),
+        },
+      };
+
+      let mut data: T = match from_str(&contents) {
+        Ok(deserialized) => deserialized,
+        // Missing files read as empty, only an unparsable file is worth reporting
+        Err(source) => {
+          if !contents.is_empty() {
+            log_error(&Error::StorageCorrupted { path: self.path.clone(), source });
+          }
+
+          T::default()
+        }
+      };
+
+      data.cap();
+
+      // A plain file is rewritten encrypted on the next save
+      let last_saved_contents = match self.cipher {
+        Some(_) if !contents.is_empty() => String::new(),
+        _ => contents,
+      };
+
+      LoadedStorage {
+        data,
+        last_saved_contents,
+        read_error,
+      }
+    })
+  }
+
+  fn load_mut(&mut self) -> &mut LoadedStorage<T> {
+    self.load();
+    self.loaded.get_mut().expect("storage is loaded")
+  }
+
+  pub fn get(&self) -> &T {
+    &self.load().data
+  }
+
+  pub fn get_mut(&mut self) -> &mut T {
+    &mut self.load_mut().data
+  }
+
+  /// Write to disk if the data changed since the last save.
+  pub fn save(&mut self) -> Result<()> {
+    // Nothing was read, so nothing changed
+    if !self.is_loaded() || self.is_in_memory() {
+      return Ok(());
+    }
+
+    let path = self.path.clone();
+    let cipher = self.cipher.clone();
+    let loaded = self.load_mut();
+
+    if let Some(read_error) = loaded.read_error.clone() {
+      self.last_save_error = Some(read_error);
+      return Ok(());
+    }
+
+    let serialized_database = to_string(&loaded.data)?;
+
+    // Return early if the database has not changed
+    if loaded.last_saved_contents.eq(&serialized_database) {
+      return Ok(());
+    }
+
+    // Write to disk
+    match write_contents(&path, &serialized_database, cipher.as_deref()) {
+      Ok(()) => {
+        loaded.last_saved_contents = serialized_database;
+
+        self.last_saved_at = Some(now().as_millis() as u64);
+        self.last_save_error = None;
+
+        Ok(())
+      },
+      Err(e) => {
+        log_error(&e);
+        self.last_save_error = Some(e.to_string());
+        Ok(())
+      }
+    }
+  }
+}
+
+impl<T: StorageData> Default for StorageFile<T> {
+  fn default() -> Self {
+    Self::in_memory()
+  }
+}
@@ -0,0 +1,93 @@
+//! Subscription.
+//!
+//! This module contains basic methods and types for the plugin event channels windows listen to.
+//! Windows receive every broadcast until they subscribe, then only the channels they subscribed to.
+
+use std::{
+  collections::{BTreeSet, HashMap},
+  result::Result as StdResult,
+  sync::RwLock,
+};
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Managed state for the channels of windows that subscribed, keyed by window label.
+#[derive(Default)]
+pub struct WindowsSubscriptions(pub RwLock<HashMap<String, BTreeSet<String>>>);
+
+/// Add channels a window listens to.
+pub fn subscribe<R: Runtime, M: Manager<R>>(manager: &M, label: &str, channels: Vec<String>) -> Result<()> {
+  manager.state::<WindowsSubscriptions>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .entry(label.to_string())
+  .or_default()
+  .extend(channels);
+
+  Ok(())
+}
+
+/// Remove channels a window listens to, all of them if `channels` is empty.
+///
+/// A window without channels left receives no broadcasts, until it subscribes again.
+pub fn unsubscribe<R: Runtime, M: Manager<R>>(manager: &M, label: &str, channels: Vec<String>) -> Result<()> {
+  let windows_subscriptions = manager.state::<WindowsSubscriptions>();
+
+  let mut subscriptions = windows_subscriptions.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let subscribed = subscriptions.entry(label.to_string()).or_default();
+
+  match channels.is_empty() {
+    true => subscribed.clear(),
+    false => subscribed.retain(|channel| !channels.contains(channel)),
+  }
+
+  Ok(())
+}
+
+/// Forget the channels of a destroyed window.
+pub fn clear_subscriptions<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  manager.state::<WindowsSubscriptions>().0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .remove(label);
+
+  Ok(())
+}
+
+/// Get labels of the open windows listening to a channel, except the ignored ones.
+pub fn subscribers<R: Runtime, M: Manager<R>>(manager: &M, channel: &str, ignored_labels: &[String]) -> Result<Vec<String>> {
+  let windows_subscriptions = manager.state::<WindowsSubscriptions>();
+
+  let subscriptions = windows_subscriptions.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let mut labels = manager.windows()
+    .into_keys()
+    .filter(|label| !ignored_labels.contains(label))
+    .filter(|label| subscriptions.get(label).map_or(true, |channels| channels.contains(channel)))
+    .collect::<Vec<String>>();
+
+  labels.sort();
+
+  Ok(labels)
+}
+
+/// Emit an event to the windows listening to its channel, except the ignored ones.
+pub fn emit_to_subscribers<R: Runtime, M: Manager<R>, S: Serialize + Clone>(
+  manager: &M,
+  channel: &str,
+  payload: S,
+  ignored_labels: &[String],
+) -> Result<()> {
+  let labels = subscribers(manager, channel, ignored_labels)?;
+
+  manager.emit_filter(channel, payload, |window| labels.iter().any(|label| label == window.label()))?;
+
+  Ok(())
+}
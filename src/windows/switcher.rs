@@ -0,0 +1,89 @@
+//! Switcher.
+//!
+//! This module contains basic methods and types for an in-app window switcher.
+//! Entries are ordered by focus, most recent first, with thumbnails rendered by the window frontends.
+
+use std::{
+  path::PathBuf,
+  result::Result as StdResult,
+  time::Duration,
+};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_SCREENSHOT_REQUEST_EVENT;
+
+use super::{
+  get_focus_history,
+  group::list_windows,
+  screenshot_from_reply,
+  send_and_wait,
+  window::WindowMode,
+  Screenshot
+};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Time windows have to render their thumbnail, windows answering later get none.
+pub const THUMBNAIL_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Window of the switcher.
+#[derive(Clone, Serialize)]
+pub struct SwitcherEntry {
+  pub label: String,
+  pub title: String,
+  pub folder: Option<PathBuf>,
+  pub group: Option<String>,
+  pub mode: WindowMode,
+  /// Capture of the window contents, if asked for and the window answered in time.
+  pub thumbnail: Option<Screenshot>,
+}
+
+/// Get the open windows, most recently focused first.
+///
+/// With `thumbnails` every window is asked for a screenshot at once, waiting at most [`THUMBNAIL_TIMEOUT`].
+pub fn get_switcher_entries<R: Runtime, M: Manager<R>>(manager: &M, thumbnails: bool) -> Result<Vec<SwitcherEntry>> {
+  let mut windows = list_windows(manager)?;
+
+  // Windows never focused keep their label order, after the focused ones
+  let focus_history = get_focus_history(manager);
+
+  windows.sort_by_key(|info| focus_history.iter().position(|label| label == &info.label).unwrap_or(usize::MAX));
+
+  let mut screenshots = match thumbnails {
+    true => send_and_wait(manager, WINDOW_SCREENSHOT_REQUEST_EVENT, JsonValue::Null, &[], THUMBNAIL_TIMEOUT)?
+      .into_iter()
+      .filter_map(|reply| screenshot_from_reply(reply, false).ok())
+      .collect::<Vec<Screenshot>>(),
+    false => Vec::new(),
+  };
+
+  Ok(
+    windows
+      .into_iter()
+      .map(|info| {
+        let title = manager.get_window(&info.label)
+          .and_then(|window| window.title().ok())
+          .unwrap_or_default();
+
+        let thumbnail = screenshots
+          .iter()
+          .position(|screenshot| screenshot.label == info.label)
+          .map(|index| screenshots.swap_remove(index));
+
+        SwitcherEntry {
+          label: info.label,
+          title,
+          folder: info.folder,
+          group: info.group,
+          mode: info.mode,
+          thumbnail,
+        }
+      })
+      .collect()
+  )
+}
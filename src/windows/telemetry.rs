@@ -0,0 +1,50 @@
+//! Telemetry.
+//!
+//! This module contains basic methods and types for instrumenting open requests.
+//! Apps register a hook on the plugin builder, the plugin never sends data itself.
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime};
+
+use super::OpenContext;
+
+/// Statistics about an open request.
+#[derive(Clone, Debug, Serialize)]
+pub struct OpenStats {
+  pub context: OpenContext,
+  pub initial_startup: bool,
+  pub files: usize,
+  pub folders: usize,
+  pub urls: usize,
+  /// Windows created for the request.
+  pub new_windows: usize,
+  /// Existing windows used for the request.
+  pub reused_windows: usize,
+  /// Windows restored from the last session, only on initial startup.
+  pub restored_windows: usize,
+  /// Empty windows restored from backups.
+  pub restored_backups: usize,
+  /// Error message if the request failed.
+  pub error: Option<String>,
+}
+
+/// Hook invoked on every open request.
+///
+/// Register it with [`TauriWindows::telemetry`].
+///
+/// [`TauriWindows::telemetry`]: crate::TauriWindows::telemetry
+pub trait WindowsTelemetry: Send + Sync {
+  fn on_open(&self, stats: &OpenStats);
+}
+
+/// Managed state for the telemetry hook.
+#[derive(Default)]
+pub struct WindowsTelemetryHook(pub Option<Box<dyn WindowsTelemetry>>);
+
+/// Pass open statistics to the registered hook, if any.
+pub fn report_open<R: Runtime, M: Manager<R>>(manager: &M, stats: &OpenStats) {
+  if let Some(telemetry) = &manager.state::<WindowsTelemetryHook>().0 {
+    telemetry.on_open(stats);
+  }
+}
@@ -0,0 +1,137 @@
+//! Theme.
+//!
+//! This module contains basic methods and types for window themes.
+//! Themes can't be changed on a live webview, so changes are delivered to the frontend as events.
+
+use std::{result::Result as StdResult, sync::RwLock};
+
+use serde::Serialize;
+
+use tauri::{Manager, Runtime, Theme};
+
+use crate::error::Error;
+use crate::event::WINDOW_THEME_CHANGED_EVENT;
+
+use super::{WindowsSettings, WindowsStateCache, SPLASH_WINDOW_LABEL};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Payload of the theme changed event.
+#[derive(Clone, Debug, Serialize)]
+pub struct ThemeChangedPayload {
+  pub label: String,
+  pub theme: Theme,
+  /// Whether the window follows the system theme.
+  pub follows_system: bool,
+}
+
+/// Managed state for the last known system theme.
+#[derive(Default)]
+pub struct SystemTheme(pub RwLock<Option<Theme>>);
+
+/// Set the theme of a window, or of all windows if `label` is `None`.
+///
+/// A `None` theme makes windows follow the system theme again.
+/// The theme is cached so restored windows are built with it.
+pub fn set_theme<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  label: Option<&str>,
+  theme: Option<Theme>,
+) -> Result<()> {
+  let windows = manager.windows();
+
+  let labels = windows
+    .keys()
+    .filter(|window_label| window_label.as_str() != SPLASH_WINDOW_LABEL)
+    .filter(|window_label| label.map_or(true, |label| label == window_label.as_str()))
+    .cloned()
+    .collect::<Vec<String>>();
+
+  if let Some(label) = label {
+    if labels.is_empty() {
+      return Err(Error::WindowStateWithLabelNotFound(label.to_string()));
+    }
+  }
+
+  {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let states = labels
+      .iter()
+      .filter_map(|label| cache.get_item(label).map(|state| (label.clone(), state)))
+      .map(|(label, mut state)| {
+        state.configuration.theme = theme;
+        (label, state)
+      })
+      .collect::<Vec<_>>();
+
+    cache.set_items(states.into_iter())?;
+  }
+
+  for label in labels {
+    if let Some(window) = windows.get(&label) {
+      let resolved_theme = match theme {
+        Some(theme) => theme,
+        None => window.theme()?,
+      };
+
+      window.emit(WINDOW_THEME_CHANGED_EVENT, ThemeChangedPayload {
+        label,
+        theme: resolved_theme,
+        follows_system: theme.is_none(),
+      })?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Broadcast a system theme change to windows following the system theme.
+///
+/// Does nothing unless the `follow_system_theme` setting is enabled.
+/// The system reports the change once per window, only the first report is broadcast.
+pub fn handle_system_theme_changed<R: Runtime, M: Manager<R>>(manager: &M, theme: Theme) -> Result<()> {
+  let enabled = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .follow_system_theme;
+
+  if !enabled {
+    return Ok(());
+  }
+
+  {
+    let system_theme = manager.state::<SystemTheme>();
+
+    let mut last_theme = system_theme.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    if *last_theme == Some(theme) {
+      return Ok(());
+    }
+
+    *last_theme = Some(theme);
+  }
+
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  for (label, window) in manager.windows() {
+    let follows_system = cache.get_item(&label)
+      .map_or(true, |state| state.configuration.theme.is_none());
+
+    if label != SPLASH_WINDOW_LABEL && follows_system {
+      window.emit(WINDOW_THEME_CHANGED_EVENT, ThemeChangedPayload {
+        label: label.clone(),
+        theme,
+        follows_system,
+      })?;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,35 @@
+//! Trace.
+//!
+//! This module contains basic methods and types for explaining open requests.
+//! Decisions are only collected for requests that set `explain`.
+
+/// Decisions made while opening resources, in order.
+#[derive(Default)]
+pub struct OpenTrace {
+  enabled: bool,
+  steps: Vec<String>,
+}
+
+impl OpenTrace {
+  pub fn new(enabled: bool) -> Self {
+    Self {
+      enabled,
+      steps: Vec::new(),
+    }
+  }
+
+  /// Record a decision, the message is only built in explain mode.
+  pub fn step<F: FnOnce() -> String>(&mut self, step: F) {
+    if self.enabled {
+      self.steps.push(step());
+    }
+  }
+
+  /// Hand over the decisions, `None` outside explain mode.
+  pub fn finish(self) -> Option<Vec<String>> {
+    match self.enabled {
+      true => Some(self.steps),
+      false => None,
+    }
+  }
+}
@@ -0,0 +1,200 @@
+//! Trust.
+//!
+//! This module contains basic methods and types for workspace trust.
+//! Folders open in restricted mode until the frontend trusts them, trusted folders are persisted.
+
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  result::Result as StdResult,
+  sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_TRUST_REQUEST_EVENT;
+
+use super::{
+  storage::{StorageCipher, StorageData, StorageFile, StorageStatus},
+  WindowsSettings
+};
+
+type Result<T> = StdResult<T, Error>;
+
+pub(crate) const TRUST_FILENAME: &str = ".windows_trust_session";
+
+/// Workspace trust settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > trust`.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TrustSettings {
+  /// Whether folders must be trusted before they are fully opened.
+  pub enabled: bool,
+}
+
+/// Payload of the trust request event.
+#[derive(Clone, Debug, Serialize)]
+pub struct TrustRequestPayload {
+  pub folder: PathBuf,
+}
+
+/// Folders trusted by the user.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct TrustedFolders {
+  pub folders: Vec<PathBuf>,
+}
+
+impl StorageData for TrustedFolders {}
+
+/// Trust store and pending trust requests.
+pub struct InnerWindowsTrustCache {
+  storage: StorageFile<TrustedFolders>,
+  /// Folders waiting for an answer, keyed by window label.
+  pending: HashMap<String, PathBuf>,
+}
+
+impl InnerWindowsTrustCache {
+  pub fn new(file: &Path) -> Self {
+    InnerWindowsTrustCache {
+      storage: StorageFile::new(file),
+      pending: HashMap::new(),
+    }
+  }
+
+  /// Cache kept in memory only, nothing is read from or written to disk.
+  pub fn in_memory() -> Self {
+    InnerWindowsTrustCache {
+      storage: StorageFile::in_memory(),
+      pending: HashMap::new(),
+    }
+  }
+
+  /// Encrypt the file backing the cache, see [`StorageFile::set_cipher`].
+  pub fn set_cipher(&mut self, cipher: Arc<dyn StorageCipher>) {
+    self.storage.set_cipher(cipher);
+  }
+
+  pub fn trusted(&self) -> &TrustedFolders {
+    self.storage.get()
+  }
+
+  /// Check if a folder or one of its parents is trusted.
+  pub fn is_trusted(&self, folder: &Path) -> bool {
+    self.trusted().folders.iter().any(|trusted| folder.starts_with(trusted))
+  }
+
+  pub fn trust(&mut self, folder: &Path) -> Result<()> {
+    if !self.is_trusted(folder) {
+      self.storage.get_mut().folders.push(folder.to_path_buf());
+    }
+
+    self.pending.retain(|_label, pending| !pending.starts_with(folder));
+
+    self.storage.save()
+  }
+
+  /// Revoke trust of a folder and of trusted folders inside it.
+  pub fn revoke(&mut self, folder: &Path) -> Result<()> {
+    self.storage.get_mut().folders.retain(|trusted| !trusted.starts_with(folder));
+    self.storage.save()
+  }
+
+  pub fn storage_status(&self) -> StorageStatus {
+    self.storage.status()
+  }
+
+  pub fn save(&mut self) -> Result<()> {
+    self.storage.save()
+  }
+
+  pub fn get_pending(&self, label: &str) -> Option<&PathBuf> {
+    self.pending.get(label)
+  }
+
+  pub fn set_pending(&mut self, label: &str, folder: Option<&PathBuf>) {
+    match folder {
+      Some(folder) => self.pending.insert(label.to_string(), folder.clone()),
+      None => self.pending.remove(label),
+    };
+  }
+}
+
+impl Default for InnerWindowsTrustCache {
+  fn default() -> Self {
+    InnerWindowsTrustCache::in_memory()
+  }
+}
+
+#[derive(Default)]
+pub struct WindowsTrustCache(pub RwLock<InnerWindowsTrustCache>);
+
+impl WindowsTrustCache {
+  pub fn in_memory() -> Self {
+    Self(RwLock::new(InnerWindowsTrustCache::in_memory()))
+  }
+
+  /// Cache backed by its file in the directory, encrypted if a cipher is set.
+  pub fn open(dir: &Path, cipher: Option<Arc<dyn StorageCipher>>) -> Self {
+    let mut cache = InnerWindowsTrustCache::new(&dir.join(TRUST_FILENAME));
+
+    if let Some(cipher) = cipher {
+      cache.set_cipher(cipher);
+    }
+
+    Self(RwLock::new(cache))
+  }
+}
+
+/// Check if a folder can be fully opened.
+///
+/// Always true unless the `trust` setting is enabled.
+pub fn is_folder_trusted<R: Runtime, M: Manager<R>>(manager: &M, folder: &Path) -> Result<bool> {
+  let enabled = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .trust
+  .enabled;
+
+  if !enabled {
+    return Ok(true);
+  }
+
+  let windows_trust_cache = manager.state::<WindowsTrustCache>();
+
+  let cache = windows_trust_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  Ok(cache.is_trusted(folder))
+}
+
+/// Ask the frontend of a window to trust its folder.
+///
+/// The request is kept until answered so it can be sent again once the page loaded.
+pub fn request_trust<R: Runtime, M: Manager<R>>(manager: &M, label: &str, folder: &Path) -> Result<()> {
+  let windows_trust_cache = manager.state::<WindowsTrustCache>();
+
+  windows_trust_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .set_pending(label, Some(&folder.to_path_buf()));
+
+  emit_trust_request(manager, label)
+}
+
+/// Send the pending trust request of a window, if any.
+pub fn emit_trust_request<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let folder = manager.state::<WindowsTrustCache>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .get_pending(label)
+  .cloned();
+
+  match (folder, manager.get_window(label)) {
+    (Some(folder), Some(window)) => {
+      window.emit(WINDOW_TRUST_REQUEST_EVENT, TrustRequestPayload { folder })?;
+      Ok(())
+    },
+    _ => Ok(())
+  }
+}
@@ -0,0 +1,440 @@
+//! Watcher.
+//!
+//! This module contains basic methods and types for watching folders open in windows.
+//! Folders are polled on a background thread so no platform specific watcher is needed.
+
+use std::{
+  collections::HashMap,
+  fs::{read_dir, symlink_metadata, Metadata},
+  path::{Path, PathBuf},
+  result::Result as StdResult,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+    RwLock
+  },
+  thread,
+  time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::Error;
+use crate::event::WINDOW_FOLDER_CHANGED_EVENT;
+
+use super::{close_when_empty, log_error, report_missing_files, WindowsSettings, WindowsStateCache};
+#[cfg(feature = "recents")]
+use super::WindowsRecentsCache;
+
+type Result<T> = StdResult<T, Error>;
+
+/// Folder watcher settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > watch`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatchSettings {
+  /// Whether folders open in windows are watched.
+  pub enabled: bool,
+  /// Poll interval, changes are emitted once the folder is unchanged for a full interval.
+  pub interval_ms: u64,
+  /// Maximum entries tracked per folder, deeper entries are ignored.
+  pub max_entries: usize,
+}
+
+impl Default for WatchSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      interval_ms: 1000,
+      max_entries: 10_000,
+    }
+  }
+}
+
+/// Entry renamed inside a watched folder.
+#[derive(Clone, Debug, Serialize)]
+pub struct RenamedEntry {
+  pub from: PathBuf,
+  pub to: PathBuf,
+}
+
+/// Payload of the folder changed event.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FolderChangedPayload {
+  pub folder: PathBuf,
+  pub created: Vec<PathBuf>,
+  pub removed: Vec<PathBuf>,
+  pub renamed: Vec<RenamedEntry>,
+  /// New path of the watched folder if it was renamed.
+  pub folder_renamed: Option<PathBuf>,
+  /// Whether the watched folder was deleted.
+  pub folder_removed: bool,
+}
+
+impl FolderChangedPayload {
+  fn is_empty(&self) -> bool {
+    self.created.is_empty()
+      && self.removed.is_empty()
+      && self.renamed.is_empty()
+      && self.folder_renamed.is_none()
+      && !self.folder_removed
+  }
+}
+
+/// Watcher running for a window.
+pub struct FolderWatch {
+  folder: PathBuf,
+  stop: Arc<AtomicBool>,
+}
+
+impl Drop for FolderWatch {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+  }
+}
+
+/// Managed state for folder watchers, keyed by window label.
+#[derive(Default)]
+pub struct FolderWatchers(pub RwLock<HashMap<String, FolderWatch>>);
+
+/// Metadata used to match renamed entries.
+#[derive(Clone, PartialEq)]
+struct EntrySignature {
+  is_dir: bool,
+  len: u64,
+  modified: Option<SystemTime>,
+  id: Option<u64>,
+}
+
+impl EntrySignature {
+  fn new(metadata: &Metadata) -> Self {
+    Self {
+      is_dir: metadata.is_dir(),
+      len: metadata.len(),
+      modified: metadata.modified().ok(),
+      id: file_id(metadata),
+    }
+  }
+
+  /// Check if two entries are likely the same file.
+  ///
+  /// Uses the file id where available, else type, size and modified time.
+  fn is_same_entry(&self, other: &EntrySignature) -> bool {
+    match (self.id, other.id) {
+      (Some(id), Some(other_id)) => id == other_id,
+      _ => self.is_dir == other.is_dir && self.len == other.len && self.modified == other.modified,
+    }
+  }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<u64> {
+  use std::os::unix::fs::MetadataExt;
+
+  Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<u64> {
+  None
+}
+
+type Snapshot = HashMap<PathBuf, EntrySignature>;
+
+/// Read the entries of a folder, without following symlinks.
+fn snapshot(folder: &Path, max_entries: usize) -> Snapshot {
+  let mut entries = Snapshot::new();
+  let mut folders = vec![folder.to_path_buf()];
+
+  while let Some(current) = folders.pop() {
+    let dir = match read_dir(&current) {
+      Ok(dir) => dir,
+      Err(_e) => continue,
+    };
+
+    for entry in dir.flatten() {
+      if entries.len() >= max_entries {
+        return entries;
+      }
+
+      let path = entry.path();
+
+      if let Ok(metadata) = symlink_metadata(&path) {
+        if metadata.is_dir() {
+          folders.push(path.clone());
+        }
+
+        entries.insert(path, EntrySignature::new(&metadata));
+      }
+    }
+  }
+
+  entries
+}
+
+/// Get created, removed and renamed entries between two snapshots.
+///
+/// Entries inside a renamed folder are reported through the folder only.
+fn diff(folder: &Path, before: &Snapshot, after: &Snapshot) -> FolderChangedPayload {
+  let mut removed: Vec<PathBuf> = before.keys().filter(|path| !after.contains_key(*path)).cloned().collect();
+  let mut created: Vec<PathBuf> = after.keys().filter(|path| !before.contains_key(*path)).cloned().collect();
+  let mut renamed: Vec<RenamedEntry> = Vec::new();
+
+  // Parents first so children of renamed folders can be dropped
+  removed.sort();
+  created.sort();
+
+  for from in &removed {
+    if renamed.iter().any(|entry| from.starts_with(&entry.from)) {
+      continue;
+    }
+
+    let signature = &before[from];
+
+    let to = created.iter().find(|to| {
+      !renamed.iter().any(|entry| to.starts_with(&entry.to)) && after[*to].is_same_entry(signature)
+    });
+
+    if let Some(to) = to {
+      renamed.push(RenamedEntry {
+        from: from.clone(),
+        to: to.clone(),
+      });
+    }
+  }
+
+  removed.retain(|path| !renamed.iter().any(|entry| path.starts_with(&entry.from)));
+  created.retain(|path| !renamed.iter().any(|entry| path.starts_with(&entry.to)));
+
+  FolderChangedPayload {
+    folder: folder.to_path_buf(),
+    created,
+    removed,
+    renamed,
+    ..Default::default()
+  }
+}
+
+/// Find where a deleted folder went, if it was renamed within its parent.
+fn find_renamed_folder(folder: &Path, signature: &Option<EntrySignature>) -> Option<PathBuf> {
+  let signature = signature.as_ref()?;
+
+  // Without a file id any folder could match
+  signature.id?;
+
+  let parent = folder.parent()?;
+
+  read_dir(parent).ok()?
+    .flatten()
+    .map(|entry| entry.path())
+    .find(|path| {
+      symlink_metadata(path).map_or(false, |metadata| {
+        metadata.is_dir() && EntrySignature::new(&metadata).is_same_entry(signature)
+      })
+    })
+}
+
+/// Update cached state and recents after the watched folder was renamed or deleted.
+fn fix_up_folder<R: Runtime>(
+  handle: &AppHandle<R>,
+  folder: &Path,
+  renamed_to: Option<&Path>,
+) -> Result<()> {
+  let windows_state_cache = handle.state::<WindowsStateCache>();
+
+  let mut cache = windows_state_cache.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  match renamed_to {
+    Some(to) => cache.rename_folder(folder, to)?,
+    None => cache.remove_folder(folder)?,
+  };
+
+  #[cfg(feature = "recents")]
+  {
+    let windows_recents_cache = handle.state::<WindowsRecentsCache>();
+
+    let mut cache = windows_recents_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    match renamed_to {
+      Some(to) => cache.rename_folder(folder, to)?,
+      None => cache.remove_folder(folder)?,
+    };
+  }
+
+  Ok(())
+}
+
+/// Poll a folder until stopped or the window is gone.
+fn watch<R: Runtime>(
+  handle: AppHandle<R>,
+  label: String,
+  mut folder: PathBuf,
+  settings: WatchSettings,
+  stop: Arc<AtomicBool>,
+) {
+  let interval = Duration::from_millis(settings.interval_ms.max(1));
+
+  let mut folder_signature = symlink_metadata(&folder).ok().map(|metadata| EntrySignature::new(&metadata));
+  let mut base = snapshot(&folder, settings.max_entries);
+  let mut previous = base.clone();
+
+  loop {
+    thread::sleep(interval);
+
+    if stop.load(Ordering::Relaxed) {
+      return;
+    }
+
+    let window = match handle.get_window(&label) {
+      Some(window) => window,
+      None => return,
+    };
+
+    // Watched folder itself is gone
+    if !folder.is_dir() {
+      let renamed_to = find_renamed_folder(&folder, &folder_signature);
+
+      // Files of a renamed folder are followed, see `fix_up_folder`
+      if renamed_to.is_none() {
+        if let Err(e) = report_missing_files(&handle, &[(folder.clone(), None)]) {
+          log_error(&e);
+        }
+      }
+
+      if let Err(e) = fix_up_folder(&handle, &folder, renamed_to.as_deref()) {
+        log_error(&e);
+      }
+
+      let payload = FolderChangedPayload {
+        folder: folder.clone(),
+        folder_removed: renamed_to.is_none(),
+        folder_renamed: renamed_to.clone(),
+        ..Default::default()
+      };
+
+      if let Err(e) = window.emit(WINDOW_FOLDER_CHANGED_EVENT, payload) {
+        log_error(&e);
+      }
+
+      // The window lost its folder
+      if renamed_to.is_none() {
+        if let Err(e) = close_when_empty(&handle, &label) {
+          log_error(&e);
+        }
+      }
+
+      match renamed_to {
+        Some(to) => {
+          folder_signature = symlink_metadata(&to).ok().map(|metadata| EntrySignature::new(&metadata));
+          base = snapshot(&to, settings.max_entries);
+          previous = base.clone();
+          folder = to;
+
+          continue;
+        },
+        None => return,
+      }
+    }
+
+    let current = snapshot(&folder, settings.max_entries);
+
+    // Wait until the folder settles before emitting
+    if current != previous {
+      previous = current;
+      continue;
+    }
+
+    if current == base {
+      continue;
+    }
+
+    let payload = diff(&folder, &base, &current);
+
+    let removed = payload.removed
+      .iter()
+      .map(|path| (path.clone(), None))
+      .chain(payload.renamed.iter().map(|entry| (entry.from.clone(), Some(entry.to.clone()))))
+      .collect::<Vec<(PathBuf, Option<PathBuf>)>>();
+
+    if let Err(e) = report_missing_files(&handle, &removed) {
+      log_error(&e);
+    }
+
+    if !payload.is_empty() {
+      if let Err(e) = window.emit(WINDOW_FOLDER_CHANGED_EVENT, payload) {
+        log_error(&e);
+      }
+    }
+
+    base = current;
+  }
+}
+
+/// Watch the folder open in a window.
+///
+/// Replaces the watcher of the window if the folder changed, and stops it if `folder` is `None`.
+/// Does nothing unless the `watch` setting is enabled.
+pub fn watch_folder<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  label: &str,
+  folder: Option<&PathBuf>,
+) -> Result<()> {
+  let settings = match manager.state::<WindowsSettings>().0.read() {
+    Ok(settings) => settings.watch.clone(),
+    Err(e) => return Err(Error::RwLock(e.to_string())),
+  };
+
+  if !settings.enabled {
+    return Ok(());
+  }
+
+  let folder_watchers = manager.state::<FolderWatchers>();
+
+  let mut watchers = folder_watchers.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let folder = match folder {
+    Some(folder) => folder,
+    None => {
+      watchers.remove(label);
+      return Ok(());
+    }
+  };
+
+  if watchers.get(label).map_or(false, |watch| &watch.folder == folder) {
+    return Ok(());
+  }
+
+  let stop = Arc::new(AtomicBool::new(false));
+
+  // Replacing the watch stops the previous thread
+  watchers.insert(label.to_string(), FolderWatch {
+    folder: folder.clone(),
+    stop: stop.clone(),
+  });
+
+  let handle = manager.app_handle();
+  let label = label.to_string();
+  let folder = folder.clone();
+
+  thread::spawn(move || watch(handle, label, folder, settings, stop));
+
+  Ok(())
+}
+
+/// Stop watching the folder open in a window.
+pub fn unwatch_folder<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let folder_watchers = manager.state::<FolderWatchers>();
+
+  let mut watchers = folder_watchers.0.write()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  watchers.remove(label);
+
+  Ok(())
+}
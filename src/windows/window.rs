@@ -5,45 +5,49 @@
 //! Extends window.
 
 use std::{
-  collections::{HashMap, hash_map::DefaultHasher},
-  fs::File,
-  path::{PathBuf, Path},
+  collections::{BTreeSet, HashMap, hash_map::DefaultHasher},
+  fs::{create_dir_all, remove_dir_all, rename},
+  path::{Component, PathBuf, Path},
   result::Result as StdResult,
-  sync::{
-    atomic::{AtomicUsize, Ordering},
-    RwLock
-  },
-  time::{Duration, SystemTime, UNIX_EPOCH}, io::Write, hash::{Hasher, Hash},
+  sync::{Arc, RwLock},
+  time::{Duration, SystemTime, UNIX_EPOCH}, hash::{Hasher, Hash},
 };
 
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_str, to_string};
+use serde_json::{from_str, Value as JsonValue};
+use sha2::{Digest, Sha256};
 
 use tauri::{
-  api::{
-    file::read_string,
-    path::{data_dir}
-  },
+  api::file::read_string,
   window::Monitor, Manager, PhysicalPosition,
-  PhysicalSize, Runtime, Window,
+  PhysicalSize, Runtime, Theme, Url, Window, WindowUrl,
 };
 
 use crate::error::Error;
 
-use super::PathToOpen;
+use super::{
+  diagnostics::{log_error, write_lock},
+  index::FolderIndex,
+  route::navigate,
+  storage::{read_contents, StorageCipher, StorageData, StorageFile, StorageStatus},
+  PathToOpen
+};
 
 type Result<T> = StdResult<T, Error>;
 
-static COUNTER: AtomicUsize = AtomicUsize::new(1);
-const STATE_FILENAME: &str = ".windows_state_session";
-const BACKUP_FILENAME: &str = ".windows_backup_session";
-const RECENTS_FILENAME: &str = ".windows_recents_session";
+pub(crate) const STATE_FILENAME: &str = ".windows_state_session";
+pub(crate) const BACKUP_FILENAME: &str = ".windows_backup_session";
+pub(crate) const BACKUP_DIRNAME: &str = ".windows_backups";
+pub(crate) const RECENTS_FILENAME: &str = ".windows_recents_session";
 const MAX_TOTAL_RECENT_ENTRIES: u16 = 500;
+const MAX_TOTAL_WINDOW_STATES: usize = 500;
 const RECENTLY_OPENED_STORAGE_KEY: &str = "history.recently_opened_paths_list";
 
 /// Window bounds rectangle.
 /// 
 /// A rectangle representing the window.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub struct WindowBounds {
   pub x: i32,
   pub y: i32,
@@ -51,6 +55,21 @@ pub struct WindowBounds {
   pub width: u32,
 }
 
+/// Window bounds in logical pixels.
+///
+/// Independent of the monitor scale factor, used to restore windows with the right size.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LogicalWindowBounds {
+  pub x: f64,
+  pub y: f64,
+  /// Inner height.
+  pub height: f64,
+  /// Inner width.
+  pub width: f64,
+  /// Scale factor of the monitor the bounds were measured on.
+  pub scale_factor: f64,
+}
+
 impl Default for WindowBounds {
   fn default() -> Self {
     Self {
@@ -62,10 +81,26 @@ impl Default for WindowBounds {
   }
 }
 
+impl WindowBounds {
+  /// Get the area shared with other bounds.
+  pub fn intersection_area(&self, other: &WindowBounds) -> u64 {
+    let left = self.x.max(other.x) as i64;
+    let top = self.y.max(other.y) as i64;
+    let right = (self.x as i64 + self.width as i64).min(other.x as i64 + other.width as i64);
+    let bottom = (self.y as i64 + self.height as i64).min(other.y as i64 + other.height as i64);
+
+    if right <= left || bottom <= top {
+      return 0;
+    }
+
+    ((right - left) * (bottom - top)) as u64
+  }
+}
+
 /// Window screen mode.
 /// 
 /// A flag representing the screen style for the window.
-#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum WindowMode {
   Maximized,
   Normal,
@@ -82,7 +117,7 @@ impl Default for WindowMode {
 /// Ready state for winodw.
 /// 
 /// A flag for whether the window can currently handle events.
-#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum ReadyState {
   None,
   Navigating,
@@ -95,6 +130,27 @@ impl Default for ReadyState {
   }
 }
 
+/// Webview configuration for a window.
+///
+/// Persisted with the window state so restored windows use the same webview profile.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct WebviewOptions {
+  /// User agent of the webview.
+  pub user_agent: Option<String>,
+  /// Additional arguments for the webview, only supported on Windows.
+  pub additional_browser_args: Option<String>,
+  /// Isolated data directory for the webview.
+  pub data_directory: Option<PathBuf>,
+}
+
+impl WebviewOptions {
+  pub fn is_empty(&self) -> bool {
+    self.user_agent.is_none()
+      && self.additional_browser_args.is_none()
+      && self.data_directory.is_none()
+  }
+}
+
 /// Window configuration cached during runtime.
 /// 
 /// Contains info that will be backed up.
@@ -106,10 +162,29 @@ pub struct WindowConfiguration {
   pub maximized: bool,
   pub cache_path: Option<PathBuf>,
   pub backup_path: Option<PathBuf>,
-	pub home_dir: Option<PathBuf>,
-	pub tmp_dir: Option<PathBuf>,
-	pub user_data_dir: Option<PathBuf>,
+  /// Home directory of the window, resolved when the window opens.
+  pub home_dir: Option<PathBuf>,
+  /// Temporary directory of the window, resolved when the window opens.
+  pub tmp_dir: Option<PathBuf>,
+  /// Directory the webview keeps its profile in, resolved when the window opens.
+  pub user_data_dir: Option<PathBuf>,
   pub is_initial_startup: bool,
+  /// External url loaded in the window, windows with url have no file/folder.
+  pub url: Option<Url>,
+  #[serde(default)]
+  pub webview: WebviewOptions,
+  /// Forced theme, `None` follows the system theme.
+  #[serde(default)]
+  pub theme: Option<Theme>,
+  /// Route of the app page shown in the window, restored with the session.
+  #[serde(default)]
+  pub app_route: Option<String>,
+  /// Whether the contents are hidden from screen capture.
+  #[serde(default)]
+  pub content_protected: bool,
+  /// Whether the window is always on top of other windows.
+  #[serde(default)]
+  pub always_on_top: bool,
 }
 
 /// Window state cached during runtime
@@ -117,12 +192,77 @@ pub struct WindowConfiguration {
 pub struct WindowState {
   pub configuration: WindowConfiguration,
   pub mode: WindowMode,
+  /// Index of the monitor the window is on.
   pub display: u32,
+  /// Stable id of the window, kept across restores unlike the label.
   pub id: u32,
   pub last_focus_time: Duration,
   pub ready_state: ReadyState,
   pub backup_path: Option<PathBuf>,
   pub folder: Option<PathBuf>,
+  /// Time the window was destroyed, `None` while the window is open.
+  pub closed_time: Option<Duration>,
+  /// Time the window was created by the plugin.
+  #[serde(default)]
+  pub created_time: Option<Duration>,
+  /// Time the page of the window first loaded.
+  #[serde(default)]
+  pub page_load_time: Option<Duration>,
+  /// Time the frontend first declared readiness with `window_ready`.
+  #[serde(default)]
+  pub ready_time: Option<Duration>,
+  /// Whether the folder is open in restricted mode until it is trusted.
+  #[serde(default)]
+  pub restricted: bool,
+  /// Logical project the window belongs to.
+  #[serde(default)]
+  pub group: Option<String>,
+  /// Last normal bounds of the window.
+  #[serde(default)]
+  pub logical_bounds: Option<LogicalWindowBounds>,
+  /// Data owned by the frontend, set through `set_window_state`.
+  #[serde(default)]
+  pub user_data: Option<JsonValue>,
+  /// Key of a window open at most once, see [`WindowOptions::singleton_key`](crate::WindowOptions::singleton_key).
+  #[serde(default)]
+  pub singleton_key: Option<String>,
+  /// Zoom factor of the page, `None` when not zoomed.
+  #[serde(default)]
+  pub zoom: Option<f64>,
+  /// Whether the document of the window has unsaved changes.
+  #[serde(default)]
+  pub dirty: bool,
+}
+
+/// Preferences of the last window a folder was open in.
+///
+/// Applied when the folder opens in a new window, so each project comes back as it was left.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FolderPreferences {
+  /// Zoom factor of the page, `None` when not zoomed.
+  pub zoom: Option<f64>,
+  /// Forced theme, `None` follows the system theme.
+  pub theme: Option<Theme>,
+  pub always_on_top: bool,
+  /// Time the preferences were last recorded.
+  pub updated_time: Duration,
+}
+
+impl FolderPreferences {
+  /// Get the preferences of a window state.
+  pub fn from_state(state: &WindowState) -> Self {
+    Self {
+      zoom: state.zoom,
+      theme: state.configuration.theme,
+      always_on_top: state.configuration.always_on_top,
+      updated_time: now(),
+    }
+  }
+
+  /// Check whether the preferences are the same, ignoring when they were recorded.
+  fn same_as(&self, other: &FolderPreferences) -> bool {
+    self.zoom == other.zoom && self.theme == other.theme && self.always_on_top == other.always_on_top
+  }
 }
 
 #[derive(Clone, Default, Deserialize, PartialEq, Serialize)]
@@ -136,80 +276,423 @@ pub struct WindowsState {
   pub opened_windows: HashMap<String, WindowState>,
   pub last_active_window: Option<LastActiveWindow>,
   pub focused_window: Option<String>,
-  pub was_restarted: bool
+  pub was_restarted: bool,
+  /// Windows open when the app was relaunched, restored on the next launch.
+  #[serde(default)]
+  pub restart_windows: Vec<String>,
+  /// Window labels by focus order, most recently focused first.
+  #[serde(default)]
+  pub focus_history: Vec<String>,
+  /// Preferences of the last window of each folder.
+  #[serde(default)]
+  pub folder_preferences: HashMap<PathBuf, FolderPreferences>,
+}
+
+impl StorageData for WindowsState {
+  fn cap(&mut self) {
+    // Keep the preferences of the most recently used folders
+    if self.folder_preferences.len() > MAX_TOTAL_WINDOW_STATES {
+      let mut by_updated_time = self.folder_preferences
+        .iter()
+        .map(|(folder, preferences)| (preferences.updated_time, folder.clone()))
+        .collect::<Vec<(Duration, PathBuf)>>();
+
+      by_updated_time.sort_by(|a, b| b.0.cmp(&a.0));
+
+      for (_updated_time, folder) in by_updated_time.into_iter().skip(MAX_TOTAL_WINDOW_STATES) {
+        self.folder_preferences.remove(&folder);
+      }
+    }
+
+    if self.opened_windows.len() <= MAX_TOTAL_WINDOW_STATES {
+      return;
+    }
+
+    let last_active_label = self.last_active_window.as_ref().map(|window| window.label.clone());
+
+    // Keep the most recently focused windows, and always the last active one
+    let mut by_focus_time = self.opened_windows
+      .iter()
+      .map(|(label, window_state)| (window_state.last_focus_time, label.clone()))
+      .collect::<Vec<(Duration, String)>>();
+
+    by_focus_time.sort_by(|a, b| b.0.cmp(&a.0));
+
+    for (_last_focus_time, label) in by_focus_time.into_iter().skip(MAX_TOTAL_WINDOW_STATES) {
+      if Some(&label) != last_active_label.as_ref() {
+        self.opened_windows.remove(&label);
+      }
+    }
+  }
 }
 
 /// Managed state for cache in memory cache of window states during runtime.
 /// 
-/// Provides in memory cache, and file back up
-#[derive(Clone, Deserialize, Serialize)]
+/// Fields of a window state changing on every focus or navigation.
+#[derive(Clone, Default)]
+struct VolatileWindowState {
+  last_focus_time: Option<Duration>,
+  ready_state: Option<ReadyState>,
+}
+
+/// Focus and ready state kept in memory, folded into the window states on close and exit.
+///
+/// Keeps focus changes from rewriting the state file.
+#[derive(Clone, Default)]
+struct VolatileState {
+  windows: HashMap<String, VolatileWindowState>,
+  /// Focus history of this session, `None` until a window is focused.
+  focus_history: Option<Vec<String>>,
+  focused_window: Option<String>,
+}
+
+/// Provides in memory cache, and file back up.
+/// The file is read on first access.
 pub struct InnerWindowsStateCache{
-  pub storage_path: PathBuf,
-  pub last_saved_storage_contents: String,
-  pub storage: WindowsState
+  storage: StorageFile<WindowsState>,
+  /// Labels of window states changed since the last `take_changes`.
+  changed: BTreeSet<String>,
+  /// Open windows by folder, rebuilt on the first lookup after a change.
+  folder_index: OnceCell<Arc<FolderIndex>>,
+  /// Index before the last change, its normalized folders are reused by the rebuild.
+  stale_folder_index: Option<Arc<FolderIndex>>,
+  volatile: VolatileState,
 }
 
 impl InnerWindowsStateCache {
 
   pub fn new(file: &Path) -> Self {
-    match read_string(file) {
-      Ok(contents) => {
-        let storage = match from_str(&contents) {
-          Ok(deserialized) => deserialized,
-          Err(e) => {
-            WindowsState::default()
-          }
-        };
+    InnerWindowsStateCache {
+      storage: StorageFile::new(file),
+      changed: BTreeSet::new(),
+      folder_index: OnceCell::new(),
+      stale_folder_index: None,
+      volatile: VolatileState::default(),
+    }
+  }
+
+  /// Cache kept in memory only, nothing is read from or written to disk.
+  pub fn in_memory() -> Self {
+    InnerWindowsStateCache {
+      storage: StorageFile::in_memory(),
+      changed: BTreeSet::new(),
+      folder_index: OnceCell::new(),
+      stale_folder_index: None,
+      volatile: VolatileState::default(),
+    }
+  }
+
+  /// Encrypt the file backing the cache, see [`StorageFile::set_cipher`].
+  pub fn set_cipher(&mut self, cipher: Arc<dyn StorageCipher>) {
+    self.storage.set_cipher(cipher);
+  }
+
+  /// Point backup paths of window states at backups moved from another directory.
+  pub fn rebase_backup_paths(&mut self, from: &Path, to: &Path) -> Result<()> {
+    let from = from.join(BACKUP_DIRNAME);
+    let to = to.join(BACKUP_DIRNAME);
+
+    let mut changed = Vec::new();
+
+    for (label, state) in self.state_mut().opened_windows.iter_mut() {
+      for backup_path in [&mut state.backup_path, &mut state.configuration.backup_path] {
+        let rebased = backup_path.as_ref()
+          .and_then(|path| path.strip_prefix(&from).ok())
+          .map(|relative| to.join(relative));
 
-        InnerWindowsStateCache {
-          storage_path: file.to_path_buf(), 
-          last_saved_storage_contents: contents,
-          storage: storage
+        if rebased.is_some() {
+          *backup_path = rebased;
+          changed.push(label.clone());
         }
-      },
-      Err(e) => {
-        InnerWindowsStateCache {
-          storage_path: PathBuf::new(), 
-          last_saved_storage_contents: "".to_string(),
-          storage: WindowsState::default()
-        }        
       }
     }
-  }  
+
+    for label in changed {
+      self.mark_changed(&label);
+    }
+
+    self.save()
+  }
+
+  /// Record a change to the state of a window.
+  pub fn mark_changed(&mut self, label: &str) {
+    self.changed.insert(label.to_string());
+  }
+
+  pub fn has_changes(&self) -> bool {
+    !self.changed.is_empty()
+  }
+
+  /// Take labels of window states changed since the last call.
+  pub fn take_changes(&mut self) -> Vec<String> {
+    std::mem::take(&mut self.changed).into_iter().collect()
+  }
+
+  /// Replace all window states, e.g. with an imported snapshot.
+  pub fn replace(&mut self, mut state: WindowsState) -> Result<()> {
+    state.cap();
+
+    let labels = self.state().opened_windows
+      .keys()
+      .chain(state.opened_windows.keys())
+      .cloned()
+      .collect::<Vec<String>>();
+
+    *self.state_mut() = state;
+    self.volatile = VolatileState::default();
+    self.changed.extend(labels);
+
+    self.save()
+  }
+
+  pub fn storage_path(&self) -> &Path {
+    self.storage.path()
+  }
+
+  pub fn storage_status(&self) -> StorageStatus {
+    self.storage.status()
+  }
 
   pub fn storage(&self) -> &WindowsState {
-    &self.storage
+    self.storage.get()
   }  
 
   pub fn state(&self) -> &WindowsState {
-    &self.storage
+    self.storage.get()
   }
 
   pub fn state_mut(&mut self) -> &mut WindowsState {
-    &mut self.storage
+    if let Some(folder_index) = self.folder_index.take() {
+      self.stale_folder_index = Some(folder_index);
+    }
+
+    self.storage.get_mut()
+  }
+
+  /// Get open windows by folder.
+  ///
+  /// Kept between lookups and rebuilt after the state changes.
+  pub fn folder_index(&self) -> Arc<FolderIndex> {
+    self.folder_index
+      .get_or_init(|| Arc::new(FolderIndex::new(self.state(), self.stale_folder_index.as_deref())))
+      .clone()
   }
 
   pub fn get_state(&self) -> WindowState {WindowState::default()}
   pub fn get_state_mut(&self) -> WindowState {WindowState::default()}
   pub fn set_state(&self) -> Result<()> {Ok(())}
-  pub fn handle_destroyed_window(&mut self, label: &str) {}
-  pub fn handle_focused_window(&mut self, label: &str, focus: &bool) {}
+  pub fn handle_destroyed_window(&mut self, label: &str) {
+    self.fold_volatile();
+
+    if let Some(state) = self.state_mut().opened_windows.get_mut(label) {
+      state.closed_time = Some(now());
+      self.mark_changed(label);
+    }
+
+    if let Err(e) = self.save() {
+      log_error(&e);
+    }
+  }
+  /// Record the focus in memory, the state file is updated on close and exit.
+  pub fn handle_focused_window(&mut self, label: &str, focus: &bool) {
+    if !*focus || !self.state().opened_windows.contains_key(label) {
+      return;
+    }
+
+    let mut focus_history = match self.volatile.focus_history.take() {
+      Some(focus_history) => focus_history,
+      None => self.state().focus_history.clone(),
+    };
+
+    focus_history.retain(|focused| focused != label);
+    focus_history.insert(0, label.to_string());
+    focus_history.truncate(MAX_TOTAL_WINDOW_STATES);
+
+    self.volatile.focus_history = Some(focus_history);
+    self.volatile.windows.entry(label.to_string()).or_default().last_focus_time = Some(now());
+
+    // Kept on blur, so it names the last focused window on exit
+    self.volatile.focused_window = Some(label.to_string());
+
+    self.mark_changed(label);
+  }
+
+  /// Get the time a window was last focused.
+  pub fn last_focus_time(&self, label: &str) -> Option<Duration> {
+    self.volatile.windows.get(label)
+      .and_then(|volatile| volatile.last_focus_time)
+      .or_else(|| self.state().opened_windows.get(label).map(|state| state.last_focus_time))
+  }
+
+  /// Record the focus time of a window without focusing it.
+  pub fn set_last_focus_time(&mut self, label: &str) -> Result<()> {
+    if !self.state().opened_windows.contains_key(label) {
+      return Err(Error::WindowStateWithLabelNotFound(label.to_string()));
+    }
+
+    self.volatile.windows.entry(label.to_string()).or_default().last_focus_time = Some(now());
+
+    Ok(())
+  }
+
+  pub fn is_ready(&self, label: &str) -> bool {
+    self.get_item(label).map_or(false, |state| state.ready_state == ReadyState::Ready)
+  }
+
+  pub fn set_ready_state(&mut self, label: &str, ready_state: ReadyState) -> Result<()> {
+    if !self.state().opened_windows.contains_key(label) {
+      return Err(Error::WindowStateWithLabelNotFound(label.to_string()));
+    }
+
+    self.volatile.windows.entry(label.to_string()).or_default().ready_state = Some(ready_state);
+
+    Ok(())
+  }
+
+  /// Apply focus and ready state kept in memory to a window state.
+  fn apply_volatile_window(&self, label: &str, state: &mut WindowState) {
+    if let Some(volatile) = self.volatile.windows.get(label) {
+      if let Some(last_focus_time) = volatile.last_focus_time {
+        state.last_focus_time = last_focus_time;
+      }
+
+      if let Some(ready_state) = &volatile.ready_state {
+        state.ready_state = ready_state.clone();
+      }
+    }
+  }
+
+  /// Get the window states with focus and ready state kept in memory applied.
+  pub fn folded_state(&self) -> WindowsState {
+    let mut state = self.state().clone();
+
+    for (label, window_state) in state.opened_windows.iter_mut() {
+      self.apply_volatile_window(label, window_state);
+    }
+
+    if let Some(focus_history) = &self.volatile.focus_history {
+      state.focus_history = focus_history.clone();
+    }
+
+    if let Some(focused) = &self.volatile.focused_window {
+      if let Some(window_state) = state.opened_windows.get(focused) {
+        state.last_active_window = Some(LastActiveWindow {
+          label: focused.clone(),
+          state: window_state.clone(),
+        });
+      }
+
+      state.focused_window = Some(focused.clone());
+    }
+
+    state
+  }
+
+  /// Fold focus and ready state kept in memory into the window states, e.g. before writing them.
+  pub fn fold_volatile(&mut self) {
+    let state = self.folded_state();
+
+    if state != *self.state() {
+      *self.state_mut() = state;
+    }
+  }
+
+  /// Refresh the state kept with the last active window.
+  pub fn update_last_active_window(&mut self) {
+    let state = self.state_mut();
+
+    if let Some(last_active_window) = state.last_active_window.as_mut() {
+      if let Some(window_state) = state.opened_windows.get(&last_active_window.label) {
+        last_active_window.state = window_state.clone();
+      }
+    }
+  }
+
+  pub fn handle_bounds_changed(&mut self, label: &str, bounds: Option<LogicalWindowBounds>, display: u32) {
+    if let Some(window_state) = self.state_mut().opened_windows.get_mut(label) {
+      if bounds.is_some() {
+        window_state.logical_bounds = bounds;
+      }
+
+      window_state.display = display;
+      self.mark_changed(label);
+    }
+  }
+
+  /// Get the id for a window opening `configuration`.
+  ///
+  /// Reuses the id of a closed window with the same folder or url so ids survive restores,
+  /// otherwise allocates a new one. Ids start at 1, 0 means unassigned.
+  pub fn get_window_id(&self, configuration: &WindowConfiguration) -> u32 {
+    let opened_windows = &self.state().opened_windows;
+
+    let restored_id = opened_windows
+      .values()
+      .filter(|state| state.closed_time.is_some() && state.id != 0)
+      .find(|state| {
+        let cached = &state.configuration;
+
+        (configuration.folder.is_some() && cached.folder == configuration.folder)
+          || (configuration.url.is_some() && cached.url == configuration.url)
+      })
+      .map(|state| state.id);
+
+    // A restored id may already be taken by an open window on the same folder
+    match restored_id {
+      Some(id) if !opened_windows.values().any(|state| state.closed_time.is_none() && state.id == id) => id,
+      _ => opened_windows.values().map(|state| state.id).max().unwrap_or(0) + 1,
+    }
+  }
+
+  /// Get window labels by focus order, most recently focused first.
+  ///
+  /// May contain windows that are closed.
+  pub fn focus_history(&self) -> &[String] {
+    match &self.volatile.focus_history {
+      Some(focus_history) => focus_history,
+      None => &self.state().focus_history,
+    }
+  }
   pub fn handle_close_window(&mut self, label: &str) {}
 
   pub fn get_item(&self, key: &str) -> Option<WindowState> {
-    match self.storage.opened_windows.get(key) {
-      Some(window) => Some(window.clone()),
-      None => None
+    let mut state = self.state().opened_windows.get(key)?.clone();
+
+    self.apply_volatile_window(key, &mut state);
+
+    Some(state)
+  }
+
+  /// Remember the preferences of a window for its folder.
+  fn remember_folder_preferences(&mut self, state: &WindowState) {
+    let folder = match &state.folder {
+      Some(folder) => folder,
+      None => return,
+    };
+
+    let preferences = FolderPreferences::from_state(state);
+
+    let unchanged = self.state().folder_preferences
+      .get(folder)
+      .map_or(false, |stored| stored.same_as(&preferences));
+
+    if !unchanged {
+      self.state_mut().folder_preferences.insert(folder.clone(), preferences);
     }
   }
 
+  /// Get the preferences of the last window a folder was open in.
+  pub fn folder_preferences(&self, folder: &Path) -> Option<FolderPreferences> {
+    self.state().folder_preferences.get(folder).cloned()
+  }
+
+  /// Set the state of a window, replacing focus and ready state kept in memory.
   pub fn set_item(&mut self, key: &str, data: WindowState) -> Result<()> {
-    match self.storage.opened_windows.insert(key.to_string(), data) {
-      Some(state) => {
-        self.save()
-      },
-      None => Err(Error::WindowStateWithLabelNotFound(key.to_string()))
-    }
+    self.remember_folder_preferences(&data);
+    self.volatile.windows.remove(key);
+    self.state_mut().opened_windows.insert(key.to_string(), data);
+    self.mark_changed(key);
+    self.save()
   }
   
   pub fn set_items<I>(&mut self, items: I) -> Result<()> 
@@ -220,20 +703,18 @@ impl InnerWindowsStateCache {
 
     for (key, data) in items {
       // Shortcut for data that did not change
-      if let Some(stored_data) = self.storage.opened_windows.get(&key) {
+      if let Some(stored_data) = self.state().opened_windows.get(&key) {
         if *stored_data == data {
           continue;
         }
       }
-      // Otherwise add an item
-      else {
-        match self.storage.opened_windows.insert(key, data) {
-          Some(state) => {
-            save = true;
-          },
-          None => {}
-        };
-      }
+
+      // Otherwise add or update an item
+      self.remember_folder_preferences(&data);
+      self.mark_changed(&key);
+      self.volatile.windows.remove(&key);
+      self.state_mut().opened_windows.insert(key, data);
+      save = true;
     }
 
     if !save {
@@ -245,89 +726,230 @@ impl InnerWindowsStateCache {
   }
   
   pub fn remove_item(&mut self, key: &str) -> Result<()> {
-    match self.storage.opened_windows.remove(key) {
-      Some(state) => Ok(()),
+    self.volatile.windows.remove(key);
+
+    match self.state_mut().opened_windows.remove(key) {
+      Some(_state) => {
+        self.mark_changed(key);
+        Ok(())
+      },
       None => Err(Error::WindowStateWithLabelNotFound(key.to_string()))
     }
   }
   
+  /// Remove states of windows closed a long time ago.
+  ///
+  /// Drops closed windows older than `max_age` and the oldest closed windows beyond `max_entries`.
+  /// Open windows and the last active window are always kept.
+  /// Returns the number of removed states.
+  pub fn prune(&mut self, max_age: Option<Duration>, max_entries: Option<usize>) -> Result<usize> {
+    let now = now();
+    let last_active_label = self.state().last_active_window.as_ref().map(|window| window.label.clone());
+    let labels = self.state().opened_windows.keys().cloned().collect::<Vec<String>>();
+    let opened_windows = &mut self.state_mut().opened_windows;
+    let count = opened_windows.len();
+
+    let is_prunable = |label: &String, state: &WindowState| {
+      state.closed_time.is_some() && Some(label) != last_active_label.as_ref()
+    };
+
+    if let Some(max_age) = max_age {
+      opened_windows.retain(|label, state| {
+        !is_prunable(label, state) || now.saturating_sub(state.closed_time.unwrap_or(now)) <= max_age
+      });
+    }
+
+    if let Some(max_entries) = max_entries {
+      let mut closed_windows = opened_windows
+        .iter()
+        .filter(|(label, state)| is_prunable(label, state))
+        .map(|(label, state)| (state.closed_time, label.clone()))
+        .collect::<Vec<(Option<Duration>, String)>>();
+
+      let excess = opened_windows.len().saturating_sub(max_entries).min(closed_windows.len());
+
+      // Oldest closed first
+      closed_windows.sort();
+
+      for (_closed_time, label) in closed_windows.into_iter().take(excess) {
+        opened_windows.remove(&label);
+      }
+    }
+
+    let removed = count - opened_windows.len();
+
+    if removed > 0 {
+      let state = self.state_mut();
+      let opened_windows = &state.opened_windows;
+
+      let removed_labels = labels
+        .into_iter()
+        .filter(|label| !opened_windows.contains_key(label))
+        .collect::<Vec<String>>();
+
+      state.focus_history.retain(|label| opened_windows.contains_key(label));
+
+      if let Some(focus_history) = self.volatile.focus_history.as_mut() {
+        focus_history.retain(|label| !removed_labels.contains(label));
+      }
+
+      self.volatile.windows.retain(|label, _volatile| !removed_labels.contains(label));
+
+      self.changed.extend(removed_labels);
+
+      self.save()?;
+    }
+
+    Ok(removed)
+  }
+  
   pub fn close(&mut self) -> Result<()> {
     self.save()
   }
   
-  fn save(&mut self) -> Result<()> {
-    let serialized_database = to_string(&self.storage).unwrap_or("".to_string());
+  /// Point states of a renamed folder to its new path.
+  pub fn rename_folder(&mut self, from: &Path, to: &Path) -> Result<()> {
+    let mut changed = Vec::new();
 
-		// Return early if the database has not changed
-    if self.last_saved_storage_contents.eq(&serialized_database) {
-      Ok(())
-    }
-    // Write to disk
-    else {
-      match File::create(&self.storage_path) {
-        Ok(mut file) => {
-          file.write_all(serialized_database.as_bytes())?;
-          self.last_saved_storage_contents = serialized_database;
+    for (label, state) in self.state_mut().opened_windows.iter_mut() {
+      let previous = state.clone();
 
-          Ok(())
-        },
-        Err(e) => {
-          Ok(())
+      if let Some(folder) = state.folder.as_ref().and_then(|folder| rebase_path(folder, from, to)) {
+        state.folder = Some(folder.clone());
+        state.configuration.folder = Some(folder);
+      }
+
+      for file in state.configuration.files_to_open_or_create.iter_mut() {
+        if let Some(rebased) = rebase_path(file, from, to) {
+          *file = rebased;
         }
       }
+
+      if *state != previous {
+        changed.push(label.clone());
+      }
     }
+
+    self.changed.extend(changed);
+
+    self.save()
   }
-}
 
-impl Default for InnerWindowsStateCache {
-  fn default() -> Self {
-    if let Some(dir) = data_dir() {
-      match read_string(dir.join(STATE_FILENAME)) {
-        Ok(contents) => {
-          let storage = match from_str(&contents) {
-            Ok(deserialized) => deserialized,
-            Err(e) => {
-              WindowsState::default()
-            }
-          };
-  
-          InnerWindowsStateCache {
-            storage_path: dir.join(STATE_FILENAME), 
-            last_saved_storage_contents: contents,
-            storage: storage
-          }
-        },
-        Err(e) => {
-          InnerWindowsStateCache {
-            storage_path: PathBuf::new(), 
-            last_saved_storage_contents: "".to_string(),
-            storage: WindowsState::default()
-          }        
-        }
+  /// Drop a deleted folder from the states of windows that had it open.
+  pub fn remove_folder(&mut self, folder: &Path) -> Result<()> {
+    let mut changed = Vec::new();
+
+    for (label, state) in self.state_mut().opened_windows.iter_mut() {
+      let previous = state.clone();
+
+      if state.folder.as_ref().map_or(false, |state_folder| state_folder.starts_with(folder)) {
+        state.folder = None;
+        state.configuration.folder = None;
       }
-    }else {
-      InnerWindowsStateCache {
-        storage_path: PathBuf::new(), 
-        last_saved_storage_contents: "".to_string(),
-        storage: WindowsState::default(),
+
+      state.configuration.files_to_open_or_create.retain(|file| !file.starts_with(folder));
+
+      if *state != previous {
+        changed.push(label.clone());
       }
     }
+
+    self.changed.extend(changed);
+
+    self.save()
+  }
+
+  pub fn save(&mut self) -> Result<()> {
+    self.storage.save()
+  }
+}
+
+/// Replace the `from` prefix of a path with `to`.
+///
+/// Returns `None` if the path is not inside `from`.
+fn rebase_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+  path.strip_prefix(from).ok().map(|relative| to.join(relative))
+}
+
+/// Get the form of a path used to compare it with other paths.
+///
+/// `.` and `..` are resolved lexically, symlinks of the longest existing ancestor are resolved
+/// so paths that don't exist yet still match the folder they will be created in.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+
+  for component in path.components() {
+    match component {
+      Component::CurDir => {},
+      Component::ParentDir => match normalized.components().next_back() {
+        Some(Component::Normal(_)) => {
+          normalized.pop();
+        },
+        // Parent of the root is the root
+        Some(Component::RootDir) | Some(Component::Prefix(_)) => {},
+        _ => normalized.push(component),
+      },
+      _ => normalized.push(component),
+    }
+  }
+
+  let mut missing = Vec::new();
+  let mut existing = normalized.as_path();
+
+  loop {
+    if let Ok(canonical) = existing.canonicalize() {
+      return missing.iter().rev().fold(canonical, |path, name| path.join(name));
+    }
+
+    match (existing.parent(), existing.file_name()) {
+      (Some(parent), Some(name)) => {
+        missing.push(name);
+        existing = parent;
+      },
+      _ => return normalized,
+    }
+  }
+}
+
+impl Default for InnerWindowsStateCache {
+  fn default() -> Self {
+    InnerWindowsStateCache::in_memory()
   }
 }
 
 /// Managed state for window states.
-#[derive(Default, Deserialize, Serialize)]
+#[derive(Default)]
 pub struct WindowsStateCache(pub RwLock<InnerWindowsStateCache>);
 
+impl WindowsStateCache {
+  pub fn in_memory() -> Self {
+    Self(RwLock::new(InnerWindowsStateCache::in_memory()))
+  }
+
+  /// Cache backed by its file in the directory, encrypted if a cipher is set.
+  pub fn open(dir: &Path, cipher: Option<Arc<dyn StorageCipher>>) -> Self {
+    let mut cache = InnerWindowsStateCache::new(&dir.join(STATE_FILENAME));
+
+    if let Some(cipher) = cipher {
+      cache.set_cipher(cipher);
+    }
+
+    Self(RwLock::new(cache))
+  }
+}
+
 /// Folder backup info.
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct FolderBackupInfo {
   pub window: String,
-  pub folder: Option<PathBuf>
+  pub folder: Option<PathBuf>,
+  /// Whether the folder existed on the last validation.
+  #[serde(default = "default_exists")]
+  pub exists: bool,
 }
 
 /// Empty window backup info.
-#[derive(Clone, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct EmptyWindowBackupInfo {
   pub window: String,
   pub backup_folder: Option<PathBuf>,
@@ -341,6 +963,7 @@ pub struct WindowsBackup {
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct InnerWindowsBackupCache {
+  /// Directory of the backups, empty if they are kept in memory only.
   pub backup_path: PathBuf,
   pub backups: WindowsBackup
 }
@@ -370,7 +993,35 @@ impl InnerWindowsBackupCache {
     }
   }
 
-  pub fn add_folder_backup(&mut self, folder: &PathBuf, window: &str) -> PathBuf {
+  /// Cache kept in memory only, backup folders are never created.
+  pub fn in_memory() -> Self {
+    InnerWindowsBackupCache {
+      backup_path: PathBuf::new(),
+      backups: WindowsBackup::default()
+    }
+  }
+
+  /// Read the backup metadata of the directory, decrypted with the cipher if it is encrypted.
+  ///
+  /// Backup folders hold files written by the app, they are encrypted by the app if needed.
+  pub fn open(dir: &Path, cipher: Option<&dyn StorageCipher>) -> Self {
+    let backups = read_contents(&dir.join(BACKUP_FILENAME), cipher)
+      .ok()
+      .and_then(|contents| from_str(&contents).ok())
+      .unwrap_or_default();
+
+    InnerWindowsBackupCache {
+      backup_path: dir.to_path_buf(),
+      backups
+    }
+  }
+
+  /// Whether backups are kept in memory only.
+  pub fn is_in_memory(&self) -> bool {
+    self.backup_path.as_os_str().is_empty()
+  }
+
+  pub fn add_folder_backup(&mut self, folder: &PathBuf, window: &str) -> Result<PathBuf> {
 		if !self.backups.folders.iter().any(
       |info| info.folder.as_ref().and_then(
         |backup_folder| if backup_folder.eq(folder) {Some(true)} else {None}
@@ -378,15 +1029,34 @@ impl InnerWindowsBackupCache {
     ) {
 			self.backups.folders.push(FolderBackupInfo {
         window: window.to_string(),
-        folder: Some(folder.clone())
+        folder: Some(folder.clone()),
+        exists: true,
       });
 			self.save();
 		}
 
-	  self.get_backup_path(&self.get_folder_hash(folder))
+	  self.migrate_folder_hash(folder)?;
+
+	  self.create_backup_folder(&self.get_folder_hash(folder))
   }
 
-  pub fn add_empty_window_backup(&mut self, backup_folder_candidate: &Option<PathBuf>, window: &str) -> PathBuf {
+  /// Hand the folder backup over to another window.
+  pub fn move_folder_backup(&mut self, folder: &PathBuf, window: &str) {
+    let mut changed = false;
+
+    for info in self.backups.folders.iter_mut() {
+      if info.folder.as_ref() == Some(folder) && info.window != window {
+        info.window = window.to_string();
+        changed = true;
+      }
+    }
+
+    if changed {
+      self.save();
+    }
+  }
+
+  pub fn add_empty_window_backup(&mut self, backup_folder_candidate: &Option<PathBuf>, window: &str) -> Result<PathBuf> {
 		// Generate a new folder if this is a new empty workspace
     let backup_folder = match backup_folder_candidate {
       Some(folder) => folder.clone(),
@@ -407,58 +1077,188 @@ impl InnerWindowsBackupCache {
 			self.save();
 		}
 
-		self.get_backup_path(&backup_folder)
+		self.create_backup_folder(&backup_folder)
+  }
+
+  /// Remove the backups of a window and their folders.
+  ///
+  /// Returns the number of removed backups.
+  pub fn remove_window_backups(&mut self, window: &str) -> Result<usize> {
+    let mut backup_folders = self.backups.folders
+      .iter()
+      .filter(|info| info.window == window)
+      .filter_map(|info| info.folder.as_ref().map(|folder| self.get_folder_hash(folder)))
+      .collect::<Vec<PathBuf>>();
+
+    backup_folders.extend(
+      self.backups.empty_windows
+        .iter()
+        .filter(|info| info.window == window)
+        .filter_map(|info| info.backup_folder.clone())
+    );
+
+    self.backups.folders.retain(|info| info.window != window);
+    self.backups.empty_windows.retain(|info| info.window != window);
+    self.save();
+
+    if self.is_in_memory() {
+      return Ok(backup_folders.len());
+    }
+
+    for backup_folder in &backup_folders {
+      let backup_path = self.get_backup_path(backup_folder);
+
+      // Never remove anything outside the backups directory
+      if backup_path.starts_with(self.get_backups_dir()) && backup_path.is_dir() {
+        remove_dir_all(&backup_path)?;
+      }
+    }
+
+    Ok(backup_folders.len())
+  }
+
+  /// Create the backup folder if it is missing.
+  fn create_backup_folder(&self, folder: &PathBuf) -> Result<PathBuf> {
+    let backup_path = self.get_backup_path(folder);
+
+    if !self.is_in_memory() {
+      create_dir_all(&backup_path)?;
+    }
+
+    Ok(backup_path)
   }
   
+  /// Check folder backups against the filesystem.
+  ///
+  /// Removes backups of missing folders when `remove` is set, otherwise marks them as missing.
+  /// Returns the number of missing folders.
+  pub fn validate(&mut self, remove: bool) -> usize {
+    for info in self.backups.folders.iter_mut() {
+      info.exists = info.folder.as_ref().map_or(false, |folder| folder.exists());
+    }
+
+    let missing = self.backups.folders.iter().filter(|info| !info.exists).count();
+
+    if remove {
+      self.backups.folders.retain(|info| info.exists);
+    }
+
+    self.save();
+
+    missing
+  }
+
+  /// Get a new empty window id, unique across restarts.
+  ///
+  /// Creation time in milliseconds followed by random characters.
   pub fn get_random_empty_window_id(&self) -> PathBuf {
-    let id = COUNTER.fetch_add(1, Ordering::Relaxed).to_string();
+    let random = uuid::Uuid::new_v4().simple().to_string();
+
+    PathBuf::from(format!("{}-{}", now().as_millis(), &random[..8]))
+  }
+
+  /// Give an empty window backup with a numeric id of earlier versions a unique id.
+  ///
+  /// Numeric ids restarted on every launch and collided with restored backups.
+  /// Renames the backup folder and returns its new path, `None` if the backup needs no migration.
+  pub fn migrate_empty_window_id(&mut self, backup_path: &Path) -> Result<Option<PathBuf>> {
+    let is_legacy = backup_path.parent() == Some(self.get_backups_dir().as_path())
+      && backup_path.file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| !name.is_empty() && name.chars().all(|c| c.is_ascii_digit()));
+
+    if !is_legacy || self.is_in_memory() {
+      return Ok(None);
+    }
+
+    let id = self.get_random_empty_window_id();
+    let migrated_path = self.get_backup_path(&id);
+
+    if backup_path.is_dir() {
+      rename(backup_path, &migrated_path)
+      .map_err(|source| Error::MigrationFailed { from: backup_path.to_path_buf(), to: migrated_path.clone(), source })?;
+    }
+
+    let legacy_id = backup_path.file_name().map(PathBuf::from);
+
+    for info in self.backups.empty_windows.iter_mut() {
+      if info.backup_folder.as_deref() == Some(backup_path) || info.backup_folder == legacy_id {
+        info.backup_folder = Some(id.clone());
+      }
+    }
+
+    self.save();
 
-    PathBuf::from(id)
+    Ok(Some(migrated_path))
   }
   
+  /// Get the backup folder name of a folder.
+  ///
+  /// Sha-256 of the canonical path, stable across processes and Rust versions.
   pub fn get_folder_hash(&self, folder: &PathBuf) -> PathBuf {
+    let folder = folder.canonicalize().unwrap_or_else(|_e| folder.clone());
+
+    let digest = Sha256::digest(folder.to_string_lossy().as_bytes());
+
+    PathBuf::from(digest.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+  }
+
+  /// Get the backup folder name of a folder used by earlier versions.
+  fn get_legacy_folder_hash(&self, folder: &PathBuf) -> PathBuf {
     let mut s = DefaultHasher::new();
     folder.hash(&mut s);
     PathBuf::from(s.finish().to_string())
   }
 
+  /// Rename the backup folder of a folder from the legacy hash.
+  ///
+  /// Legacy hashes are only stable within a Rust version, folders hashed by another version aren't found.
+  fn migrate_folder_hash(&self, folder: &PathBuf) -> Result<()> {
+    if self.is_in_memory() {
+      return Ok(());
+    }
+
+    let legacy_path = self.get_backup_path(&self.get_legacy_folder_hash(folder));
+    let backup_path = self.get_backup_path(&self.get_folder_hash(folder));
+
+    if legacy_path.is_dir() && !backup_path.exists() {
+      rename(&legacy_path, &backup_path)?;
+    }
+
+    Ok(())
+  }
+
+  /// Directory holding the backup folders of all windows.
+  pub fn get_backups_dir(&self) -> PathBuf {
+    self.backup_path.join(BACKUP_DIRNAME)
+  }
+
   pub fn get_backup_path(&self, folder: &PathBuf) -> PathBuf {
-    self.backup_path.join(folder)
+    self.get_backups_dir().join(folder)
+  }
+
+  /// Status of the backup metadata file, read eagerly on creation.
+  pub fn storage_status(&self) -> StorageStatus {
+    StorageStatus {
+      loaded: true,
+      ..StorageStatus::of(&self.backup_path)
+    }
+  }
+
+  /// Replace all backup metadata, e.g. with an imported snapshot.
+  ///
+  /// Backup folders on disk are left untouched.
+  pub fn replace(&mut self, backups: WindowsBackup) {
+    self.backups = backups;
+    self.save();
   }
   
-  fn save(&self) {}
+  pub fn save(&self) {}
 }
 
 impl Default for InnerWindowsBackupCache {
   fn default() -> Self {
-    if let Some(dir) = data_dir() {
-      match read_string(dir.join(BACKUP_FILENAME)) {
-        Ok(contents) => {
-          let backups = match from_str(&contents) {
-            Ok(deserialized) => deserialized,
-            Err(e) => {
-              WindowsBackup::default()
-            }
-          };
-  
-          InnerWindowsBackupCache {
-            backup_path: dir,
-            backups
-          }
-        },
-        Err(e) => {
-          InnerWindowsBackupCache {
-            backup_path: dir,
-            backups: WindowsBackup::default()
-          }        
-        }
-      }
-    }else {
-      InnerWindowsBackupCache {
-        backup_path: PathBuf::new(),
-        backups: WindowsBackup::default()
-      } 
-    }
+    InnerWindowsBackupCache::in_memory()
   }
 }
 
@@ -466,6 +1266,17 @@ impl Default for InnerWindowsBackupCache {
 #[derive(Default)]
 pub struct WindowsBackupCache(pub RwLock<InnerWindowsBackupCache>);
 
+impl WindowsBackupCache {
+  pub fn in_memory() -> Self {
+    Self(RwLock::new(InnerWindowsBackupCache::in_memory()))
+  }
+
+  /// Cache of the backups in the directory, with its metadata file decrypted by the cipher if set.
+  pub fn open(dir: &Path, cipher: Option<Arc<dyn StorageCipher>>) -> Self {
+    Self(RwLock::new(InnerWindowsBackupCache::open(dir, cipher.as_deref())))
+  }
+}
+
 #[derive(Default)]
 pub struct RecentPath {
   pub label: Option<String>,
@@ -478,81 +1289,214 @@ pub struct RecentPath {
 pub struct RecentFile {
   pub label: String,
   pub file: PathBuf,
-  pub window: String
+  pub window: String,
+  /// Whether the file existed on the last validation.
+  #[serde(default = "default_exists")]
+  pub exists: bool,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct RecentFolder {
   pub label: String,
   pub folder: PathBuf,
-  pub window: String
+  pub window: String,
+  /// Whether the folder existed on the last validation.
+  #[serde(default = "default_exists")]
+  pub exists: bool,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct RecentlyOpened {
+  pub files: Vec<RecentFile>,
+  pub folders: Vec<RecentFolder>
+}
+
+/// Entries are assumed to exist until validated.
+fn default_exists() -> bool {
+  true
+}
+
+impl RecentlyOpened {
+  /// Get paths of all entries and whether they exist.
+  fn entries(&self) -> Vec<(PathBuf, bool)> {
+    self.files
+      .iter()
+      .map(|recent| (recent.file.clone(), recent.exists))
+      .chain(self.folders.iter().map(|recent| (recent.folder.clone(), recent.exists)))
+      .collect()
+  }
+}
+
+/// Changes to recently opened files and folders.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RecentsChangedPayload {
+  pub added: Vec<PathBuf>,
+  pub removed: Vec<PathBuf>,
+  /// Entries marked as missing.
+  pub missing: Vec<PathBuf>,
+}
+
+impl RecentsChangedPayload {
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.missing.is_empty()
+  }
+
+  fn add(&mut self, path: &PathBuf) {
+    self.removed.retain(|removed| removed != path);
+
+    if !self.added.contains(path) {
+      self.added.push(path.clone());
+    }
+  }
+
+  fn remove(&mut self, path: &PathBuf) {
+    self.added.retain(|added| added != path);
+    self.missing.retain(|missing| missing != path);
+
+    if !self.removed.contains(path) {
+      self.removed.push(path.clone());
+    }
+  }
+
+  fn mark_missing(&mut self, path: &PathBuf) {
+    if !self.missing.contains(path) {
+      self.missing.push(path.clone());
+    }
+  }
 }
 
-#[derive(Clone, Default, Deserialize, Serialize)]
-pub struct RecentlyOpened {
-  files: Vec<RecentFile>,
-  folders: Vec<RecentFolder>
+impl StorageData for RecentlyOpened {
+  fn cap(&mut self) {
+    self.folders.truncate(MAX_TOTAL_RECENT_ENTRIES.into());
+    self.files.truncate(MAX_TOTAL_RECENT_ENTRIES.into());
+  }
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+/// Managed state for cache in memory cache of recently opened files and folders.
+///
+/// The file is read on first access.
 pub struct InnerWindowsRecentsCache {
-  pub recents_path: PathBuf,
-  last_saved_recents_contents: String,
-  pub recents: RecentlyOpened
+  storage: StorageFile<RecentlyOpened>,
+  /// Changes since the last `take_changes`.
+  changes: RecentsChangedPayload,
 }
 
 impl InnerWindowsRecentsCache {
   pub fn new(file: &Path) -> Self {
-    match read_string(file) {
-      Ok(contents) => {
-        let recents = match from_str(&contents) {
-          Ok(deserialized) => deserialized,
-          Err(e) => {
-            RecentlyOpened::default()
-          }
-        };
+    InnerWindowsRecentsCache {
+      storage: StorageFile::new(file),
+      changes: RecentsChangedPayload::default(),
+    }
+  }
 
-        InnerWindowsRecentsCache {
-          last_saved_recents_contents: contents,
-          recents_path: file.to_path_buf(),
-          recents
-        }
-      },
-      Err(e) => {
-        InnerWindowsRecentsCache {
-          last_saved_recents_contents: "".to_string(),
-          recents_path: PathBuf::new(),
-          recents: RecentlyOpened::default()
-        }        
-      }
-    }  
+  /// Cache kept in memory only, nothing is read from or written to disk.
+  pub fn in_memory() -> Self {
+    InnerWindowsRecentsCache {
+      storage: StorageFile::in_memory(),
+      changes: RecentsChangedPayload::default(),
+    }
   }
-  
-  pub fn add_recents(&mut self, recents: Vec<PathToOpen>) -> Result<()> {
-    for recent in recents {
-      if recent.folder.is_some() {
-        self.recents.folders.push(RecentFolder {
-          label: recent.label.unwrap(),
-          folder: recent.folder.unwrap(),
-          window: recent.window.unwrap()
-        });
+
+  /// Encrypt the file backing the cache, see [`StorageFile::set_cipher`].
+  pub fn set_cipher(&mut self, cipher: Arc<dyn StorageCipher>) {
+    self.storage.set_cipher(cipher);
+  }
+
+  pub fn has_changes(&self) -> bool {
+    !self.changes.is_empty()
+  }
+
+  /// Take changes made since the last call.
+  pub fn take_changes(&mut self) -> RecentsChangedPayload {
+    std::mem::take(&mut self.changes)
+  }
+
+  /// Replace all recents, e.g. with an imported snapshot.
+  pub fn replace(&mut self, mut recents: RecentlyOpened) -> Result<()> {
+    recents.cap();
+
+    self.track(|stored| *stored = recents);
+
+    self.save()
+  }
+
+  /// Apply a change to the recents, recording the entries added, removed or gone missing.
+  fn track<T>(&mut self, change: impl FnOnce(&mut RecentlyOpened) -> T) -> T {
+    let before = self.recents().entries();
+    let res = change(self.recents_mut());
+    let after = self.recents().entries();
+
+    for (path, exists) in &after {
+      match before.iter().find(|(before_path, _exists)| before_path == path) {
+        None => self.changes.add(path),
+        Some((_path, true)) if !exists => self.changes.mark_missing(path),
+        _ => {},
       }
-      else {
-        self.recents.files.push(RecentFile {
-          label: recent.label.unwrap(),
-          file: recent.file.unwrap(),
-          window: recent.window.unwrap()
-        });
+    }
+
+    for (path, _exists) in &before {
+      if !after.iter().any(|(after_path, _exists)| after_path == path) {
+        self.changes.remove(path);
       }
     }
 
-		if self.recents.folders.len() > MAX_TOTAL_RECENT_ENTRIES.into() {
-			self.recents.folders.truncate(MAX_TOTAL_RECENT_ENTRIES.into());
-		}
+    res
+  }
 
-		if self.recents.files.len() > MAX_TOTAL_RECENT_ENTRIES.into() {
-			self.recents.files.truncate(MAX_TOTAL_RECENT_ENTRIES.into());
-		}
+  pub fn recents_path(&self) -> &Path {
+    self.storage.path()
+  }
+
+  pub fn storage_status(&self) -> StorageStatus {
+    self.storage.status()
+  }
+
+  pub fn recents(&self) -> &RecentlyOpened {
+    self.storage.get()
+  }
+
+  pub fn recents_mut(&mut self) -> &mut RecentlyOpened {
+    self.storage.get_mut()
+  }
+  
+  /// Add opened files and folders, most recent first.
+  ///
+  /// Entries already in the recents move to the front, paths are matched with [`normalize_path`].
+  pub fn add_recents(&mut self, recents: Vec<PathToOpen>) -> Result<()> {
+    self.track(|stored| {
+      for recent in recents {
+        let path = match recent.path() {
+          Some(path) => normalize_path(path),
+          None => continue,
+        };
+
+        // The path may have changed type since it was added
+        stored.folders.retain(|stored| normalize_path(&stored.folder) != path);
+        stored.files.retain(|stored| normalize_path(&stored.file) != path);
+
+        let label = recent.label.unwrap_or_default();
+        let window = recent.window.unwrap_or_default();
+
+        if let Some(folder) = recent.folder {
+          stored.folders.insert(0, RecentFolder {
+            label,
+            folder,
+            window,
+            exists: recent.exists,
+          });
+        }
+        else if let Some(file) = recent.file {
+          stored.files.insert(0, RecentFile {
+            label,
+            file,
+            window,
+            exists: recent.exists,
+          });
+        }
+      }
+
+      stored.cap();
+    });
 
 		self.save()
   }
@@ -564,79 +1508,162 @@ impl InnerWindowsRecentsCache {
   }
   
   pub fn clear(&mut self) {
-    self.recents.folders.clear();
-    self.recents.folders.shrink_to_fit();
-    self.recents.files.clear();
-    self.recents.files.shrink_to_fit();
+    self.track(|stored| {
+      stored.folders.clear();
+      stored.folders.shrink_to_fit();
+      stored.files.clear();
+      stored.files.shrink_to_fit();
+    });
   }
   
   pub fn get_recent(&self) {
 
   }
 
-  fn save(&mut self) -> Result<()> {
-    let serialized_database = to_string(&self.recents).unwrap_or("".to_string());
+  /// Get recently opened files and folders, annotated with whether they still exist.
+  pub fn get_recents(&self) -> RecentlyOpened {
+    let mut recents = self.recents().clone();
 
-		// Return early if the database has not changed
-    if self.last_saved_recents_contents.eq(&serialized_database) {
-      Ok(())
+    for file in recents.files.iter_mut() {
+      file.exists = file.file.exists();
     }
-    // Write to disk
-    else {
-      match File::create(&self.recents_path) {
-        Ok(mut file) => {
-          file.write_all(serialized_database.as_bytes())?;
-          self.last_saved_recents_contents = serialized_database;
 
-          Ok(())
-        },
-        Err(e) => {
-          Ok(())
+    for folder in recents.folders.iter_mut() {
+      folder.exists = folder.folder.exists();
+    }
+
+    recents
+  }
+
+  /// Check recents against the filesystem.
+  ///
+  /// Removes missing entries when `remove` is set, otherwise marks them as missing.
+  /// Returns the number of missing entries.
+  pub fn validate(&mut self, remove: bool) -> Result<usize> {
+    let missing = self.track(|stored| {
+      for file in stored.files.iter_mut() {
+        file.exists = file.file.exists();
+      }
+
+      for folder in stored.folders.iter_mut() {
+        folder.exists = folder.folder.exists();
+      }
+
+      let missing = stored.files.iter().filter(|file| !file.exists).count()
+        + stored.folders.iter().filter(|folder| !folder.exists).count();
+
+      if remove {
+        stored.files.retain(|file| file.exists);
+        stored.folders.retain(|folder| folder.exists);
+      }
+
+      missing
+    });
+
+    self.save()?;
+
+    Ok(missing)
+  }
+
+  /// Mark recents of paths deleted or moved outside the app as missing, including entries inside removed folders.
+  pub fn mark_missing(&mut self, paths: &[PathBuf]) -> Result<()> {
+    let paths = paths.iter().map(|path| normalize_path(path)).collect::<Vec<PathBuf>>();
+
+    let is_removed = |path: &PathBuf| {
+      let path = normalize_path(path);
+
+      paths.iter().any(|removed| path.starts_with(removed))
+    };
+
+    self.track(|stored| {
+      for file in stored.files.iter_mut().filter(|file| is_removed(&file.file)) {
+        file.exists = false;
+      }
+
+      for folder in stored.folders.iter_mut().filter(|folder| is_removed(&folder.folder)) {
+        folder.exists = false;
+      }
+    });
+
+    self.save()
+  }
+
+  /// Point recents inside a renamed folder to its new path.
+  pub fn rename_folder(&mut self, from: &Path, to: &Path) -> Result<()> {
+    self.track(|stored| {
+      for recent in stored.folders.iter_mut() {
+        if let Some(folder) = rebase_path(&recent.folder, from, to) {
+          recent.folder = folder;
         }
       }
-    }
+
+      for recent in stored.files.iter_mut() {
+        if let Some(file) = rebase_path(&recent.file, from, to) {
+          recent.file = file;
+        }
+      }
+    });
+
+    self.save()
+  }
+
+  /// Mark recents inside a deleted folder as missing.
+  pub fn remove_folder(&mut self, folder: &Path) -> Result<()> {
+    self.track(|stored| {
+      for recent in stored.folders.iter_mut() {
+        if recent.folder.starts_with(folder) {
+          recent.exists = false;
+        }
+      }
+
+      for recent in stored.files.iter_mut() {
+        if recent.file.starts_with(folder) {
+          recent.exists = false;
+        }
+      }
+    });
+
+    self.save()
+  }
+
+  pub fn save(&mut self) -> Result<()> {
+    self.storage.save()
   }
 }
 
 impl Default for InnerWindowsRecentsCache {
   fn default() -> Self {
-    if let Some(dir) = data_dir() {
-      match read_string(dir.join(BACKUP_FILENAME)) {
-        Ok(contents) => {
-          let recents = match from_str(&contents) {
-            Ok(deserialized) => deserialized,
-            Err(e) => {
-              RecentlyOpened::default()
-            }
-          };
-  
-          InnerWindowsRecentsCache {
-            last_saved_recents_contents: contents,
-            recents_path: dir.join(BACKUP_FILENAME),
-            recents: recents
-          }
-        },
-        Err(e) => {
-          InnerWindowsRecentsCache {
-            last_saved_recents_contents: "".to_string(),
-            recents_path: dir.join(BACKUP_FILENAME),
-            recents: RecentlyOpened::default()
-          }        
-        }
-      }
-    }else {
-      InnerWindowsRecentsCache {
-        last_saved_recents_contents: "".to_string(),
-        recents_path: PathBuf::new(),
-        recents: RecentlyOpened::default()
-      } 
-    }
+    InnerWindowsRecentsCache::in_memory()
   }
 }
 
 #[derive(Default)]
 pub struct WindowsRecentsCache(pub RwLock<InnerWindowsRecentsCache>);
 
+impl WindowsRecentsCache {
+  pub fn in_memory() -> Self {
+    Self(RwLock::new(InnerWindowsRecentsCache::in_memory()))
+  }
+
+  /// Cache backed by its file in the directory, encrypted if a cipher is set.
+  pub fn open(dir: &Path, cipher: Option<Arc<dyn StorageCipher>>) -> Self {
+    let mut cache = InnerWindowsRecentsCache::new(&dir.join(RECENTS_FILENAME));
+
+    if let Some(cipher) = cipher {
+      cache.set_cipher(cipher);
+    }
+
+    Self(RwLock::new(cache))
+  }
+}
+
+/// Get the current time since the unix epoch.
+pub(crate) fn now() -> Duration {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+}
+
 /// Trait for [`WindowBounds`] helpers.
 /// 
 /// [`WindowBounds`]: WindowBounds
@@ -691,8 +1718,11 @@ pub trait WindowFilesTrait {
 /// 
 /// Provides methods for asynchronous events, reload/reopen, and event handling.
 pub trait WindowTrait {
-  /// Load file or url into webview
-  fn load(&self);
+  /// Load an app page or external url into the webview.
+  ///
+  /// The window is navigating until the new page invokes `window_ready`, events sent with
+  /// `emit_when_ready` are held until then when the ready handshake is enabled.
+  fn load(&self, url: &WindowUrl) -> Result<()>;
 
   /// Reload window, with cached up state.
   fn reload(&self);
@@ -727,8 +1757,89 @@ pub trait WindowTrait {
   fn destroy(&self) -> Result<()>;
 }
 
-fn get_working_area(monitor: &Monitor) {
-  todo!();
+/// Get the area of a monitor available to windows.
+///
+/// The runtime doesn't expose the work area, so the full monitor bounds are used.
+pub fn get_working_area(monitor: &Monitor) -> WindowBounds {
+  let position = monitor.position();
+  let size = monitor.size();
+
+  WindowBounds {
+    x: position.x,
+    y: position.y,
+    height: size.height,
+    width: size.width,
+  }
+}
+
+/// Get the logical bounds of a window.
+///
+/// Returns `None` while the window is minimized, maximized or fullscreen, only normal bounds are restored.
+pub fn get_logical_bounds<R: Runtime>(window: &Window<R>) -> Result<Option<LogicalWindowBounds>> {
+  if window.is_minimized()? || window.is_maximized()? || window.is_fullscreen()? {
+    return Ok(None);
+  }
+
+  let scale_factor = window.scale_factor()?;
+  let position = window.outer_position()?.to_logical::<f64>(scale_factor);
+  let size = window.inner_size()?.to_logical::<f64>(scale_factor);
+
+  Ok(Some(LogicalWindowBounds {
+    x: position.x,
+    y: position.y,
+    height: size.height,
+    width: size.width,
+    scale_factor,
+  }))
+}
+
+/// Remember the logical bounds of a window after it moved, resized or changed scale factor.
+///
+/// The cache is written on the next save.
+pub fn handle_window_bounds_changed<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<()> {
+  let window = match manager.get_window(label) {
+    Some(window) => window,
+    None => return Ok(()),
+  };
+
+  let bounds = get_logical_bounds(&window)?;
+  let display = get_window_display(&window)?;
+
+  write_lock(&manager.state::<WindowsStateCache>().0)?
+  .handle_bounds_changed(label, bounds, display);
+
+  Ok(())
+}
+
+/// Get the index of the monitor a window is on among the available monitors.
+pub fn get_window_display<R: Runtime>(window: &Window<R>) -> Result<u32> {
+  let monitor = match get_window_monitor(window)? {
+    Some(monitor) => monitor,
+    None => return Ok(0),
+  };
+
+  let display = window.available_monitors()?
+    .iter()
+    .position(|available| available.name() == monitor.name() && available.position() == monitor.position())
+    .unwrap_or_default();
+
+  Ok(display as u32)
+}
+
+/// Get the monitor a window is on.
+///
+/// Falls back to the monitor sharing the most area with the window, then the primary monitor.
+pub fn get_window_monitor<R: Runtime>(window: &Window<R>) -> Result<Option<Monitor>> {
+  if let Some(monitor) = window.current_monitor()? {
+    return Ok(Some(monitor));
+  }
+
+  let monitors = window.available_monitors()?;
+
+  match find_monitor_with_biggest_intersection(&monitors, &window.get_bounds()) {
+    Some(monitor) => Ok(Some(monitor)),
+    None => Ok(window.primary_monitor()?),
+  }
 }
 fn get_monitor_matching(window: &Window) {
   todo!();
@@ -742,8 +1853,14 @@ fn find_monitor_nearest_point(monitors: &Vec<Monitor>, window: &Window) {
 fn find_monitor_containing_point(monitors: &Vec<Monitor>, window: &Window) {
   todo!();
 }
-fn find_monitor_with_biggest_intersection(monitors: &Vec<Monitor>, window: &Window) {
-  todo!();
+/// Find the monitor sharing the most area with the bounds.
+pub fn find_monitor_with_biggest_intersection(monitors: &[Monitor], bounds: &WindowBounds) -> Option<Monitor> {
+  monitors
+    .iter()
+    .map(|monitor| (get_working_area(monitor).intersection_area(bounds), monitor))
+    .filter(|(area, _monitor)| *area > 0)
+    .max_by_key(|(area, _monitor)| *area)
+    .map(|(_area, monitor)| monitor.clone())
 }
 
 fn intersect_windows(a: impl WindowBoundsTrait, b: impl WindowBoundsTrait) {
@@ -785,10 +1902,7 @@ impl<R: Runtime> WindowStateTrait for Window<R> {
     let cache = window_states_cache.0.read()
     .map_err(|e| Error::RwLock(e.to_string()))?;
 
-    match cache.get_item(self.label()) {
-      Some(state) => Ok(state.last_focus_time),
-      None => Ok(Duration::new(0, 0)),
-    }
+    Ok(cache.last_focus_time(self.label()).unwrap_or_default())
   }
   
   fn set_last_focus_time(&self) -> Result<()> {
@@ -797,20 +1911,7 @@ impl<R: Runtime> WindowStateTrait for Window<R> {
     let mut cache = window_states_cache.0.write()
     .map_err(|e| Error::RwLock(e.to_string()))?;
 
-    match cache.storage.opened_windows.get_mut(self.label()) {
-      Some(state) => {
-        let start = SystemTime::now();
-
-        state.last_focus_time = start
-          .duration_since(UNIX_EPOCH)
-          .expect("Time went backwards");
-
-        Ok(())
-      }
-      None => Err(Error::WindowStateWithLabelNotFound(
-        self.label().to_string(),
-      )),
-    }
+    cache.set_last_focus_time(self.label())
   }
 
   fn set_window_state(&self, new_state: WindowState) -> Result<()> {
@@ -843,7 +1944,28 @@ impl<R: Runtime> WindowFilesTrait for Window<R> {
 }
 
 impl<R: Runtime> WindowTrait for Window<R> {
-  fn load(&self) {}
+  fn load(&self, url: &WindowUrl) -> Result<()> {
+    {
+      let window_states_cache = self.state::<WindowsStateCache>();
+
+      let mut cache = window_states_cache.0.write()
+      .map_err(|e| Error::RwLock(e.to_string()))?;
+
+      let mut state = cache.get_item(self.label())
+      .ok_or_else(|| Error::WindowStateWithLabelNotFound(self.label().to_string()))?;
+
+      state.configuration.url = match url {
+        WindowUrl::External(url) => Some(url.clone()),
+        _ => None,
+      };
+      state.configuration.app_route = None;
+
+      cache.set_item(self.label(), state)?;
+      cache.set_ready_state(self.label(), ReadyState::Navigating)?;
+    }
+
+    navigate(self, url)
+  }
   
   fn reload(&self) {}
   
@@ -857,15 +1979,7 @@ impl<R: Runtime> WindowTrait for Window<R> {
     let mut cache = window_states_cache.0.write()
     .map_err(|e| Error::RwLock(e.to_string()))?;
 
-    match cache.storage.opened_windows.get_mut(self.label()) {
-      Some(state) => {
-        state.ready_state = ReadyState::Ready;
-        Ok(())
-      }
-      None => Err(Error::WindowStateWithLabelNotFound(
-        self.label().to_string(),
-      )),
-    }
+    cache.set_ready_state(self.label(), ReadyState::Ready)
   }
   
   fn ready(&self) {}
@@ -874,15 +1988,8 @@ impl<R: Runtime> WindowTrait for Window<R> {
     let window_states_cache = self.state::<WindowsStateCache>();
 
     let res = match window_states_cache.0.read() {
-      Ok(cache) => {
-        match cache.storage.opened_windows.get(self.label()) {
-          Some(state) => state.ready_state == ReadyState::Ready,
-          None => false,
-        }
-      },
-      Err(e) => {
-        false
-      }
+      Ok(cache) => cache.is_ready(self.label()),
+      Err(_e) => false,
     };
     
     res
@@ -901,4 +2008,279 @@ impl<R: Runtime> WindowTrait for Window<R> {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+  use std::{
+    env::temp_dir,
+    fs::{read, remove_file},
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+    sync::Arc,
+    time::Duration,
+  };
+
+  use crate::error::Error;
+
+  use super::{
+    normalize_path,
+    InnerWindowsRecentsCache,
+    InnerWindowsStateCache,
+    PathToOpen,
+    StorageCipher,
+    WindowConfiguration,
+    WindowState
+  };
+
+  /// Not a real cipher, only tells keys apart.
+  struct XorCipher(u8);
+
+  impl StorageCipher for XorCipher {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+      Ok([self.0].into_iter().chain(plaintext.iter().map(|byte| byte ^ self.0)).collect())
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+      match ciphertext.split_first() {
+        Some((key, data)) if *key == self.0 => Ok(data.iter().map(|byte| byte ^ self.0).collect()),
+        _ => Err(Error::Encryption("wrong key".to_string())),
+      }
+    }
+  }
+
+  fn state_file() -> PathBuf {
+    temp_dir().join(format!("tauri-plugin-windows-{}.json", uuid::Uuid::new_v4().simple()))
+  }
+
+  fn state(folder: &str) -> WindowState {
+    WindowState {
+      folder: Some(PathBuf::from(folder)),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn state_cache_reads_saved_file() {
+    let file = state_file();
+
+    let mut cache = InnerWindowsStateCache::new(&file);
+    cache.set_item("main", state("project")).unwrap();
+
+    let cache = InnerWindowsStateCache::new(&file);
+    let folder = cache.get_item("main").and_then(|state| state.folder);
+    remove_file(&file).unwrap();
+
+    assert_eq!(folder, Some(PathBuf::from("project")));
+  }
+
+  #[test]
+  fn encrypted_state_cache_needs_its_key() {
+    let file = state_file();
+
+    let mut cache = InnerWindowsStateCache::new(&file);
+    cache.set_cipher(Arc::new(XorCipher(7)));
+    cache.set_item("main", state("project")).unwrap();
+
+    let contents = read(&file).unwrap();
+    assert!(!String::from_utf8_lossy(&contents).contains("project"));
+
+    let mut cache = InnerWindowsStateCache::new(&file);
+    cache.set_cipher(Arc::new(XorCipher(7)));
+    assert_eq!(cache.get_item("main").and_then(|state| state.folder), Some(PathBuf::from("project")));
+
+    // Another key reads nothing and leaves the file alone
+    let mut cache = InnerWindowsStateCache::new(&file);
+    cache.set_cipher(Arc::new(XorCipher(9)));
+    assert!(cache.get_item("main").is_none());
+    cache.set_item("other", state("other")).unwrap();
+
+    let unchanged = read(&file).unwrap();
+    remove_file(&file).unwrap();
+
+    assert_eq!(unchanged, contents);
+    assert!(cache.storage_status().last_save_error.is_some());
+  }
+
+  #[test]
+  fn rebases_backup_paths_of_moved_backups() {
+    let legacy_dir = temp_dir().join("shared");
+    let dir = legacy_dir.join("com.tauri.app");
+
+    let mut cache = InnerWindowsStateCache::in_memory();
+
+    for (label, backup_path) in [("moved", legacy_dir.join(".windows_backups").join("1")), ("other", temp_dir().join("other"))] {
+      let state = WindowState {
+        backup_path: Some(backup_path.clone()),
+        configuration: WindowConfiguration {
+          backup_path: Some(backup_path),
+          ..Default::default()
+        },
+        ..Default::default()
+      };
+
+      cache.set_item(label, state).unwrap();
+    }
+
+    cache.rebase_backup_paths(&legacy_dir, &dir).unwrap();
+
+    let moved = cache.get_item("moved").unwrap();
+    assert_eq!(moved.backup_path, Some(dir.join(".windows_backups").join("1")));
+    assert_eq!(moved.configuration.backup_path, moved.backup_path);
+    assert_eq!(cache.get_item("other").and_then(|state| state.backup_path), Some(temp_dir().join("other")));
+  }
+
+  #[test]
+  fn in_memory_state_cache_never_writes() {
+    let mut cache = InnerWindowsStateCache::in_memory();
+    cache.set_item("main", state("project")).unwrap();
+
+    assert!(cache.get_item("main").is_some());
+    assert!(cache.storage_status().last_saved_at.is_none());
+    assert!(cache.storage_status().size.is_none());
+  }
+
+  #[test]
+  fn normalize_path_is_idempotent() {
+    let root = temp_dir().join("tauri-plugin-windows-missing");
+
+    let paths = vec![
+      root.join("a").join("..").join("b"),
+      root.join(".").join("ünïcødé").join("日本語"),
+      root.join("..").join("..").join("with space"),
+      PathBuf::from(format!("{}{}", root.join("dots.in.name").display(), MAIN_SEPARATOR)),
+      PathBuf::from("relative").join("..").join("..").join("up"),
+      temp_dir(),
+    ];
+
+    for path in paths {
+      let normalized = normalize_path(&path);
+
+      assert_eq!(normalize_path(&normalized), normalized, "{:?}", path);
+    }
+  }
+
+  #[test]
+  fn normalize_path_stops_at_root() {
+    let root = Path::new(&MAIN_SEPARATOR.to_string()).to_path_buf();
+
+    assert_eq!(normalize_path(&root.join("..").join("..")), normalize_path(&root));
+  }
+
+  #[test]
+  fn folder_index_follows_state_changes() {
+    let folder = temp_dir().join("tauri-plugin-windows-missing").join("indexed");
+
+    let mut cache = InnerWindowsStateCache::in_memory();
+    cache.set_item("main", WindowState {
+      folder: Some(folder.join(".")),
+      ..Default::default()
+    }).unwrap();
+
+    assert_eq!(cache.folder_index().windows_on_folder(&folder), vec!["main"]);
+    assert_eq!(cache.folder_index().windows_on_file(&folder.join("src").join("main.rs")), vec!["main"]);
+    assert!(cache.folder_index().windows_on_file(&folder.with_file_name("other")).is_empty());
+
+    cache.state_mut().opened_windows.get_mut("main").unwrap().closed_time = Some(Duration::from_secs(1));
+
+    assert!(cache.folder_index().windows_on_folder(&folder).is_empty());
+  }
+
+  #[test]
+  fn folder_preferences_outlive_window_states() {
+    let mut cache = InnerWindowsStateCache::in_memory();
+
+    let mut project = state("project");
+    project.zoom = Some(1.5);
+    project.configuration.always_on_top = true;
+    cache.set_item("main", project).unwrap();
+    cache.remove_item("main").unwrap();
+
+    let preferences = cache.folder_preferences(Path::new("project")).unwrap();
+    assert_eq!(preferences.zoom, Some(1.5));
+    assert!(preferences.always_on_top);
+    assert!(cache.folder_preferences(Path::new("other")).is_none());
+  }
+
+  #[test]
+  fn focus_is_kept_in_memory_until_folded() {
+    let mut cache = InnerWindowsStateCache::in_memory();
+    cache.set_item("main", WindowState::default()).unwrap();
+
+    let persisted = cache.state().clone();
+
+    cache.handle_focused_window("main", &true);
+
+    assert!(*cache.state() == persisted);
+    assert_eq!(cache.focus_history().to_vec(), vec!["main".to_string()]);
+    assert!(cache.last_focus_time("main").unwrap() > Duration::ZERO);
+
+    cache.fold_volatile();
+
+    assert_eq!(cache.state().focus_history, vec!["main".to_string()]);
+    assert_eq!(cache.state().focused_window.as_deref(), Some("main"));
+    assert_eq!(cache.state().opened_windows["main"].last_focus_time, cache.last_focus_time("main").unwrap());
+  }
+
+  fn recent_folder(folder: PathBuf) -> PathToOpen {
+    PathToOpen {
+      folder: Some(folder),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn recents_dedupe_spellings() {
+    let folder = temp_dir().join("tauri-plugin-windows-missing").join("ünïcødé");
+    let other = temp_dir().join("tauri-plugin-windows-missing").join("other");
+
+    let spellings = vec![
+      folder.clone(),
+      PathBuf::from(format!("{}{}", folder.display(), MAIN_SEPARATOR)),
+      folder.join("."),
+      folder.join("a").join(".."),
+    ];
+
+    let mut cache = InnerWindowsRecentsCache::in_memory();
+
+    for spelling in spellings {
+      cache.add_recents(vec![recent_folder(other.clone()), recent_folder(spelling)]).unwrap();
+
+      let folders = &cache.recents().folders;
+
+      assert_eq!(folders.len(), 2);
+      assert_eq!(normalize_path(&folders[0].folder), normalize_path(&folder));
+      assert_eq!(folders[1].folder, other);
+    }
+
+    // Adding it again moves it to the front
+    cache.add_recents(vec![recent_folder(other.clone())]).unwrap();
+
+    assert_eq!(cache.recents().folders[0].folder, other);
+    assert!(cache.recents().files.is_empty());
+  }
+
+  #[test]
+  fn recents_inside_removed_folder_are_missing() {
+    let root = temp_dir().join("tauri-plugin-windows-missing");
+
+    let existing = |folder: PathBuf| PathToOpen { exists: true, ..recent_folder(folder) };
+
+    let mut cache = InnerWindowsRecentsCache::in_memory();
+
+    cache.add_recents(vec![
+      existing(root.join("removed")),
+      existing(root.join("removed").join("nested")),
+      existing(root.join("kept")),
+    ]).unwrap();
+    cache.take_changes();
+
+    cache.mark_missing(&[root.join("removed")]).unwrap();
+
+    let missing = cache.recents().folders
+      .iter()
+      .filter(|recent| !recent.exists)
+      .map(|recent| recent.folder.clone())
+      .collect::<Vec<PathBuf>>();
+
+    assert_eq!(missing.len(), 2);
+    assert!(!missing.contains(&root.join("kept")));
+    assert_eq!(cache.take_changes().missing.len(), 2);
+  }
+}
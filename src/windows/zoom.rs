@@ -0,0 +1,147 @@
+//! Zoom.
+//!
+//! This module contains basic methods and types for the zoom level of windows.
+//! Levels are recorded in the window state and applied to the page each time it loads.
+
+use std::result::Result as StdResult;
+
+use serde::{Deserialize, Serialize};
+
+use tauri::{Manager, Runtime, Window};
+
+use crate::error::Error;
+use crate::event::{WINDOW_ZOOM_IN_EVENT, WINDOW_ZOOM_OUT_EVENT, WINDOW_ZOOM_RESET_EVENT};
+
+use super::{WindowsSettings, WindowsStateCache};
+
+type Result<T> = StdResult<T, Error>;
+
+/// Zoom settings.
+///
+/// Read from `tauri.conf.json > plugins > windows > zoom`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ZoomSettings {
+  /// Change of the zoom factor per zoom in or out.
+  pub step: f64,
+  /// Smallest zoom factor.
+  pub min: f64,
+  /// Largest zoom factor.
+  pub max: f64,
+}
+
+impl Default for ZoomSettings {
+  fn default() -> Self {
+    Self {
+      step: 0.1,
+      min: 0.3,
+      max: 3.0,
+    }
+  }
+}
+
+/// Get the zoom factor of a window, `1.0` if it was never zoomed.
+pub fn get_zoom<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<f64> {
+  let windows_state_cache = manager.state::<WindowsStateCache>();
+
+  let cache = windows_state_cache.0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?;
+
+  let state = cache.get_item(label)
+  .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+  Ok(state.zoom.unwrap_or(1.0))
+}
+
+/// Set the zoom factor of a window, kept within the bounds of the settings.
+///
+/// Returns the zoom factor applied.
+pub fn set_zoom<R: Runtime, M: Manager<R>>(manager: &M, label: &str, zoom: f64) -> Result<f64> {
+  let window = manager.get_window(label)
+  .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
+
+  let settings = manager.state::<WindowsSettings>().0.read()
+  .map_err(|e| Error::RwLock(e.to_string()))?
+  .zoom
+  .clone();
+
+  // Rounded so repeated steps don't drift, e.g. 1.1 + 0.1 isn't 1.2
+  let zoom = (zoom.max(settings.min).min(settings.max) * 100.0).round() / 100.0;
+
+  {
+    let windows_state_cache = manager.state::<WindowsStateCache>();
+
+    let mut cache = windows_state_cache.0.write()
+    .map_err(|e| Error::RwLock(e.to_string()))?;
+
+    let mut state = cache.get_item(label)
+    .ok_or_else(|| Error::WindowStateWithLabelNotFound(label.to_string()))?;
+
+    state.zoom = if zoom == 1.0 { None } else { Some(zoom) };
+
+    cache.set_item(label, state)?;
+  }
+
+  apply_zoom(&window, zoom)?;
+
+  Ok(zoom)
+}
+
+/// Zoom a window in by one step.
+pub fn zoom_in<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<f64> {
+  let step = zoom_step(manager)?;
+
+  set_zoom(manager, label, get_zoom(manager, label)? + step)
+}
+
+/// Zoom a window out by one step.
+pub fn zoom_out<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<f64> {
+  let step = zoom_step(manager)?;
+
+  set_zoom(manager, label, get_zoom(manager, label)? - step)
+}
+
+/// Reset the zoom of a window to `1.0`.
+pub fn zoom_reset<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<f64> {
+  set_zoom(manager, label, 1.0)
+}
+
+/// Apply the recorded zoom of a window to its page, needed again after each page load.
+pub fn restore_zoom<R: Runtime>(window: &Window<R>) -> Result<()> {
+  match get_zoom(window, window.label())? {
+    zoom if zoom == 1.0 => Ok(()),
+    zoom => apply_zoom(window, zoom),
+  }
+}
+
+/// Zoom the window a zoom menu item was clicked in.
+///
+/// Returns whether the menu item was a zoom item.
+pub fn handle_zoom_menu_event<R: Runtime>(window: &Window<R>, menu_item_id: &str) -> Result<bool> {
+  let label = window.label();
+
+  match menu_item_id {
+    WINDOW_ZOOM_IN_EVENT => zoom_in(window, label)?,
+    WINDOW_ZOOM_OUT_EVENT => zoom_out(window, label)?,
+    WINDOW_ZOOM_RESET_EVENT => zoom_reset(window, label)?,
+    _ => return Ok(false),
+  };
+
+  Ok(true)
+}
+
+fn zoom_step<R: Runtime, M: Manager<R>>(manager: &M) -> Result<f64> {
+  Ok(
+    manager.state::<WindowsSettings>().0.read()
+    .map_err(|e| Error::RwLock(e.to_string()))?
+    .zoom
+    .step
+  )
+}
+
+/// Webviews of Tauri v1 have no zoom, the page is scaled through css instead.
+fn apply_zoom<R: Runtime>(window: &Window<R>, zoom: f64) -> Result<()> {
+  window.eval(&format!("document.documentElement.style.zoom = '{}'", zoom))?;
+
+  Ok(())
+}
@@ -1,25 +1,74 @@
 import { invoke } from "@tauri-apps/api/tauri";
-import { OpenConfiguration, WindowOptions } from "./windows";
+import {
+  BroadcastReply,
+  ContextMenuEntry,
+  Diagnostics,
+  LastActiveWindow,
+  MonitorInfo,
+  OpenBehavior,
+  OpenConfiguration,
+  OpenedWindowInfo,
+  OpenOutcome,
+  OpenWindowResult,
+  PickOptions,
+  RecentlyOpened,
+  ResourceValidation,
+  Screenshot,
+  SnapRegion,
+  SwitcherEntry,
+  StartupInfo,
+  StartupMetrics,
+  Theme,
+  TileLayout,
+  WindowInfo,
+  WindowOptions,
+  WindowOptionsUpdate,
+  WindowPosition,
+  WindowState,
+  WindowStatePatch,
+} from "./windows";
 
 class Windows {
   constructor() {}
 
-  open_window(configuration: OpenConfiguration): Promise<null> {
+  open_window(configuration: OpenConfiguration): Promise<OpenedWindowInfo> {
     return invoke("plugin:windows|open_window", {
       configuration,
     });
   }
 
+  open_with_outcome(configuration: OpenConfiguration): Promise<OpenOutcome> {
+    return invoke("plugin:windows|open_with_outcome", {
+      configuration,
+    });
+  }
+
+  open_windows(
+    configurations: OpenConfiguration[]
+  ): Promise<OpenWindowResult[]> {
+    return invoke("plugin:windows|open_windows", {
+      configurations,
+    });
+  }
+
   open_empty_window(
     configuration: OpenConfiguration,
     options: WindowOptions
-  ): Promise<null> {
+  ): Promise<OpenedWindowInfo> {
     return invoke("plugin:windows|open_empty_window", {
       configuration,
       options,
     });
   }
 
+  // opens `index.html#/<route>`, reusing the singleton or folder window of the options
+  open_route(route: string, options?: WindowOptions): Promise<OpenedWindowInfo> {
+    return invoke("plugin:windows|open_route", {
+      route,
+      options,
+    });
+  }
+
   open_existing_window(
     configuration: OpenConfiguration,
     windowToUse: string
@@ -30,27 +79,437 @@ class Windows {
     });
   }
 
-  send_to_focused(channel: string, payload: any): Promise<null> {
+  // app path or external url in `url_allowlist`, events are held until the new page invokes `window_ready`
+  navigate_window(label: string, url: string): Promise<null> {
+    return invoke("plugin:windows|navigate_window", {
+      label,
+      url,
+    });
+  }
+
+  open_deep_link(url: string): Promise<OpenedWindowInfo> {
+    return invoke("plugin:windows|open_deep_link", {
+      url,
+    });
+  }
+
+  open_files(
+    paths: string[],
+    behavior?: OpenBehavior
+  ): Promise<OpenedWindowInfo> {
+    return invoke("plugin:windows|open_files", {
+      paths,
+      behavior,
+    });
+  }
+
+  open_folder(
+    path: string,
+    behavior?: OpenBehavior
+  ): Promise<OpenedWindowInfo> {
+    return invoke("plugin:windows|open_folder", {
+      path,
+      behavior,
+    });
+  }
+
+  pick_and_open_files(
+    options?: PickOptions
+  ): Promise<OpenedWindowInfo | null> {
+    return invoke("plugin:windows|pick_and_open_files", {
+      options,
+    });
+  }
+
+  pick_and_open_folder(
+    options?: PickOptions
+  ): Promise<OpenedWindowInfo | null> {
+    return invoke("plugin:windows|pick_and_open_folder", {
+      options,
+    });
+  }
+
+  get_default_open_directory(): Promise<string | null> {
+    return invoke("plugin:windows|get_default_open_directory");
+  }
+
+  duplicate_window(label: string): Promise<OpenedWindowInfo> {
+    return invoke("plugin:windows|duplicate_window", {
+      label,
+    });
+  }
+
+  move_resource(
+    fromLabel: string,
+    toLabel: string,
+    path: string
+  ): Promise<OpenedWindowInfo> {
+    return invoke("plugin:windows|move_resource", {
+      fromLabel,
+      toLabel,
+      path,
+    });
+  }
+
+  prune_state(): Promise<number> {
+    return invoke("plugin:windows|prune_state");
+  }
+
+  get_startup_info(): Promise<StartupInfo> {
+    return invoke("plugin:windows|get_startup_info");
+  }
+
+  update_window_options(
+    label: string,
+    options: WindowOptionsUpdate
+  ): Promise<null> {
+    return invoke("plugin:windows|update_window_options", {
+      label,
+      options,
+    });
+  }
+
+  // the window renders itself, answering `windows://screenshot-request` with `acknowledge_broadcast` and a base64 PNG
+  screenshot_window(label: string, toFile?: boolean): Promise<Screenshot> {
+    return invoke("plugin:windows|screenshot_window", {
+      label,
+      toFile,
+    });
+  }
+
+  // most recently focused first, thumbnails are screenshots windows answer within 1.5 seconds
+  get_switcher_entries(thumbnails?: boolean): Promise<SwitcherEntry[]> {
+    return invoke("plugin:windows|get_switcher_entries", {
+      thumbnails,
+    });
+  }
+
+  // the window renders the menu from `windows://context-menu`, returns the id to answer with
+  show_context_menu(
+    label: string,
+    entries: ContextMenuEntry[],
+    position?: WindowPosition
+  ): Promise<number> {
+    return invoke("plugin:windows|show_context_menu", {
+      label,
+      entries,
+      position,
+    });
+  }
+
+  // entryId is null when the menu was dismissed
+  select_context_menu_entry(
+    label: string,
+    id: number,
+    entryId: string | null
+  ): Promise<null> {
+    return invoke("plugin:windows|select_context_menu_entry", {
+      label,
+      id,
+      entryId,
+    });
+  }
+
+  // zoom functions return the new zoom factor, kept within the `zoom` settings
+  zoom_in(label: string): Promise<number> {
+    return invoke("plugin:windows|zoom_in", {
+      label,
+    });
+  }
+
+  zoom_out(label: string): Promise<number> {
+    return invoke("plugin:windows|zoom_out", {
+      label,
+    });
+  }
+
+  zoom_reset(label: string): Promise<number> {
+    return invoke("plugin:windows|zoom_reset", {
+      label,
+    });
+  }
+
+  set_zoom(label: string, zoom: number): Promise<number> {
+    return invoke("plugin:windows|set_zoom", {
+      label,
+      zoom,
+    });
+  }
+
+  // release builds need the `devtools` feature of the plugin
+  set_devtools(label: string, open: boolean): Promise<null> {
+    return invoke("plugin:windows|set_devtools", {
+      label,
+      open,
+    });
+  }
+
+  // hides the window from screen capture, kept when the window is restored
+  set_content_protected(label: string, enabled: boolean): Promise<null> {
+    return invoke("plugin:windows|set_content_protected", {
+      label,
+      protected: enabled,
+    });
+  }
+
+  get_window_state(label: string): Promise<WindowState> {
+    return invoke("plugin:windows|get_window_state", {
+      label,
+    });
+  }
+
+  get_backup_path(label: string): Promise<string | null> {
+    return invoke("plugin:windows|get_backup_path", {
+      label,
+    });
+  }
+
+  set_window_state(
+    label: string,
+    patch: WindowStatePatch
+  ): Promise<WindowState> {
+    return invoke("plugin:windows|set_window_state", {
+      label,
+      patch,
+    });
+  }
+
+  get_focus_history(): Promise<string[]> {
+    return invoke("plugin:windows|get_focus_history");
+  }
+
+  focus_previous_window(): Promise<string | null> {
+    return invoke("plugin:windows|focus_previous_window");
+  }
+
+  get_window_stack(): Promise<string[]> {
+    return invoke("plugin:windows|get_window_stack");
+  }
+
+  restore_stacking_order(): Promise<null> {
+    return invoke("plugin:windows|restore_stacking_order");
+  }
+
+  // bring a window created by the app under plugin management, false if it already was
+  adopt_window(label: string): Promise<boolean> {
+    return invoke("plugin:windows|adopt_window", {
+      label,
+    });
+  }
+
+  list_windows(): Promise<WindowInfo[]> {
+    return invoke("plugin:windows|list_windows");
+  }
+
+  set_window_group(label: string, group: string | null): Promise<null> {
+    return invoke("plugin:windows|set_window_group", {
+      label,
+      group,
+    });
+  }
+
+  focus_group(group: string): Promise<null> {
+    return invoke("plugin:windows|focus_group", {
+      group,
+    });
+  }
+
+  close_group(group: string): Promise<null> {
+    return invoke("plugin:windows|close_group", {
+      group,
+    });
+  }
+
+  minimize_group(group: string): Promise<null> {
+    return invoke("plugin:windows|minimize_group", {
+      group,
+    });
+  }
+
+  save_layout(name: string): Promise<null> {
+    return invoke("plugin:windows|save_layout", {
+      name,
+    });
+  }
+
+  apply_layout(name: string): Promise<null> {
+    return invoke("plugin:windows|apply_layout", {
+      name,
+    });
+  }
+
+  get_monitors(): Promise<MonitorInfo[]> {
+    return invoke("plugin:windows|get_monitors");
+  }
+
+  get_window_monitor(label: string): Promise<MonitorInfo | null> {
+    return invoke("plugin:windows|get_window_monitor", {
+      label,
+    });
+  }
+
+  snap_window(label: string, region: SnapRegion): Promise<null> {
+    return invoke("plugin:windows|snap_window", {
+      label,
+      region,
+    });
+  }
+
+  tile_windows(labels: string[], layout: TileLayout): Promise<null> {
+    return invoke("plugin:windows|tile_windows", {
+      labels,
+      layout,
+    });
+  }
+
+  set_theme(label: string | null, theme: Theme | null): Promise<null> {
+    return invoke("plugin:windows|set_theme", {
+      label,
+      theme,
+    });
+  }
+
+  relaunch_preserving_session(): Promise<null> {
+    return invoke("plugin:windows|relaunch_preserving_session");
+  }
+
+  acknowledge_quit(label: string, allow: boolean): Promise<null> {
+    return invoke("plugin:windows|acknowledge_quit", {
+      label,
+      allow,
+    });
+  }
+
+  confirm_close(label: string, allow: boolean): Promise<null> {
+    return invoke("plugin:windows|confirm_close", {
+      label,
+      allow,
+    });
+  }
+
+  window_ready(): Promise<null> {
+    return invoke("plugin:windows|window_ready");
+  }
+
+  set_pending_work(label: string, pendingWork: boolean): Promise<null> {
+    return invoke("plugin:windows|set_pending_work", {
+      label,
+      pendingWork,
+    });
+  }
+
+  set_dirty(label: string, dirty: boolean): Promise<null> {
+    return invoke("plugin:windows|set_dirty", {
+      label,
+      dirty,
+    });
+  }
+
+  trust_folder(folder: string): Promise<null> {
+    return invoke("plugin:windows|trust_folder", {
+      folder,
+    });
+  }
+
+  revoke_trust(folder: string): Promise<null> {
+    return invoke("plugin:windows|revoke_trust", {
+      folder,
+    });
+  }
+
+  get_recents(): Promise<RecentlyOpened> {
+    return invoke("plugin:windows|get_recents");
+  }
+
+  validate_resources(validation: ResourceValidation): Promise<number> {
+    return invoke("plugin:windows|validate_resources", {
+      validation,
+    });
+  }
+
+  // resolves to the label of the window the message was sent to
+  send_to_focused(channel: string, payload: any): Promise<string | null> {
     return invoke("plugin:windows|send_to_focused", {
       channel,
       payload,
     });
   }
 
-  send_to_all(channel: string, payload: any): Promise<null> {
+  // sticky messages are replayed to windows opened later, once they are ready
+  send_to_all(channel: string, payload: any, windowLabelsToIgnore?: Array<string>, sticky?: boolean): Promise<null> {
     return invoke("plugin:windows|send_to_all", {
       channel,
       payload,
+      windowLabelsToIgnore,
+      sticky,
     });
   }
 
-  get_focused_window(): Promise<string> {
+  // resolves once every window acknowledged or the timeout passed, 5 seconds by default
+  send_to_all_and_wait(
+    channel: string,
+    payload: any,
+    windowLabelsToIgnore?: Array<string>,
+    timeoutMs?: number
+  ): Promise<Array<BroadcastReply>> {
+    return invoke("plugin:windows|send_to_all_and_wait", {
+      channel,
+      payload,
+      windowLabelsToIgnore,
+      timeoutMs,
+    });
+  }
+
+  // answer a `BroadcastPayload` from `send_to_all_and_wait`, failed if `error` is set
+  acknowledge_broadcast(id: number, result?: any, error?: string): Promise<null> {
+    return invoke("plugin:windows|acknowledge_broadcast", {
+      id,
+      result,
+      error,
+    });
+  }
+
+  // broadcasts reach every window until it subscribes, then only its channels
+  subscribe(label: string, channels: Array<string>): Promise<null> {
+    return invoke("plugin:windows|subscribe", {
+      label,
+      channels,
+    });
+  }
+
+  // an empty list of channels unsubscribes from all of them
+  unsubscribe(label: string, channels: Array<string>): Promise<null> {
+    return invoke("plugin:windows|unsubscribe", {
+      label,
+      channels,
+    });
+  }
+
+  get_focused_window(): Promise<string | null> {
     return invoke("plugin:windows|get_focused_window");
   }
 
-  get_last_active_window(): Promise<string> {
+  get_last_active_window(): Promise<LastActiveWindow | null> {
     return invoke("plugin:windows|get_last_active_window");
   }
+
+  get_startup_metrics(): Promise<StartupMetrics> {
+    return invoke("plugin:windows|get_startup_metrics");
+  }
+
+  get_diagnostics(): Promise<Diagnostics> {
+    return invoke("plugin:windows|get_diagnostics");
+  }
+
+  export_state(): Promise<string> {
+    return invoke("plugin:windows|export_state");
+  }
+
+  import_state(json: string): Promise<null> {
+    return invoke("plugin:windows|import_state", {
+      json,
+    });
+  }
 }
 
 const WindowsService = new Windows();
@@ -1,4 +1,7 @@
 export enum OpenContext {
+  // opening through the api
+  Api,
+
   // opening when running from the command line
   Cli,
 
@@ -12,6 +15,12 @@ export enum OpenContext {
   Dialog,
   // opening from the OS's UI
   Desktop,
+
+  // opening by dropping resources on a window
+  Drop,
+
+  // opening from a deep link (custom uri scheme) activation
+  Url,
 }
 
 export interface WindowOpenable {
@@ -23,6 +32,7 @@ export interface OpenConfiguration {
   label: string | null | undefined;
   url: string | null | undefined;
   uris_to_open: Array<WindowOpenable> | null | undefined;
+  urls_to_open?: Array<string>;
   context_window_label: string | null | undefined;
   context: OpenContext;
   force_new_window: boolean;
@@ -32,6 +42,125 @@ export interface OpenConfiguration {
   prefer_new_window: boolean;
   initial_startup: boolean;
   diff_mode: boolean;
+  create_if_missing?: boolean;
+  // collect the decisions made while opening
+  explain?: boolean;
+  // activation of windows receiving the resources
+  activation?: ActivationPolicy;
+  // open the matching files of requested folders instead of the folders
+  expand_folders?: FolderExpansion | null;
+  // repeating the id shortly after it opened returns the first result instead
+  request_id?: string | null;
+  // set as `window.__WINDOWS_INIT__` in new windows before the page loads
+  init_payload?: any;
+}
+
+export interface FolderExpansion {
+  // glob pattern relative to the folder, e.g. `**/*.md`
+  pattern?: string;
+  // ignored files and folders, in `.gitignore` syntax
+  ignore?: Array<string>;
+  use_gitignore?: boolean;
+  max_depth?: number;
+  // files to open from each folder at most
+  max_files?: number;
+}
+
+export enum ActivationPolicy {
+  // bring the window to front and focus it
+  Focus = "Focus",
+
+  // leave focus where it is, request user attention instead
+  Background = "Background",
+}
+
+export enum OpenPath {
+  // a new window was created
+  NewWindow = "NewWindow",
+
+  // an existing window was used
+  ExistingWindow = "ExistingWindow",
+
+  // the max windows limit was reached, the least recently focused window was reused
+  MaxWindowsReached = "MaxWindowsReached",
+}
+
+export interface OpenedWindowInfo {
+  label: string;
+  id: number;
+  display: number;
+  path: OpenPath;
+  // effective configuration, with the resources it resolved to
+  configuration: OpenConfiguration | null;
+  // decisions made while opening, only in explain mode
+  trace: Array<string> | null;
+  // requests that were ahead of this one in the open queue
+  queue_position: number | null;
+}
+
+// result of each configuration of `open_windows`
+export type OpenWindowResult = { Ok: OpenedWindowInfo } | { Err: string };
+
+export type ErrorKind = "NotFound" | "NotAllowed" | "Rejected" | "Window" | "Io" | "Other";
+
+export interface OpenOutcome {
+  opened: OpenedWindowInfo[];
+  // path or url with the kind of its error, an empty path stands for the whole request
+  failed: [string, ErrorKind][];
+}
+
+export enum OpenBehavior {
+  // let the settings decide
+  Default = "Default",
+
+  // always open in a new window
+  NewWindow = "NewWindow",
+
+  // always reuse the last active window
+  ReuseWindow = "ReuseWindow",
+}
+
+export interface DialogFilter {
+  name: string;
+  extensions: Array<string>;
+}
+
+export interface PickOptions {
+  title?: string | null;
+  filters?: Array<DialogFilter>;
+  multiple?: boolean;
+  default_directory?: string | null;
+  behavior?: OpenBehavior;
+}
+
+export enum ResourceValidation {
+  // don't validate
+  Off = "Off",
+
+  // mark missing entries
+  Mark = "Mark",
+
+  // remove missing entries
+  Remove = "Remove",
+}
+
+export interface RecentFile {
+  label: string;
+  file: string;
+  window: string;
+  exists: boolean;
+}
+
+export interface RecentFolder {
+  label: string;
+  folder: string;
+  window: string;
+  exists: boolean;
+}
+
+export interface RecentlyOpened {
+  files: RecentFile[];
+  folders: RecentFolder[];
 }
 
 export interface WindowSize {
@@ -46,9 +175,9 @@ export interface WindowPosition {
 
 export enum Theme {
   /// Light theme.
-  Light,
+  Light = "light",
   /// Dark theme.
-  Dark,
+  Dark = "dark",
 }
 
 export interface EmptyWindowBackupInfo {
@@ -80,6 +209,27 @@ export interface TauriWindowOptions {
   title: string | null | undefined;
   transparent: boolean | null | undefined;
   visible: boolean | null | undefined;
+  user_agent?: string | null;
+  additional_browser_args?: string | null;
+  data_directory?: string | null;
+}
+
+export interface WindowOptionsUpdate {
+  always_on_top?: boolean;
+  center?: boolean;
+  decorations?: boolean;
+  focus?: boolean;
+  fullscreen?: boolean;
+  inner_size?: WindowSize;
+  max_inner_size?: WindowSize;
+  maximized?: boolean;
+  min_inner_size?: WindowSize;
+  position?: WindowPosition;
+  resizable?: boolean;
+  skip_taskbar?: boolean;
+  theme?: Theme;
+  title?: string;
+  visible?: boolean;
 }
 
 export interface WindowOptions extends TauriWindowOptions {
@@ -92,4 +242,411 @@ export interface WindowOptions extends TauriWindowOptions {
   files_to_open: FilesToOpen;
   window_to_use: string | null | undefined;
   folder: string | null | undefined;
+  activation?: ActivationPolicy;
+  // key of a window open at most once, opening it again activates the open window
+  singleton_key?: string | null;
+  // navigate an open singleton window to `url` when it's opened again
+  update_singleton?: boolean;
+  // sent with the `windows://singleton` event each time the window is opened
+  singleton_payload?: any;
+  // route of the app page the window shows, see `open_route`
+  app_route?: string | null;
+  // set as `window.__WINDOWS_INIT__` before the page loads, not for external urls
+  init_payload?: any;
+  // source of the open request, delivered with resources sent to an existing window
+  context?: OpenContext;
+  // the user home if not set
+  home_dir?: string | null;
+  // the system temp directory if not set
+  tmp_dir?: string | null;
+  // webview profile directory, used as `data_directory` unless that is set
+  user_data_dir?: string | null;
+}
+
+export interface RenamedEntry {
+  from: string;
+  to: string;
+}
+
+// payload of the `windows://folder-changed` event
+export interface FolderChangedPayload {
+  folder: string;
+  created: string[];
+  removed: string[];
+  renamed: RenamedEntry[];
+  folder_renamed: string | null;
+  folder_removed: boolean;
+}
+
+// payload of the `windows://trust-request` event
+export interface TrustRequestPayload {
+  folder: string;
+}
+
+// payload of the `windows://close-requested` event
+export interface CloseRequestedPayload {
+  label: string;
+  pending_work: boolean;
+  // unsaved changes reported with `set_dirty`
+  dirty: boolean;
+}
+
+// payload of the `windows://before-quit` and `windows://quit-cancelled` events
+export interface BeforeQuitPayload {
+  id: number;
+}
+
+// payload of the `windows://restore-available` event
+export interface StartupInfo {
+  crashed: boolean;
+  was_restarted: boolean;
+}
+
+// payload of the `windows://theme-changed` event
+export interface ThemeChangedPayload {
+  label: string;
+  theme: Theme;
+  follows_system: boolean;
+}
+
+// region of the monitor, fractions are relative to the monitor work area
+export type SnapRegion =
+  | "LeftHalf"
+  | "RightHalf"
+  | "TopHalf"
+  | "BottomHalf"
+  | "TopLeft"
+  | "TopRight"
+  | "BottomLeft"
+  | "BottomRight"
+  | { Fraction: { x: number; y: number; width: number; height: number } };
+
+export type TileLayout =
+  | "Grid"
+  | "Columns"
+  | "Rows"
+  | { Custom: { columns: number } };
+
+export type WindowMode = "Maximized" | "Normal" | "Minimized" | "Fullscreen";
+
+export interface WindowInfo {
+  label: string;
+  id: number;
+  display: number;
+  folder: string | null;
+  group: string | null;
+  mode: WindowMode;
+  dirty: boolean;
+  // false for windows created by the app, until adopted
+  managed: boolean;
+}
+
+export interface Duration {
+  secs: number;
+  nanos: number;
+}
+
+export interface LogicalWindowBounds {
+  x: number;
+  y: number;
+  height: number;
+  width: number;
+  scale_factor: number;
+}
+
+export interface WindowState {
+  configuration: Record<string, unknown>;
+  mode: WindowMode;
+  display: number;
+  id: number;
+  last_focus_time: Duration;
+  ready_state: "None" | "Navigating" | "Ready";
+  backup_path: string | null;
+  folder: string | null;
+  closed_time: Duration | null;
+  created_time: Duration | null;
+  // time the page first loaded
+  page_load_time: Duration | null;
+  // time the frontend first invoked `window_ready`
+  ready_time: Duration | null;
+  restricted: boolean;
+  group: string | null;
+  logical_bounds: LogicalWindowBounds | null;
+  user_data: unknown;
+  // key of a window open at most once
+  singleton_key: string | null;
+  // zoom factor of the page, null when not zoomed
+  zoom: number | null;
+  // unsaved changes reported with `set_dirty`
+  dirty: boolean;
+}
+
+export interface LastActiveWindow {
+  label: string;
+  state: WindowState;
+}
+
+export interface StorageStatus {
+  path: string;
+  // size on disk in bytes, null if the file doesn't exist
+  size: number | null;
+  loaded: boolean;
+  // last successful write in milliseconds since the unix epoch
+  last_saved_at: number | null;
+  last_save_error: string | null;
+  // whether the file is written encrypted
+  encrypted: boolean;
+}
+
+export interface RecordedError {
+  time: number;
+  message: string;
+}
+
+export interface Diagnostics {
+  version: string;
+  storage: {
+    state: StorageStatus;
+    // null without the `recents` feature
+    recents: StorageStatus | null;
+    // null without the `backups` feature
+    backups: StorageStatus | null;
+    trust: StorageStatus;
+    layouts: StorageStatus;
+  };
+  open_windows: number;
+  window_states: number;
+  recent_files: number;
+  recent_folders: number;
+  folder_backups: number;
+  empty_window_backups: number;
+  // times the event loop had to wait for a cache lock
+  lock_contentions: number;
+  // last internal errors, oldest first
+  errors: RecordedError[];
+}
+
+// times in milliseconds since the window was created
+export interface WindowStartupMetrics {
+  label: string;
+  // milliseconds since the unix epoch
+  created_at: number;
+  // null while the page is loading
+  page_load: number | null;
+  // null until the frontend invokes `window_ready`
+  ready: number | null;
+}
+
+export interface LatencyStats {
+  count: number;
+  min: number;
+  median: number;
+  max: number;
+}
+
+export interface StartupMetrics {
+  // oldest first
+  windows: WindowStartupMetrics[];
+  page_load: LatencyStats | null;
+  ready: LatencyStats | null;
+}
+
+export interface WindowStatePatch {
+  folder?: string;
+  user_data?: unknown;
+  mode?: WindowMode;
+}
+
+// payload of the `windows://open_files_progress` event
+export interface OpenFilesProgressPayload {
+  index: number;
+  total: number;
+}
+
+export interface PathToOpen {
+  folder: string | null;
+  file: string | null;
+  backup_path: string | null;
+  path_type: "File" | "Directory";
+  exists: boolean;
+  window: string | null;
+  label: string | null;
+  url: string | null;
+}
+
+// payload of the `windows://open_files` event
+export interface OpenFilePayload {
+  files_to_open_or_create: Array<string>;
+  // source of the open request
+  context: OpenContext;
+}
+
+// payload of the `windows://add_folders` event
+export interface AddFolderPayload {
+  folders_to_add: Array<PathToOpen>;
+  // source of the open request
+  context: OpenContext;
+}
+
+// payload of the `windows://window-created` event
+export interface WindowCreatedPayload {
+  label: string;
+  id: number;
+  folder: string | null;
+  // folder to write hot-exit data to
+  backup_path: string | null;
+}
+
+// payload of the `windows://ready` event
+export interface WindowReadyPayload {
+  label: string;
+}
+
+// payload of broadcasts from `send_to_all_and_wait`
+export interface BroadcastPayload {
+  // id to answer with `acknowledge_broadcast`
+  id: number;
+  payload: any;
+}
+
+export enum BroadcastStatus {
+  Acknowledged = "Acknowledged",
+
+  // the window answered with an error
+  Failed = "Failed",
+
+  // the window didn't answer in time
+  TimedOut = "TimedOut",
+
+  // the window was closed before answering
+  Closed = "Closed",
+}
+
+export interface BroadcastReply {
+  label: string;
+  status: BroadcastStatus;
+  result: any | null;
+  error: string | null;
+}
+
+// file of a window deleted or moved outside the app
+export interface MissingFile {
+  file: string;
+  // new path if the file was seen moving inside a watched folder
+  moved_to: string | null;
+}
+
+// payload of the `windows://file-missing` event
+export interface FileMissingPayload {
+  files: Array<MissingFile>;
+}
+
+// PNG capture of a window
+// rectangle in physical pixels
+export interface WindowBounds {
+  x: number;
+  y: number;
+  height: number;
+  width: number;
+}
+
+export interface MonitorInfo {
+  // index among the available monitors, as in `WindowState.display`
+  index: number;
+  name: string | null;
+  bounds: WindowBounds;
+  // area available to windows
+  work_area: WindowBounds;
+  scale_factor: number;
+  primary: boolean;
+}
+
+export interface Screenshot {
+  label: string;
+  width: number;
+  height: number;
+  // base64 PNG, unless written to a file
+  data: string | null;
+  // temporary PNG file, if asked for one
+  path: string | null;
+}
+
+// window of an in-app window switcher
+export interface SwitcherEntry {
+  label: string;
+  title: string;
+  folder: string | null;
+  group: string | null;
+  mode: WindowMode;
+  thumbnail: Screenshot | null;
+}
+
+// window management action run by the plugin when its context menu entry is selected
+export type ContextMenuAction =
+  | { MoveToWindow: { path: string; to_label: string } }
+  | "CloseFolder"
+  | "ReopenClosedWindow";
+
+// entry of a context menu, entries without action are only delivered to the window
+export interface ContextMenuEntry {
+  id: string;
+  title: string;
+  action?: ContextMenuAction | null;
+  disabled?: boolean;
+}
+
+// payload of the `windows://context-menu` event, rendered by the frontend
+export interface ContextMenuPayload {
+  // id to answer `select_context_menu_entry` with
+  id: number;
+  entries: ContextMenuEntry[];
+  // logical position in the window, at the cursor if null
+  position: WindowPosition | null;
+}
+
+// payload of the `windows://context-menu-selected` event
+export interface ContextMenuSelection {
+  entry_id: string;
+  action: ContextMenuAction | null;
+  // window opened by `ReopenClosedWindow`
+  reopened_label: string | null;
+}
+
+// payload of the `windows://before-navigate` event, state is already captured
+export interface BeforeNavigatePayload {
+  url: string;
+}
+
+// payload of the `windows://error` event, for plugin errors about the window
+export interface WindowErrorPayload {
+  label: string;
+  kind: ErrorKind;
+  message: string;
+}
+
+// payload of the `windows://singleton` event
+export interface SingletonPayload {
+  key: string;
+  payload: any;
+  // whether the window was already open
+  reopened: boolean;
+}
+
+// payload of events from `EmitCustomEvent` routing rules
+export interface RoutedFilesPayload {
+  files: string[];
+}
+
+// payload of the `windows://recents-changed` event
+export interface RecentsChangedPayload {
+  added: string[];
+  removed: string[];
+  // entries marked as missing
+  missing: string[];
+}
+
+// payload of the `windows://state-changed` event
+export interface StateChangedPayload {
+  // windows whose state was added, updated or removed
+  labels: string[];
 }